@@ -7,7 +7,7 @@ import { OrderStatus } from '../orders/enums/order-status.enum';
 import { InjectRepository } from '@nestjs/typeorm';
 import { Repository } from 'typeorm';
 
-export type WorkflowStep = 'allocate' | 'confirm_delivery' | 'settle' | 'rollback';
+export type WorkflowStep = 'allocate' | 'dispatch' | 'confirm_delivery' | 'settle' | 'rollback';
 
 @Injectable()
 export class WorkflowOrchestrationService {
@@ -59,7 +59,38 @@ export class WorkflowOrchestrationService {
   }
 
   /**
-   * Step 2 – Confirm delivery on-chain.
+   * Step 2 – Dispatch units on-chain; the rider signs to take custody.
+   * Validates order is CONFIRMED or DISPATCHED before submitting.
+   */
+  async dispatchUnits(params: {
+    requestId: string;
+    riderAddress: string;
+    eta: number;
+  }): Promise<{ jobId: string }> {
+    const order = await this.orderRepo.findOne({ where: { id: params.requestId } });
+    if (!order) throw new BadRequestException(`Order ${params.requestId} not found`);
+    if (
+      order.status !== OrderStatus.CONFIRMED &&
+      order.status !== OrderStatus.DISPATCHED
+    ) {
+      throw new BadRequestException(
+        `Order must be CONFIRMED or DISPATCHED to dispatch units, current: ${order.status}`,
+      );
+    }
+
+    const jobId = await this.soroban.submitTransaction({
+      contractMethod: 'dispatch',
+      args: [params.requestId, params.riderAddress, params.eta],
+      idempotencyKey: `dispatch:${params.requestId}`,
+      metadata: { contractId: this.coordinatorContract },
+    });
+
+    this.logger.log(`Dispatch queued for request ${params.requestId}, job ${jobId}`);
+    return { jobId };
+  }
+
+  /**
+   * Step 3 – Confirm delivery on-chain. Must be signed by the receiving hospital.
    * Validates order is DISPATCHED/IN_TRANSIT before submitting.
    */
   async confirmDelivery(params: {
@@ -89,13 +120,16 @@ export class WorkflowOrchestrationService {
   }
 
   /**
-   * Step 3 – Settle payment on-chain.
+   * Step 4 – Settle payment on-chain by releasing the escrow to the bank.
+   * When `acceptedUnitIds` is given only those units are paid for and the
+   * rest of the escrow is refunded to the hospital.
    * Validates order is DELIVERED before submitting.
    * The coordinator contract will reject if delivery is not confirmed on-chain.
    */
   async settlePayment(params: {
     requestId: string;
     callerAddress: string;
+    acceptedUnitIds?: string[];
   }): Promise<{ jobId: string }> {
     const order = await this.orderRepo.findOne({ where: { id: params.requestId } });
     if (!order) throw new BadRequestException(`Order ${params.requestId} not found`);
@@ -106,8 +140,10 @@ export class WorkflowOrchestrationService {
     }
 
     const jobId = await this.soroban.submitTransaction({
-      contractMethod: 'settle_payment',
-      args: [params.requestId, params.callerAddress],
+      contractMethod: params.acceptedUnitIds ? 'settle_partial' : 'settle_payment',
+      args: params.acceptedUnitIds
+        ? [params.requestId, params.callerAddress, params.acceptedUnitIds]
+        : [params.requestId, params.callerAddress],
       idempotencyKey: `settle:${params.requestId}`,
       metadata: { contractId: this.coordinatorContract },
     });
@@ -117,7 +153,8 @@ export class WorkflowOrchestrationService {
   }
 
   /**
-   * Rollback – admin-only. Releases units and refunds payment on-chain.
+   * Rollback – admin-only. Runs stage-aware compensations on-chain (release,
+   * recall or credit note) and is rejected if a mandatory step fails.
    */
   async rollback(params: {
     requestId: string;
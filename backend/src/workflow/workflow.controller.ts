@@ -31,6 +31,19 @@ export class WorkflowController {
     });
   }
 
+  @Post(':requestId/dispatch')
+  @RequirePermissions(Permission.ADMIN_ACCESS)
+  dispatch(
+    @Param('requestId', ParseUUIDPipe) requestId: string,
+    @Body() body: { riderAddress: string; eta: number },
+  ) {
+    return this.service.dispatchUnits({
+      requestId,
+      riderAddress: body.riderAddress,
+      eta: body.eta,
+    });
+  }
+
   @Post(':requestId/confirm-delivery')
   @RequirePermissions(Permission.ADMIN_ACCESS)
   confirmDelivery(
@@ -47,11 +60,13 @@ export class WorkflowController {
   @RequirePermissions(Permission.ADMIN_ACCESS)
   settle(
     @Param('requestId', ParseUUIDPipe) requestId: string,
+    @Body() body: { acceptedUnitIds?: string[] },
     @Request() req: { user: { stellarAddress?: string; sub: string } },
   ) {
     return this.service.settlePayment({
       requestId,
       callerAddress: req.user.stellarAddress ?? req.user.sub,
+      acceptedUnitIds: body?.acceptedUnitIds,
     });
   }
 
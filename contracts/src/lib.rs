@@ -1,7 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, vec, Address, Bytes, Env,
-    Map, String, Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, vec, Address, Bytes, BytesN,
+    Env, Map, String, Symbol, Vec,
 };
 
 pub mod constants;
@@ -25,6 +25,12 @@ mod test_storage_layout;
 /// marker as legacy and must not silently decode future version markers.
 pub const EVENT_SCHEMA_VERSION: u32 = 1;
 
+/// Storage schema version written by this build.
+///
+/// Stored under `SCHEMA` at initialization. After `upgrade` installs a build
+/// with a higher value, the admin runs `migrate` to bring storage up to it.
+pub const STORAGE_SCHEMA_VERSION: u32 = 1;
+
 /// Error types for blood registration and transfer
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -72,6 +78,8 @@ pub enum Error {
     InvalidMultiSigConfig = 30,
     DuplicateApproval = 31,
     EscrowNotReleasable = 32,
+    /// `migrate` was called with a version other than the stored schema version
+    SchemaMismatch = 33,
 }
 
 // Alias for issue/docs terminology.
@@ -496,6 +504,7 @@ const PAYMENT_STATS_KEY: &str = "PAY_STATS";
 const MULTISIG_CONFIG_KEY: &str = "MSIG_CFG";
 const PENDING_APPROVALS_KEY: &str = "PEND_APR";
 const ESCROW_ACCOUNTS_KEY: &str = "ESC_ACCS";
+const SCHEMA_KEY: &str = "SCHEMA";
 
 const _: () = assert!(BLOOD_UNITS_KEY.len() <= 9);
 const _: () = assert!(NEXT_ID_KEY.len() <= 9);
@@ -518,6 +527,7 @@ const _: () = assert!(PAYMENT_STATS_KEY.len() <= 9);
 const _: () = assert!(MULTISIG_CONFIG_KEY.len() <= 9);
 const _: () = assert!(PENDING_APPROVALS_KEY.len() <= 9);
 const _: () = assert!(ESCROW_ACCOUNTS_KEY.len() <= 9);
+const _: () = assert!(SCHEMA_KEY.len() <= 9);
 
 /// Storage keys (single source of truth)
 pub(crate) const BLOOD_UNITS: Symbol = symbol_short!("UNITS");
@@ -541,6 +551,7 @@ pub(crate) const PAYMENT_STATS: Symbol = symbol_short!("PAY_STATS");
 pub(crate) const MULTISIG_CONFIG: Symbol = symbol_short!("MSIG_CFG");
 pub(crate) const PENDING_APPROVALS: Symbol = symbol_short!("PEND_APR");
 pub(crate) const ESCROW_ACCOUNTS: Symbol = symbol_short!("ESC_ACCS");
+pub(crate) const SCHEMA: Symbol = symbol_short!("SCHEMA");
 /// Storage key enumeration for composite keys
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -616,6 +627,9 @@ impl HealthChainContract {
     pub fn initialize(env: Env, admin: Address) -> Symbol {
         admin.require_auth();
         env.storage().instance().set(&ADMIN, &admin);
+        env.storage()
+            .instance()
+            .set(&SCHEMA, &STORAGE_SCHEMA_VERSION);
         symbol_short!("init")
     }
 
@@ -624,6 +638,61 @@ impl HealthChainContract {
         1
     }
 
+    /// Replace the contract code with a previously uploaded WASM (admin only)
+    /// Storage is kept; call `migrate` afterwards if the new build raises
+    /// `STORAGE_SCHEMA_VERSION`.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN)
+            .ok_or(Error::Unauthorized)?;
+        if admin != stored {
+            return Err(Error::Unauthorized);
+        }
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("upgraded"), symbol_short!("v1")),
+            new_wasm_hash,
+        );
+        Ok(())
+    }
+
+    /// Storage schema version in effect; deployments that predate it report 1
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage().instance().get(&SCHEMA).unwrap_or(1)
+    }
+
+    /// Migrate storage out of `from_version` (admin only)
+    /// Returns `true` once storage matches `STORAGE_SCHEMA_VERSION`; later calls
+    /// are no-ops. Version 1 is the current layout, so no steps exist yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN)
+            .ok_or(Error::Unauthorized)?;
+        if admin != stored {
+            return Err(Error::Unauthorized);
+        }
+
+        let current = Self::schema_version(env.clone());
+        if current >= STORAGE_SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(Error::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&SCHEMA, &STORAGE_SCHEMA_VERSION);
+        Ok(true)
+    }
+
     /// Get contract metadata
     pub fn get_metadata(env: Env) -> Map<Symbol, String> {
         let mut metadata = Map::new(&env);
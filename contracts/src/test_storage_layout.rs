@@ -5,14 +5,14 @@
 use soroban_sdk::{
     symbol_short,
     testutils::{Address as _, Ledger},
-    Address, Env, Map, Symbol,
+    Address, Bytes, Env, Map, Symbol,
 };
 
 use crate::{
     BloodComponent, BloodStatus, BloodType, BloodUnit, HealthChainContract,
     HealthChainContractClient, ADMIN, BLOOD_BANKS, BLOOD_UNITS, CUSTODY_EVENTS, DISPUTES, HISTORY,
     HOSPITALS, NEXT_DISPUTE_ID, NEXT_ID, NEXT_PAYMENT_ID, NEXT_REQUEST_ID, PAYMENTS, REQUESTS,
-    REQUEST_KEYS,
+    REQUEST_KEYS, SCHEMA, STORAGE_SCHEMA_VERSION,
 };
 
 #[test]
@@ -188,6 +188,78 @@ fn test_initialize_creates_admin_in_instance_storage() {
     });
 }
 
+#[test]
+fn test_initialize_writes_schema_version_to_instance_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(HealthChainContract, ());
+    let client = HealthChainContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    env.as_contract(&contract_id, || {
+        let version: u32 = env
+            .storage()
+            .instance()
+            .get(&SCHEMA)
+            .expect("SCHEMA should exist in instance storage");
+        assert_eq!(version, STORAGE_SCHEMA_VERSION);
+    });
+    assert_eq!(client.schema_version(), STORAGE_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_upgrade_preserves_blood_units() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(HealthChainContract, ());
+    let client = HealthChainContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let bank = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_blood_bank(&bank);
+    let unit_id = client.register_blood(
+        &bank,
+        &BloodType::ONegative,
+        &BloodComponent::WholeBlood,
+        &450,
+        &(env.ledger().timestamp() + 86400 * 30),
+        &None,
+    );
+
+    // Minimal module standing in for the new build. The native contract is
+    // re-registered at the same address afterwards so the upgraded instance
+    // runs this crate's code against the storage left behind.
+    let wasm: [u8; 40] = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic + version
+        0x00, 30, 17, b'c', b'o', b'n', b't', b'r', b'a', b'c', b't', b'e', b'n', b'v', b'm', b'e',
+        b't', b'a', b'v', b'0', // custom section "contractenvmetav0"
+        0, 0, 0, 0, 0, 0, 0, 22, 0, 0, 0, 0, // interface version: protocol 22
+    ];
+    let hash = env
+        .deployer()
+        .upload_contract_wasm(Bytes::from_slice(&env, &wasm));
+    client.upgrade(&admin, &hash);
+    env.register_at(&contract_id, HealthChainContract, ());
+
+    assert!(client.migrate(&admin, &1));
+    env.as_contract(&contract_id, || {
+        let units: Map<u64, BloodUnit> = env
+            .storage()
+            .persistent()
+            .get(&BLOOD_UNITS)
+            .expect("BLOOD_UNITS should survive the upgrade");
+        let unit = units.get(unit_id).expect("unit should survive the upgrade");
+        assert_eq!(unit.blood_type, BloodType::ONegative);
+        assert_eq!(unit.status, BloodStatus::Available);
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        assert_eq!(stored_admin, admin);
+    });
+}
+
 #[test]
 fn test_update_status_modifies_existing_entry_no_new_key() {
     let env = Env::default();
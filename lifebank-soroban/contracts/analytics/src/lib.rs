@@ -9,7 +9,8 @@ mod test;
 pub use error::AnalyticsError;
 pub use types::{AnalyticsConfig, DataKey, MetricsSnapshot, PeriodType, ReportingPeriod};
 
-use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+use lifebank_types::registry::resolve_service;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env};
 
 // ── Constants ─────────────────────────────────────────────────────────────────
 
@@ -17,6 +18,10 @@ const DAILY_SECS: u64 = 86_400;
 const WEEKLY_SECS: u64 = 604_800;
 const MONTHLY_SECS: u64 = 2_592_000; // 30 days
 
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
+
 // ── Storage helpers ───────────────────────────────────────────────────────────
 
 fn require_initialized(env: &Env) -> Result<AnalyticsConfig, AnalyticsError> {
@@ -130,9 +135,64 @@ impl AnalyticsContract {
             admin,
         );
 
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
         Ok(())
     }
 
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), AnalyticsError> {
+        admin.require_auth();
+        if admin != require_initialized(&env)?.admin {
+            return Err(AnalyticsError::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("anlytcs"), symbol_short!("upgraded")),
+            new_wasm_hash,
+        );
+        Ok(())
+    }
+
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate snapshots and counters out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, AnalyticsError> {
+        admin.require_auth();
+        if admin != require_initialized(&env)?.admin {
+            return Err(AnalyticsError::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(AnalyticsError::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
     // ── Configuration ─────────────────────────────────────────────────────────
 
     /// Update the reporting period. Admin only.
@@ -259,9 +319,45 @@ impl AnalyticsContract {
         })
     }
 
-    /// Get the current contract configuration.
+    /// Resolve the linked domain contracts through a deployment registry.
+    /// Services it does not list keep their initialized addresses. Admin only.
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), AnalyticsError> {
+        require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        Ok(())
+    }
+
+    /// Get the current contract configuration, with the linked contracts
+    /// resolved through the registry when one is set.
     pub fn get_config(env: Env) -> Result<AnalyticsConfig, AnalyticsError> {
-        require_initialized(&env)
+        let mut cfg = require_initialized(&env)?;
+        let registry: Option<Address> = env.storage().instance().get(&DataKey::Registry);
+        let resolve = |name: &str, fallback: Address| {
+            resolve_service(&env, registry.clone(), name).unwrap_or(fallback)
+        };
+        cfg.inventory_contract = resolve("inventory", cfg.inventory_contract);
+        cfg.requests_contract = resolve("requests", cfg.requests_contract);
+        cfg.payments_contract = resolve("payments", cfg.payments_contract);
+        cfg.reputation_contract = resolve("reputation", cfg.reputation_contract);
+        Ok(cfg)
+    }
+
+    /// Link the workflow coordinator. Admin only.
+    pub fn set_coordinator_contract(env: Env, coordinator: Address) -> Result<(), AnalyticsError> {
+        require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::CoordinatorContract, &coordinator);
+        Ok(())
+    }
+
+    /// The registry's `coordinator` entry when a registry is set, otherwise
+    /// the linked coordinator, if any.
+    pub fn get_coordinator_contract(env: Env) -> Result<Option<Address>, AnalyticsError> {
+        require_initialized(&env)?;
+        let registry = env.storage().instance().get(&DataKey::Registry);
+        Ok(resolve_service(&env, registry, "coordinator")
+            .or_else(|| env.storage().instance().get(&DataKey::CoordinatorContract)))
     }
 
     pub fn is_initialized(env: Env) -> bool {
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use lifebank_types::testutils::upload_placeholder_wasm;
+use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
 use super::{AnalyticsContract, AnalyticsContractClient, AnalyticsError, PeriodType};
 
@@ -165,3 +166,66 @@ fn test_record_donation_fails_when_not_initialized() {
     let result = client.try_record_donation();
     assert_eq!(result, Err(Ok(AnalyticsError::NotInitialized)));
 }
+
+// ── Deployment registry ───────────────────────────────────────────────────────
+
+#[test]
+fn test_config_resolves_peers_through_registry() {
+    let (env, admin, client) = setup();
+    let before = client.get_config();
+
+    let registry_id = env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+    let payments = Address::generate(&env);
+    registry.register_service(&Symbol::new(&env, "payments"), &payments);
+    client.set_registry(&registry_id);
+
+    let cfg = client.get_config();
+    assert_eq!(cfg.payments_contract, payments);
+    assert_eq!(cfg.inventory_contract, before.inventory_contract);
+    assert_eq!(cfg.requests_contract, before.requests_contract);
+}
+
+#[test]
+fn test_coordinator_resolves_through_registry() {
+    let (env, admin, client) = setup();
+    assert_eq!(client.get_coordinator_contract(), None);
+
+    let linked = Address::generate(&env);
+    client.set_coordinator_contract(&linked);
+    assert_eq!(client.get_coordinator_contract(), Some(linked));
+
+    let registry_id = env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+    let rotated = Address::generate(&env);
+    registry.register_service(&Symbol::new(&env, "coordinator"), &rotated);
+    client.set_registry(&registry_id);
+    assert_eq!(client.get_coordinator_contract(), Some(rotated));
+}
+
+// ── Upgrades ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_upgrade_keeps_config_and_migrate_is_admin_only() {
+    let (env, admin, client) = setup();
+    client.set_reporting_period(&PeriodType::Weekly);
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(AnalyticsError::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    env.register_at(&client.address, AnalyticsContract, ());
+
+    assert_eq!(client.get_config().reporting_period.duration_secs, 604_800);
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(AnalyticsError::Unauthorized))
+    );
+    assert!(client.migrate(&admin, &1));
+}
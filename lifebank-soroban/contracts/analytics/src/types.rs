@@ -62,4 +62,10 @@ pub enum DataKey {
     TotalDeliveries,
     TotalPaymentsReleased,
     TotalVolume,
+    /// Deployment registry overriding the peer addresses in the config.
+    Registry,
+    /// Workflow coordinator, linked after initialization — instance storage.
+    CoordinatorContract,
+    /// Storage schema version — instance storage.
+    SchemaVersion,
 }
@@ -13,6 +13,12 @@ pub enum CoordinatorError {
     WorkflowAlreadyStarted = 811,
     InvalidWorkflowState = 812,
     CannotRollbackSettled = 813,
+    InvalidEta = 814,
+    InvalidSettlementUnits = 815,
+    InvalidLegs = 816,
+    LegNotFound = 817,
+    UnitNotInWorkflow = 818,
+    UnitAlreadyDelivered = 819,
 
     // Cross-contract pre-condition failures
     RequestNotFound = 820,
@@ -22,12 +28,20 @@ pub enum CoordinatorError {
     PaymentNotFound = 824,
     InvalidPaymentState = 825,
     DeliveryNotConfirmed = 826,
+    DeliveryProofMissing = 827,
+    TemperatureNonCompliant = 828,
+    PaymentMismatch = 829,
 
     // Cross-contract call failures
     InventoryUpdateFailed = 830,
     PaymentUpdateFailed = 831,
     PaymentFlagFailed = 832,
+    RequestUpdateFailed = 833,
+    CompensationFailed = 834,
 
     // Circuit breaker
     ContractPaused = 840,
+
+    // Upgrades
+    SchemaMismatch = 850,
 }
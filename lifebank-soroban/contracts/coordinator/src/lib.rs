@@ -3,9 +3,22 @@
 /// Cross-contract coordinator for the HealthDonor workflow.
 ///
 /// Canonical workflow sequence enforced here:
-///   1. allocate_units  – Request must be Pending; reserves inventory units
-///   2. confirm_delivery – Workflow must be Allocated; marks units Delivered
-///   3. settle_payment   – Workflow must be Delivered; releases escrowed payment
+///   1. allocate_units  – Request must be Pending; reserves inventory units and
+///      moves the request to Approved with its assigned units
+///   2. dispatch         – Workflow must be Allocated; the rider takes custody and
+///      units move to InTransit
+///   3. confirm_delivery – Workflow must be Dispatched; the receiving hospital marks
+///      units Delivered and the request Fulfilled
+///   4. settle_payment   – Workflow must be Delivered; releases the escrow to the bank
+///
+/// `settle_partial` is the step-4 variant that pays only for the units accepted.
+/// `enforce_slas` rolls back stale allocations and flags late deliveries.
+/// `flag_temperature_breach` compromises a unit, marks its leg and can
+/// re-match a replacement through the matching contract.
+/// `rollback` runs stage-aware compensations and cancels the linked request.
+/// The coordinator must be registered via `RequestContract::set_coordinator`
+/// for the request updates to succeed, and via
+/// `PaymentContract::set_coordinator` to settle escrow.
 ///
 /// Any step that finds the prerequisite state missing returns an error and makes
 /// no state changes, providing safe rollback semantics within a single transaction.
@@ -16,152 +29,332 @@ mod types;
 mod test;
 
 pub use error::CoordinatorError;
-pub use types::{DataKey, ExcursionSummary, WorkflowRecord, WorkflowStatus};
+pub use lifebank_types::{
+    BloodComponent, BloodRequest, BloodStatus, BloodType, BloodUnit, DeliveryProof, DisputeReason,
+    FeeBreakdown, Payment, PaymentStatus, RequestStatus, Urgency,
+};
+pub use types::{
+    CompensationEntry, CompensationStep, DataKey, DeliveryChecks, ExcursionSummary, LegAllocation,
+    SlaConfig, WorkflowFilter, WorkflowIndex, WorkflowLeg, WorkflowPage, WorkflowRecord,
+    WorkflowStatus, WorkflowTimeline,
+};
+
+use lifebank_types::clients::{
+    DeliveryContractClient, IdentityContractClient, InventoryContractClient,
+    MatchingContractClient, PaymentContractClient, ReputationContractClient, RequestContractClient,
+};
+use lifebank_types::registry::resolve_service;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, String, Vec};
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, String, Vec};
+// ── Storage helpers ────────────────────────────────────────────────────────────
 
-// ── Minimal interface types mirroring the domain contracts ────────────────────
-// These allow the coordinator to inspect cross-contract return values without
-// importing compiled WASMs. The domain contracts must keep these in sync.
+/// Upper bound on records returned by `get_stuck_workflows`.
+const MAX_STUCK_RESULTS: u32 = 50;
 
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum RequestStatus {
-    Pending,
-    Approved,
-    Fulfilled,
-    Cancelled,
-}
+/// Request IDs held by one page of a workflow index.
+const INDEX_PAGE_SIZE: u32 = 50;
 
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct BloodRequest {
-    pub id: u64,
-    pub status: RequestStatus,
-}
+/// Upper bound on index entries `list_workflows` examines per call.
+const MAX_LIST_SCAN: u32 = 200;
 
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum BloodStatus {
-    Available,
-    Reserved,
-    InTransit,
-    Delivered,
-    Expired,
-    Compromised,
-    Disposed,
+/// Workflows re-indexed by each `migrate` call.
+const MIGRATION_BATCH_SIZE: u32 = 50;
+
+fn get_admin(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Admin).unwrap()
 }
 
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct BloodUnit {
-    pub id: u64,
-    pub status: BloodStatus,
+/// Address of the peer contract stored under `key`, looked up in the
+/// registry first.
+fn peer(env: &Env, key: &DataKey) -> Option<Address> {
+    let service = match key {
+        DataKey::RequestContract => "requests",
+        DataKey::InventoryContract => "inventory",
+        DataKey::PaymentContract => "payments",
+        DataKey::ReputationContract => "reputation",
+        DataKey::TemperatureContract => "temperature",
+        DataKey::MatchingContract => "matching",
+        _ => return env.storage().instance().get(key),
+    };
+    resolve_service(
+        env,
+        env.storage().instance().get(&DataKey::Registry),
+        service,
+    )
+    .or_else(|| env.storage().instance().get(key))
 }
 
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum PaymentStatus {
-    Pending,
-    Locked,
-    Released,
-    Refunded,
-    Disputed,
-    Cancelled,
+fn load_workflow(env: &Env, request_id: u64) -> Option<WorkflowRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Workflow(request_id))
 }
 
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct Payment {
-    pub id: u64,
-    pub request_id: u64,
-    pub status: PaymentStatus,
+fn save_workflow(env: &Env, wf: &WorkflowRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Workflow(wf.request_id), wf);
 }
 
-// ── Cross-contract client traits ──────────────────────────────────────────────
+// Workflow indexes are stored as pages of request IDs:
+//   DataKey::IndexPages(index)    → number of pages (u32)
+//   DataKey::IndexPage(index, p)  → Vec<u64> of at most INDEX_PAGE_SIZE IDs
+//   DataKey::IndexSlot(index, id) → page holding `id`
+// Removing an ID moves the index's last ID into its place, so every page but
+// the last is full and a position in the index maps straight to its page.
+
+fn index_pages(env: &Env, index: &WorkflowIndex) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IndexPages(index.clone()))
+        .unwrap_or(0)
+}
 
-mod request_client {
-    use super::BloodRequest;
-    use soroban_sdk::{contractclient, Address, Env};
+fn index_page(env: &Env, index: &WorkflowIndex, page: u32) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IndexPage(index.clone(), page))
+        .unwrap_or(Vec::new(env))
+}
 
-    #[contractclient(name = "RequestContractClient")]
-    pub trait RequestContractInterface {
-        fn get_request(env: Env, request_id: u64) -> BloodRequest;
+/// Store the index's last page, dropping it once empty.
+fn set_last_index_page(env: &Env, index: &WorkflowIndex, page: u32, ids: &Vec<u64>) {
+    let key = DataKey::IndexPage(index.clone(), page);
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+        env.storage()
+            .persistent()
+            .set(&DataKey::IndexPages(index.clone()), &page);
+    } else {
+        env.storage().persistent().set(&key, ids);
     }
 }
 
-mod inventory_client {
-    use super::{BloodStatus, BloodUnit};
-    use soroban_sdk::{contractclient, Address, Env, String};
-
-    #[contractclient(name = "InventoryContractClient")]
-    pub trait InventoryContractInterface {
-        fn get_blood_unit(env: Env, blood_unit_id: u64) -> BloodUnit;
-        fn update_status(
-            env: Env,
-            unit_id: u64,
-            new_status: BloodStatus,
-            authorized_by: Address,
-            reason: Option<String>,
-        ) -> BloodUnit;
-        fn mark_delivered(
-            env: Env,
-            unit_id: u64,
-            authorized_by: Address,
-            delivery_location: String,
-        ) -> BloodUnit;
-        fn get_admin(env: Env) -> Address;
+fn index_len(env: &Env, index: &WorkflowIndex) -> u32 {
+    let pages = index_pages(env, index);
+    if pages == 0 {
+        return 0;
     }
+    (pages - 1) * INDEX_PAGE_SIZE + index_page(env, index, pages - 1).len()
 }
 
-mod payment_client {
-    use super::{Payment, PaymentStatus};
-    use soroban_sdk::{contractclient, contracttype, Env, String};
+/// Append `request_id` to `index` unless it is already there.
+fn index_add(env: &Env, index: &WorkflowIndex, request_id: u64) {
+    let slot = DataKey::IndexSlot(index.clone(), request_id);
+    if env.storage().persistent().has(&slot) {
+        return;
+    }
+    let pages = index_pages(env, index);
+    let mut page = pages.saturating_sub(1);
+    let mut ids = index_page(env, index, page);
+    if pages == 0 || ids.len() >= INDEX_PAGE_SIZE {
+        page = pages;
+        ids = Vec::new(env);
+        env.storage()
+            .persistent()
+            .set(&DataKey::IndexPages(index.clone()), &(pages + 1));
+    }
+    ids.push_back(request_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::IndexPage(index.clone(), page), &ids);
+    env.storage().persistent().set(&slot, &page);
+}
 
-    #[contracttype]
-    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-    pub enum DisputeReason {
-        FailedDelivery,
-        TemperatureExcursion,
-        PaymentContested,
-        WrongItem,
-        DamagedGoods,
-        LateDelivery,
-        Other,
+fn index_remove(env: &Env, index: &WorkflowIndex, request_id: u64) {
+    let slot = DataKey::IndexSlot(index.clone(), request_id);
+    let Some(page) = env.storage().persistent().get::<_, u32>(&slot) else {
+        return;
+    };
+    env.storage().persistent().remove(&slot);
+
+    let last = index_pages(env, index) - 1;
+    let mut ids = index_page(env, index, page);
+    let pos = ids.first_index_of(request_id).unwrap();
+    if page == last {
+        ids.remove(pos);
+        set_last_index_page(env, index, last, &ids);
+        return;
     }
+    let mut tail = index_page(env, index, last);
+    let moved = tail.pop_back().unwrap();
+    ids.set(pos, moved);
+    env.storage()
+        .persistent()
+        .set(&DataKey::IndexPage(index.clone(), page), &ids);
+    env.storage()
+        .persistent()
+        .set(&DataKey::IndexSlot(index.clone(), moved), &page);
+    set_last_index_page(env, index, last, &tail);
+}
 
-    #[contractclient(name = "PaymentContractClient")]
-    pub trait PaymentContractInterface {
-        fn get_payment(env: Env, payment_id: u64) -> Payment;
-        fn update_status(env: Env, payment_id: u64, status: PaymentStatus);
-        fn record_dispute(env: Env, payment_id: u64, reason: DisputeReason, case_id: String);
+/// Visit the IDs of `index` from position `start`, at most `max` of them,
+/// until `visit` returns false. Returns the position after the last ID
+/// visited, or `None` once the index is exhausted.
+fn index_scan(
+    env: &Env,
+    index: &WorkflowIndex,
+    start: u32,
+    max: u32,
+    mut visit: impl FnMut(u64) -> bool,
+) -> Option<u32> {
+    let len = index_len(env, index);
+    let mut pos = start;
+    let mut ids: Vec<u64> = Vec::new(env);
+    while pos < len && pos - start < max {
+        if pos == start || pos.is_multiple_of(INDEX_PAGE_SIZE) {
+            ids = index_page(env, index, pos / INDEX_PAGE_SIZE);
+        }
+        let id = ids.get(pos % INDEX_PAGE_SIZE).unwrap();
+        pos += 1;
+        if !visit(id) {
+            break;
+        }
     }
+    (pos < len).then_some(pos)
 }
 
-use inventory_client::InventoryContractClient;
-use payment_client::PaymentContractClient;
-use request_client::RequestContractClient;
+fn load_timeline(env: &Env, request_id: u64) -> Option<WorkflowTimeline> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Timeline(request_id))
+}
 
-// ── Storage helpers ────────────────────────────────────────────────────────────
+/// Add a workflow to every index that covers it.
+fn index_workflow(env: &Env, wf: &WorkflowRecord) {
+    index_add(env, &WorkflowIndex::All, wf.request_id);
+    index_add(env, &WorkflowIndex::Status(wf.status), wf.request_id);
+    index_add(
+        env,
+        &WorkflowIndex::Hospital(wf.hospital.clone()),
+        wf.request_id,
+    );
+    for bank in wf.banks.iter() {
+        index_add(env, &WorkflowIndex::Bank(bank), wf.request_id);
+    }
+}
 
-fn get_admin(env: &Env) -> Address {
-    env.storage().instance().get(&DataKey::Admin).unwrap()
+/// Index and timestamp a newly allocated workflow.
+fn record_allocation(env: &Env, wf: &WorkflowRecord, actor: &Address) {
+    index_workflow(env, wf);
+    env.storage().persistent().set(
+        &DataKey::Timeline(wf.request_id),
+        &WorkflowTimeline {
+            allocated_at: env.ledger().timestamp(),
+            allocated_by: actor.clone(),
+            dispatched_at: None,
+            dispatched_by: None,
+            delivered_at: None,
+            delivered_by: None,
+            settled_at: None,
+            settled_by: None,
+            rolled_back_at: None,
+            rolled_back_by: None,
+        },
+    );
 }
 
-fn load_workflow(env: &Env, request_id: u64) -> Option<WorkflowRecord> {
+/// Move a workflow to `new_status`, keeping the status index and timeline in
+/// step, and persist it.
+fn transition(env: &Env, wf: &mut WorkflowRecord, new_status: WorkflowStatus, actor: &Address) {
+    index_remove(env, &WorkflowIndex::Status(wf.status), wf.request_id);
+    index_add(env, &WorkflowIndex::Status(new_status), wf.request_id);
+    wf.status = new_status;
+    save_workflow(env, wf);
+
+    let mut timeline = load_timeline(env, wf.request_id).unwrap();
+    let now = Some(env.ledger().timestamp());
+    let actor = Some(actor.clone());
+    match new_status {
+        WorkflowStatus::Dispatched => {
+            timeline.dispatched_at = now;
+            timeline.dispatched_by = actor;
+        }
+        WorkflowStatus::Delivered => {
+            timeline.delivered_at = now;
+            timeline.delivered_by = actor;
+        }
+        WorkflowStatus::Settled => {
+            timeline.settled_at = now;
+            timeline.settled_by = actor;
+        }
+        WorkflowStatus::RolledBack => {
+            timeline.rolled_back_at = now;
+            timeline.rolled_back_by = actor;
+        }
+        WorkflowStatus::Pending | WorkflowStatus::Allocated => {}
+    }
     env.storage()
         .persistent()
-        .get(&DataKey::Workflow(request_id))
+        .set(&DataKey::Timeline(wf.request_id), &timeline);
 }
 
-fn save_workflow(env: &Env, wf: &WorkflowRecord) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::Workflow(wf.request_id), wf);
+/// Order of the forward workflow stages, used to aggregate leg statuses.
+fn stage_rank(status: WorkflowStatus) -> u32 {
+    match status {
+        WorkflowStatus::Pending => 0,
+        WorkflowStatus::Allocated => 1,
+        WorkflowStatus::Dispatched => 2,
+        WorkflowStatus::Delivered => 3,
+        WorkflowStatus::Settled => 4,
+        WorkflowStatus::RolledBack => 5,
+    }
+}
+
+/// A workflow is as far along as its least advanced leg that was not rolled
+/// back, and RolledBack once every leg has been.
+fn aggregate_status(wf: &WorkflowRecord) -> WorkflowStatus {
+    let mut status = WorkflowStatus::RolledBack;
+    for leg in wf.legs.iter() {
+        if leg.status != WorkflowStatus::RolledBack && stage_rank(leg.status) < stage_rank(status) {
+            status = leg.status;
+        }
+    }
+    status
+}
+
+/// Indexes of the legs a step acts on: the given leg, which must be in
+/// `required`, or every leg in `required` (at least one).
+fn target_legs(
+    env: &Env,
+    wf: &WorkflowRecord,
+    leg: Option<u32>,
+    required: WorkflowStatus,
+) -> Result<Vec<u32>, CoordinatorError> {
+    let mut targets: Vec<u32> = Vec::new(env);
+    match leg {
+        Some(idx) => {
+            if wf
+                .legs
+                .get(idx)
+                .ok_or(CoordinatorError::LegNotFound)?
+                .status
+                != required
+            {
+                return Err(CoordinatorError::InvalidWorkflowState);
+            }
+            targets.push_back(idx);
+        }
+        None => {
+            for (idx, leg) in wf.legs.iter().enumerate() {
+                if leg.status == required {
+                    targets.push_back(idx as u32);
+                }
+            }
+            if targets.is_empty() {
+                return Err(CoordinatorError::InvalidWorkflowState);
+            }
+        }
+    }
+    Ok(targets)
 }
 
 // ── Contract ───────────────────────────────────────────────────────────────────
 
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 2;
+
 #[contract]
 pub struct CoordinatorContract;
 
@@ -196,9 +389,131 @@ impl CoordinatorContract {
             ),
             admin,
         );
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("coord"), symbol_short!("upgraded")),
+            new_wasm_hash,
+        );
         Ok(())
     }
 
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate workflows and legs out of `from_version`. Admin only.
+    ///
+    /// Steps:
+    /// - 1 -> 2: move the workflow indexes from single `Vec`s into pages
+    ///
+    /// Each call re-indexes at most `MIGRATION_BATCH_SIZE` workflows and saves
+    /// its position; call again with the same `from_version` until it returns
+    /// `true`. Later calls are no-ops.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(CoordinatorError::SchemaMismatch);
+        }
+
+        let finished = match current {
+            1 => Self::migrate_indexes(&env),
+            _ => return Err(CoordinatorError::SchemaMismatch),
+        };
+        if !finished {
+            return Ok(false);
+        }
+
+        env.storage().instance().remove(&DataKey::MigrationCursor);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &(current + 1));
+        Ok(current + 1 >= SCHEMA_VERSION)
+    }
+
+    /// Migration 1 -> 2: re-index the next batch of workflows listed in the
+    /// schema 1 `AllWorkflows` vector into paged indexes, dropping the old
+    /// vectors once every workflow has been visited. Returns `true` when done.
+    fn migrate_indexes(env: &Env) -> bool {
+        let legacy: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AllWorkflows)
+            .unwrap_or(Vec::new(env));
+        let start: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MigrationCursor)
+            .unwrap_or(0);
+        let end = legacy.len().min(start + MIGRATION_BATCH_SIZE);
+
+        for pos in start..end {
+            let Some(wf) = load_workflow(env, legacy.get(pos).unwrap()) else {
+                continue;
+            };
+            index_workflow(env, &wf);
+            env.storage()
+                .persistent()
+                .remove(&DataKey::HospitalIndex(wf.hospital.clone()));
+            for bank in wf.banks.iter() {
+                env.storage().persistent().remove(&DataKey::BankIndex(bank));
+            }
+        }
+        if end < legacy.len() {
+            env.storage()
+                .instance()
+                .set(&DataKey::MigrationCursor, &end);
+            return false;
+        }
+
+        env.storage().persistent().remove(&DataKey::AllWorkflows);
+        for status in [
+            WorkflowStatus::Pending,
+            WorkflowStatus::Allocated,
+            WorkflowStatus::Dispatched,
+            WorkflowStatus::Delivered,
+            WorkflowStatus::Settled,
+            WorkflowStatus::RolledBack,
+        ] {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::StatusIndex(status));
+        }
+        true
+    }
+
     /// Pause all state-mutating functions. Admin only.
     pub fn pause(env: Env, admin: Address) -> Result<(), CoordinatorError> {
         admin.require_auth();
@@ -249,13 +564,214 @@ impl CoordinatorContract {
         Ok(())
     }
 
-    /// Step 1 – Allocate inventory units to a pending request.
+    /// Configure the optional checks run by `confirm_delivery`. Admin only.
+    ///
+    /// - `proof_contract`: identity contract holding a verified `DeliveryProof`
+    ///   for the request.
+    /// - `compliance_contract`: delivery contract holding a compliant
+    ///   temperature attestation, keyed by request ID.
+    pub fn set_delivery_checks(
+        env: Env,
+        admin: Address,
+        checks: DeliveryChecks,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::DeliveryChecks, &checks);
+        Ok(())
+    }
+
+    pub fn get_delivery_checks(env: Env) -> DeliveryChecks {
+        env.storage()
+            .instance()
+            .get(&DataKey::DeliveryChecks)
+            .unwrap_or(DeliveryChecks {
+                proof_contract: None,
+                compliance_contract: None,
+            })
+    }
+
+    /// Set the SLA enforced for workflows of the given urgency. Admin only.
+    pub fn set_sla_config(
+        env: Env,
+        admin: Address,
+        urgency: Urgency,
+        config: SlaConfig,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SlaConfig(urgency), &config);
+        Ok(())
+    }
+
+    pub fn get_sla_config(env: Env, urgency: Urgency) -> Option<SlaConfig> {
+        env.storage().instance().get(&DataKey::SlaConfig(urgency))
+    }
+
+    /// Configure where SLA breaches are reported. Admin only.
+    ///
+    /// The coordinator must be the reputation contract's breach reporter.
+    /// Banks without a registered entity ID are not reported.
+    pub fn set_reputation_contract(
+        env: Env,
+        admin: Address,
+        reputation_contract: Address,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ReputationContract, &reputation_contract);
+        Ok(())
+    }
+
+    /// Map a bank address to its reputation entity ID. Admin only.
+    pub fn set_bank_entity(
+        env: Env,
+        admin: Address,
+        bank: Address,
+        entity_id: u64,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::BankEntity(bank), &entity_id);
+        Ok(())
+    }
+
+    /// Resolve peer contracts through a deployment registry. Admin only.
+    ///
+    /// Services missing from the registry fall back to the addresses set on
+    /// this contract.
+    pub fn set_registry(
+        env: Env,
+        admin: Address,
+        registry: Address,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        Ok(())
+    }
+
+    pub fn get_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Registry)
+    }
+
+    /// Set the temperature contract allowed to flag breaches. Admin only.
+    pub fn set_temperature_contract(
+        env: Env,
+        admin: Address,
+        temperature_contract: Address,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::TemperatureContract, &temperature_contract);
+        Ok(())
+    }
+
+    /// Allow or revoke an oracle reporting breaches directly. Admin only.
+    pub fn set_breach_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        allowed: bool,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        let key = DataKey::BreachOracle(oracle);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Set the matching contract used to replace breached units, or `None`
+    /// to turn automatic re-matching off. Admin only.
+    pub fn set_matching_contract(
+        env: Env,
+        admin: Address,
+        matching_contract: Option<Address>,
+    ) -> Result<(), CoordinatorError> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        if admin != get_admin(&env) {
+            return Err(CoordinatorError::Unauthorized);
+        }
+        match matching_contract {
+            Some(addr) => env
+                .storage()
+                .instance()
+                .set(&DataKey::MatchingContract, &addr),
+            None => env.storage().instance().remove(&DataKey::MatchingContract),
+        }
+        Ok(())
+    }
+
+    /// Step 1 – Allocate inventory units to a pending request from a single
+    /// bank. Use `allocate_legs` when several banks share the request.
     pub fn allocate_units(
         env: Env,
         request_id: u64,
         unit_ids: Vec<u64>,
         payment_id: u64,
         caller: Address,
+    ) -> Result<(), CoordinatorError> {
+        let legs = Vec::from_array(
+            &env,
+            [LegAllocation {
+                unit_ids,
+                payment_id,
+            }],
+        );
+        Self::allocate_legs(env, request_id, legs, caller)
+    }
+
+    /// Step 1 – Allocate a request split across banks. Each leg holds units
+    /// from one bank and its own escrow payment; no bank or payment may
+    /// appear in two legs.
+    ///
+    /// # Errors
+    /// - `InvalidLegs` - No legs, an empty leg, mixed banks within a leg, or a
+    ///   bank or payment repeated across legs
+    /// - `PaymentNotFound` - A leg's payment does not exist
+    /// - `PaymentMismatch` - A leg's payment is for another request, is not
+    ///   paid by the request's hospital or is not payable to the leg's bank
+    pub fn allocate_legs(
+        env: Env,
+        request_id: u64,
+        legs: Vec<LegAllocation>,
+        caller: Address,
     ) -> Result<(), CoordinatorError> {
         caller.require_auth();
         Self::require_initialized(&env)?;
@@ -266,13 +782,12 @@ impl CoordinatorContract {
                 return Err(CoordinatorError::WorkflowAlreadyStarted);
             }
         }
+        if legs.is_empty() {
+            return Err(CoordinatorError::InvalidLegs);
+        }
 
         // Verify request is Pending
-        let req_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::RequestContract)
-            .unwrap();
+        let req_addr: Address = peer(&env, &DataKey::RequestContract).unwrap();
         let req_client = RequestContractClient::new(&env, &req_addr);
         let request = req_client
             .try_get_request(&request_id)
@@ -284,31 +799,80 @@ impl CoordinatorContract {
         }
 
         // Reserve each inventory unit
-        let inv_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::InventoryContract)
-            .unwrap();
+        let inv_addr: Address = peer(&env, &DataKey::InventoryContract).unwrap();
         let inv_client = InventoryContractClient::new(&env, &inv_addr);
         let inv_admin = inv_client.get_admin();
+        let pay_addr: Address = peer(&env, &DataKey::PaymentContract).unwrap();
+        let pay_client = PaymentContractClient::new(&env, &pay_addr);
 
-        for i in 0..unit_ids.len() {
-            let uid = unit_ids.get(i).unwrap();
-            let unit = inv_client
-                .try_get_blood_unit(&uid)
-                .map_err(|_| CoordinatorError::UnitNotFound)?
-                .map_err(|_| CoordinatorError::UnitNotFound)?;
-
-            if unit.status != BloodStatus::Available {
-                return Err(CoordinatorError::UnitNotAvailable);
+        let mut unit_ids: Vec<u64> = Vec::new(&env);
+        let mut banks: Vec<Address> = Vec::new(&env);
+        let mut payments: Vec<u64> = Vec::new(&env);
+        let mut workflow_legs: Vec<WorkflowLeg> = Vec::new(&env);
+        for alloc in legs.iter() {
+            if alloc.unit_ids.is_empty() || payments.contains(alloc.payment_id) {
+                return Err(CoordinatorError::InvalidLegs);
+            }
+            payments.push_back(alloc.payment_id);
+
+            let mut leg_bank: Option<Address> = None;
+            for uid in alloc.unit_ids.iter() {
+                let unit = inv_client
+                    .try_get_blood_unit(&uid)
+                    .map_err(|_| CoordinatorError::UnitNotFound)?
+                    .map_err(|_| CoordinatorError::UnitNotFound)?;
+
+                if unit.status != BloodStatus::Available {
+                    return Err(CoordinatorError::UnitNotAvailable);
+                }
+                match &leg_bank {
+                    None if banks.contains(&unit.bank_id) => {
+                        return Err(CoordinatorError::InvalidLegs)
+                    }
+                    None => leg_bank = Some(unit.bank_id.clone()),
+                    Some(bank) if *bank != unit.bank_id => {
+                        return Err(CoordinatorError::InvalidLegs)
+                    }
+                    Some(_) => {}
+                }
+
+                inv_client
+                    .try_update_status(&uid, &BloodStatus::Reserved, &inv_admin, &None)
+                    .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
+                    .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
+                unit_ids.push_back(uid);
             }
 
-            inv_client
-                .try_update_status(&uid, &BloodStatus::Reserved, &inv_admin, &None)
-                .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
-                .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
+            let bank = leg_bank.unwrap();
+            // Each leg is paid for by the hospital to its own bank.
+            let payment = pay_client
+                .try_get_payment(&alloc.payment_id)
+                .map_err(|_| CoordinatorError::PaymentNotFound)?
+                .map_err(|_| CoordinatorError::PaymentNotFound)?;
+            if payment.request_id != request_id
+                || payment.payer != request.hospital_id
+                || payment.payee != bank
+            {
+                return Err(CoordinatorError::PaymentMismatch);
+            }
+            banks.push_back(bank.clone());
+            workflow_legs.push_back(WorkflowLeg {
+                bank,
+                unit_ids: alloc.unit_ids,
+                payment_id: alloc.payment_id,
+                status: WorkflowStatus::Allocated,
+                rider: None,
+                eta: None,
+                sla_breached: false,
+                breached_units: Vec::new(&env),
+            });
         }
 
+        req_client
+            .try_assign_units(&env.current_contract_address(), &request_id, &unit_ids)
+            .map_err(|_| CoordinatorError::RequestUpdateFailed)?
+            .map_err(|_| CoordinatorError::RequestUpdateFailed)?;
+
         env.events().publish(
             (
                 symbol_short!("coord"),
@@ -318,165 +882,782 @@ impl CoordinatorContract {
             (request_id, unit_ids.len()),
         );
 
-        save_workflow(
-            &env,
-            &WorkflowRecord {
-                request_id,
-                payment_id,
-                unit_ids,
-                status: WorkflowStatus::Allocated,
-                delivery_confirmed: false,
-            },
-        );
+        let wf = WorkflowRecord {
+            request_id,
+            unit_ids,
+            status: WorkflowStatus::Allocated,
+            delivery_confirmed: false,
+            hospital: request.hospital_id,
+            banks,
+            urgency: request.urgency,
+            required_by: request.required_by_timestamp,
+            legs: workflow_legs,
+        };
+        save_workflow(&env, &wf);
+        record_allocation(&env, &wf, &caller);
 
         Ok(())
     }
 
-    /// Step 2 – Confirm delivery: mark all reserved units as Delivered.
-    pub fn confirm_delivery(
+    /// Step 2 – Dispatch: the rider takes custody of every allocated leg and
+    /// its units move to InTransit.
+    ///
+    /// The handoff is signed by the rider and by the bank of each leg
+    /// dispatched.
+    pub fn dispatch(
         env: Env,
         request_id: u64,
-        caller: Address,
+        rider: Address,
+        eta: u64,
     ) -> Result<(), CoordinatorError> {
-        caller.require_auth();
-        Self::require_initialized(&env)?;
+        Self::dispatch_legs(&env, request_id, None, &rider, eta)
+    }
+
+    /// Step 2 for a single leg of a multi-bank workflow.
+    pub fn dispatch_leg(
+        env: Env,
+        request_id: u64,
+        leg: u32,
+        rider: Address,
+        eta: u64,
+    ) -> Result<(), CoordinatorError> {
+        Self::dispatch_legs(&env, request_id, Some(leg), &rider, eta)
+    }
+
+    /// Step 3 – Confirm delivery of every dispatched leg: its units are
+    /// marked Delivered. The request becomes Fulfilled once no leg is left
+    /// outstanding.
+    ///
+    /// Only the hospital that raised the request may confirm. When configured
+    /// via `set_delivery_checks`, a verified delivery proof and a compliant
+    /// temperature attestation must also exist for the request.
+    pub fn confirm_delivery(
+        env: Env,
+        request_id: u64,
+        caller: Address,
+    ) -> Result<(), CoordinatorError> {
+        Self::confirm_legs(&env, request_id, None, &caller)
+    }
+
+    /// Step 3 for a single leg of a multi-bank workflow.
+    pub fn confirm_leg_delivery(
+        env: Env,
+        request_id: u64,
+        leg: u32,
+        caller: Address,
+    ) -> Result<(), CoordinatorError> {
+        Self::confirm_legs(&env, request_id, Some(leg), &caller)
+    }
+
+    /// Step 4 – Settle payment. Blocked until every leg is delivered.
+    ///
+    /// Releases each leg's escrow in full to its bank through the payments
+    /// contract, which must list this contract as its coordinator.
+    pub fn settle_payment(
+        env: Env,
+        request_id: u64,
+        caller: Address,
+    ) -> Result<(), CoordinatorError> {
+        Self::settle(&env, request_id, None, &caller, None)?;
+        Ok(())
+    }
+
+    /// Step 4 for a single delivered leg, independent of the other legs.
+    pub fn settle_leg(
+        env: Env,
+        request_id: u64,
+        leg: u32,
+        caller: Address,
+    ) -> Result<(), CoordinatorError> {
+        Self::settle(&env, request_id, Some(leg), &caller, None)?;
+        Ok(())
+    }
+
+    /// Settle a delivered workflow where the hospital accepted only some of
+    /// the units. Only the request's hospital or the admin may call it. Each
+    /// leg's bank receives what is left of its escrow pro rata to the leg's
+    /// accepted units and the remainder is refunded to the payer; a leg with
+    /// no accepted units is refunded in full. Returns the total amount paid.
+    ///
+    /// # Errors
+    /// - `Unauthorized`           - Caller is neither the hospital nor the admin
+    /// - `InvalidSettlementUnits` - Empty, duplicated or foreign unit IDs
+    pub fn settle_partial(
+        env: Env,
+        request_id: u64,
+        caller: Address,
+        accepted_units: Vec<u64>,
+    ) -> Result<i128, CoordinatorError> {
+        Self::settle(&env, request_id, None, &caller, Some(accepted_units))
+    }
+
+    /// Rollback – admin only. Runs the compensations for each leg's stage:
+    ///
+    /// - `Allocated`  – reserved units are released back to Available.
+    /// - `Dispatched` – units have left the bank and cannot re-enter stock, so
+    ///   they are recalled as Compromised.
+    /// - `Delivered`  – units stay with the hospital; the locked payment is
+    ///   refunded as a credit note.
+    ///
+    /// In every stage a Locked payment is refunded and the request cancelled.
+    /// Each cross-contract step is recorded in the compensation log. If any
+    /// mandatory step fails the rollback is rejected with `CompensationFailed`
+    /// and nothing is changed. Rejected with `CannotRollbackSettled` once any
+    /// leg has settled; roll back the remaining legs individually instead.
+    pub fn rollback(env: Env, request_id: u64) -> Result<Vec<CompensationEntry>, CoordinatorError> {
+        Self::require_initialized(&env)?;
+        let admin = get_admin(&env);
+        admin.require_auth();
         Self::require_not_paused(&env)?;
+        Self::compensate(&env, request_id, None, &admin)
+    }
 
-        let mut wf = load_workflow(&env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+    /// Roll back one leg, leaving the other legs to complete. The request is
+    /// cancelled only when this was the last active leg. Admin only.
+    pub fn rollback_leg(
+        env: Env,
+        request_id: u64,
+        leg: u32,
+    ) -> Result<Vec<CompensationEntry>, CoordinatorError> {
+        Self::require_initialized(&env)?;
+        let admin = get_admin(&env);
+        admin.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::compensate(&env, request_id, Some(leg), &admin)
+    }
 
-        if wf.status != WorkflowStatus::Allocated {
-            return Err(CoordinatorError::InvalidWorkflowState);
+    /// Enforce leg SLAs. Permissionless; examines at most `max` workflows,
+    /// resuming where the previous sweep stopped, and returns the affected
+    /// request IDs.
+    ///
+    /// - An `Allocated` leg not dispatched within its urgency's
+    ///   `dispatch_within_secs`, or by the request deadline, is rolled back.
+    /// - A `Dispatched` leg still undelivered `delivery_grace_secs` past its
+    ///   ETA has its Locked payment disputed as `LateDelivery`. It is flagged
+    ///   once and otherwise left to continue.
+    ///
+    /// Each breach is reported to reputation for the leg's bank. A leg that
+    /// cannot be handled, such as one holding a unit inventory will not
+    /// release, is skipped with an `sla_skip` event and left as it was for
+    /// the admin; the sweep carries on with the next leg.
+    pub fn enforce_slas(env: Env, max: u32) -> Result<Vec<u64>, CoordinatorError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+
+        let now = env.ledger().timestamp();
+        let this = env.current_contract_address();
+        let candidates = Self::next_sla_candidates(&env, max);
+
+        let mut breached: Vec<u64> = Vec::new(&env);
+        for request_id in candidates.iter() {
+            let (Some(wf), Some(timeline)) = (
+                load_workflow(&env, request_id),
+                load_timeline(&env, request_id),
+            ) else {
+                continue;
+            };
+            let sla = Self::get_sla_config(env.clone(), wf.urgency);
+            let dispatch_deadline = match &sla {
+                Some(sla) => wf
+                    .required_by
+                    .min(timeline.allocated_at + sla.dispatch_within_secs),
+                None => wf.required_by,
+            };
+            let grace = sla.map_or(0, |sla| sla.delivery_grace_secs);
+
+            let mut hit = false;
+            for idx in 0..wf.legs.len() {
+                // Rolling back a leg rewrites the record; always act on the latest.
+                let mut wf = load_workflow(&env, request_id).unwrap();
+                let mut leg = wf.legs.get(idx).unwrap();
+                // One stuck leg must not stall the sweep for every workflow
+                // behind it.
+                let handled = match leg.status {
+                    WorkflowStatus::Allocated if now > dispatch_deadline => {
+                        Self::check_leg_rollback(&env, &wf, idx)
+                            .and_then(|_| Self::compensate(&env, request_id, Some(idx), &this))
+                            .map(|_| ())
+                    }
+                    WorkflowStatus::Dispatched
+                        if !leg.sla_breached
+                            && leg.eta.is_some_and(|eta| now > eta.saturating_add(grace)) =>
+                    {
+                        Self::dispute_late_delivery(&env, &leg).map(|_| {
+                            leg.sla_breached = true;
+                            wf.legs.set(idx, leg.clone());
+                            save_workflow(&env, &wf);
+                        })
+                    }
+                    _ => continue,
+                };
+                if let Err(err) = handled {
+                    env.events().publish(
+                        (
+                            symbol_short!("coord"),
+                            symbol_short!("sla_skip"),
+                            symbol_short!("v1"),
+                        ),
+                        (request_id, idx, err as u32),
+                    );
+                    continue;
+                }
+                Self::report_sla_breach(&env, request_id, &leg);
+                hit = true;
+            }
+            if hit {
+                breached.push_back(request_id);
+            }
         }
 
-        let inv_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::InventoryContract)
-            .unwrap();
+        Ok(breached)
+    }
+
+    /// Compensation steps recorded by every rollback of the workflow's legs.
+    pub fn get_compensation_log(env: Env, request_id: u64) -> Vec<CompensationEntry> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CompensationLog(request_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_workflow(env: Env, request_id: u64) -> Result<WorkflowRecord, CoordinatorError> {
+        load_workflow(&env, request_id).ok_or(CoordinatorError::WorkflowNotFound)
+    }
+
+    /// Timestamp and actor of every transition the workflow has been through.
+    pub fn get_workflow_timeline(
+        env: Env,
+        request_id: u64,
+    ) -> Result<WorkflowTimeline, CoordinatorError> {
+        load_timeline(&env, request_id).ok_or(CoordinatorError::WorkflowNotFound)
+    }
+
+    /// List workflows matching every field set in `filter`.
+    ///
+    /// The narrowest index available (hospital, then bank, then a single
+    /// status) is read from position `cursor`, and the remaining criteria
+    /// applied per record. Returns up to `limit` workflows, 20 when zero,
+    /// after examining at most `MAX_LIST_SCAN` index entries; pass the
+    /// returned `next_cursor` to continue.
+    pub fn list_workflows(
+        env: Env,
+        filter: WorkflowFilter,
+        cursor: u32,
+        limit: u32,
+    ) -> WorkflowPage {
+        let limit = if limit == 0 { 20 } else { limit };
+
+        let index = if let Some(hospital) = filter.hospital.clone() {
+            WorkflowIndex::Hospital(hospital)
+        } else if let Some(bank) = filter.bank.clone() {
+            WorkflowIndex::Bank(bank)
+        } else if filter.statuses.len() == 1 {
+            WorkflowIndex::Status(filter.statuses.get(0).unwrap())
+        } else {
+            WorkflowIndex::All
+        };
+
+        let mut items: Vec<WorkflowRecord> = Vec::new(&env);
+        let next_cursor = index_scan(&env, &index, cursor, MAX_LIST_SCAN, |id| {
+            let Some(wf) = load_workflow(&env, id) else {
+                return true;
+            };
+            if (!filter.statuses.is_empty() && !filter.statuses.contains(wf.status))
+                || filter.hospital.as_ref().is_some_and(|h| *h != wf.hospital)
+                || filter.bank.as_ref().is_some_and(|b| !wf.banks.contains(b))
+            {
+                return true;
+            }
+            items.push_back(wf);
+            items.len() < limit
+        });
+
+        WorkflowPage { items, next_cursor }
+    }
+
+    /// Workflows sitting in Allocated, Dispatched or Delivered whose last
+    /// transition is at least `older_than` seconds old. Returns at most
+    /// `MAX_STUCK_RESULTS` records, oldest status bucket first.
+    pub fn get_stuck_workflows(env: Env, older_than: u64) -> Vec<WorkflowRecord> {
+        let now = env.ledger().timestamp();
+        let mut result: Vec<WorkflowRecord> = Vec::new(&env);
+
+        for status in [
+            WorkflowStatus::Allocated,
+            WorkflowStatus::Dispatched,
+            WorkflowStatus::Delivered,
+        ] {
+            if result.len() >= MAX_STUCK_RESULTS {
+                break;
+            }
+            index_scan(&env, &WorkflowIndex::Status(status), 0, u32::MAX, |id| {
+                let (Some(wf), Some(timeline)) = (load_workflow(&env, id), load_timeline(&env, id))
+                else {
+                    return true;
+                };
+                let since = match status {
+                    WorkflowStatus::Allocated => Some(timeline.allocated_at),
+                    WorkflowStatus::Dispatched => timeline.dispatched_at,
+                    _ => timeline.delivered_at,
+                };
+                if since.is_some_and(|t| now.saturating_sub(t) >= older_than) {
+                    result.push_back(wf);
+                }
+                result.len() < MAX_STUCK_RESULTS
+            });
+        }
+
+        result
+    }
+
+    /// Flag a sustained temperature excursion on `excursion_summary.unit_id`.
+    ///
+    /// Only the configured temperature contract or a registered breach oracle
+    /// may call this. The unit is moved to Compromised in inventory, recorded
+    /// on the workflow leg covered by `payment_id` and taken off that leg.
+    /// A unit already delivered is rejected, since inventory keeps Delivered
+    /// final.
+    ///
+    /// When a matching contract is configured, a replacement from the leg's
+    /// bank is reserved in its place and joins the leg at its current stage;
+    /// the request's assigned units are updated to match. Unless a
+    /// replacement was found, a Locked payment is disputed as
+    /// `TemperatureExcursion`, and the leg settles once that dispute is
+    /// resolved.
+    ///
+    /// # Errors
+    /// - `Unauthorized`          - Caller is not the temperature contract or an oracle
+    /// - `PaymentNotFound`       - No payment with this ID
+    /// - `InvalidPaymentState`   - Payment is neither Locked nor already Disputed
+    /// - `UnitNotFound`          - Inventory has no such unit
+    /// - `UnitNotInWorkflow`     - The payment's workflow leg does not hold the unit
+    /// - `UnitAlreadyDelivered`  - The unit has already been delivered
+    /// - `InventoryUpdateFailed` - The unit could not be marked Compromised
+    /// - `RequestUpdateFailed`   - The request's assigned units could not be updated
+    /// - `PaymentFlagFailed`     - Cross-contract call to payments failed
+    pub fn flag_temperature_breach(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        excursion_summary: ExcursionSummary,
+    ) -> Result<(), CoordinatorError> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+        Self::require_breach_reporter(&env, &caller)?;
+
+        let pay_addr: Address = peer(&env, &DataKey::PaymentContract).unwrap();
+        let pay_client = PaymentContractClient::new(&env, &pay_addr);
+
+        let payment = pay_client
+            .try_get_payment(&payment_id)
+            .map_err(|_| CoordinatorError::PaymentNotFound)?
+            .map_err(|_| CoordinatorError::PaymentNotFound)?;
+
+        if payment.status != PaymentStatus::Locked && payment.status != PaymentStatus::Disputed {
+            return Err(CoordinatorError::InvalidPaymentState);
+        }
+
+        let unit_id = excursion_summary.unit_id;
+        let inv_addr: Address = peer(&env, &DataKey::InventoryContract).unwrap();
         let inv_client = InventoryContractClient::new(&env, &inv_addr);
         let inv_admin = inv_client.get_admin();
-        let location = soroban_sdk::String::from_str(&env, "delivered");
+        let unit = inv_client
+            .try_get_blood_unit(&unit_id)
+            .map_err(|_| CoordinatorError::UnitNotFound)?
+            .map_err(|_| CoordinatorError::UnitNotFound)?;
+        // Delivered is final in inventory, so a delivered unit cannot be
+        // compromised; the hospital disputes the payment instead.
+        if unit.status == BloodStatus::Delivered {
+            return Err(CoordinatorError::UnitAlreadyDelivered);
+        }
+        let mut dispute = true;
+
+        if let Some(mut wf) = load_workflow(&env, payment.request_id) {
+            let idx = wf
+                .legs
+                .iter()
+                .position(|leg| leg.payment_id == payment_id)
+                .ok_or(CoordinatorError::UnitNotInWorkflow)? as u32;
+            let mut leg = wf.legs.get(idx).unwrap();
+            if leg.breached_units.contains(unit_id) {
+                dispute = false;
+            } else {
+                let Some(pos) = leg.unit_ids.first_index_of(unit_id) else {
+                    return Err(CoordinatorError::UnitNotInWorkflow);
+                };
+                leg.breached_units.push_back(unit_id);
+                leg.unit_ids.remove(pos);
+                if let Some(pos) = wf.unit_ids.first_index_of(unit_id) {
+                    wf.unit_ids.remove(pos);
+                }
+                if matches!(
+                    leg.status,
+                    WorkflowStatus::Allocated | WorkflowStatus::Dispatched
+                ) {
+                    if let Some(replacement) =
+                        Self::rematch(&env, &wf, &leg, &unit, &inv_client, &inv_admin)?
+                    {
+                        leg.unit_ids.push_back(replacement);
+                        wf.unit_ids.push_back(replacement);
+                        dispute = false;
+                        env.events().publish(
+                            (symbol_short!("coord"), symbol_short!("rematch")),
+                            (wf.request_id, unit_id, replacement),
+                        );
+                    }
+                }
+                let req_addr: Address = peer(&env, &DataKey::RequestContract).unwrap();
+                RequestContractClient::new(&env, &req_addr)
+                    .try_update_assigned_units(
+                        &env.current_contract_address(),
+                        &wf.request_id,
+                        &wf.unit_ids,
+                    )
+                    .map_err(|_| CoordinatorError::RequestUpdateFailed)?
+                    .map_err(|_| CoordinatorError::RequestUpdateFailed)?;
+                wf.legs.set(idx, leg);
+                save_workflow(&env, &wf);
+            }
+        }
 
-        for i in 0..wf.unit_ids.len() {
-            let uid = wf.unit_ids.get(i).unwrap();
-            // Inventory enforces Reserved → InTransit → Delivered; coordinator must not skip InTransit.
-            inv_client
-                .try_update_status(&uid, &BloodStatus::InTransit, &inv_admin, &None)
-                .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
-                .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
+        if matches!(
+            unit.status,
+            BloodStatus::Available | BloodStatus::Reserved | BloodStatus::InTransit
+        ) {
             inv_client
-                .try_mark_delivered(&uid, &inv_admin, &location)
+                .try_update_status(
+                    &unit_id,
+                    &BloodStatus::Compromised,
+                    &inv_admin,
+                    &Some(String::from_str(&env, "temperature excursion")),
+                )
                 .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
                 .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
         }
 
-        wf.status = WorkflowStatus::Delivered;
-        wf.delivery_confirmed = true;
-        save_workflow(&env, &wf);
+        // A leg made whole by a replacement keeps its escrow Locked so it
+        // settles as usual; a repeated report changes nothing.
+        if payment.status == PaymentStatus::Locked && dispute {
+            let case_id = String::from_str(&env, "TEMP-EXCURSION");
+
+            pay_client
+                .try_record_dispute(
+                    &env.current_contract_address(),
+                    &payment_id,
+                    &DisputeReason::TemperatureExcursion,
+                    &case_id,
+                )
+                .map_err(|_| CoordinatorError::PaymentFlagFailed)?
+                .map_err(|_| CoordinatorError::PaymentFlagFailed)?;
+        }
+
+        let now = env.ledger().timestamp();
+        env.events().publish(
+            (symbol_short!("coord"), symbol_short!("tmp_brch")),
+            (payment_id, unit_id, now),
+        );
+
+        Ok(())
+    }
+
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Admin)
+    }
+
+    fn dispatch_legs(
+        env: &Env,
+        request_id: u64,
+        leg: Option<u32>,
+        rider: &Address,
+        eta: u64,
+    ) -> Result<(), CoordinatorError> {
+        rider.require_auth();
+        Self::require_initialized(env)?;
+        Self::require_not_paused(env)?;
+
+        let mut wf = load_workflow(env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+        let targets = target_legs(env, &wf, leg, WorkflowStatus::Allocated)?;
+        if eta <= env.ledger().timestamp() {
+            return Err(CoordinatorError::InvalidEta);
+        }
+        // Custody passes from each bank to the rider; both sides sign.
+        for idx in targets.iter() {
+            let bank = wf.legs.get(idx).unwrap().bank;
+            if bank != *rider {
+                bank.require_auth();
+            }
+        }
+
+        let inv_addr: Address = peer(env, &DataKey::InventoryContract).unwrap();
+        let inv_client = InventoryContractClient::new(env, &inv_addr);
+        let inv_admin = inv_client.get_admin();
+
+        for idx in targets.iter() {
+            let mut leg = wf.legs.get(idx).unwrap();
+            for uid in leg.unit_ids.iter() {
+                inv_client
+                    .try_update_status(&uid, &BloodStatus::InTransit, &inv_admin, &None)
+                    .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
+                    .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
+            }
+            leg.rider = Some(rider.clone());
+            leg.eta = Some(eta);
+            leg.status = WorkflowStatus::Dispatched;
+            wf.legs.set(idx, leg);
+        }
+        Self::sync_workflow(env, &mut wf, rider)?;
 
         env.events().publish(
             (
                 symbol_short!("coord"),
-                symbol_short!("dlvrd"),
+                symbol_short!("dsptch"),
                 symbol_short!("v1"),
             ),
-            request_id,
+            (request_id, rider.clone(), eta),
         );
 
         Ok(())
     }
 
-    /// Step 3 – Settle payment. Blocked if delivery not confirmed.
-    pub fn settle_payment(
-        env: Env,
+    fn confirm_legs(
+        env: &Env,
         request_id: u64,
-        caller: Address,
+        leg: Option<u32>,
+        caller: &Address,
     ) -> Result<(), CoordinatorError> {
         caller.require_auth();
-        Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        Self::require_initialized(env)?;
+        Self::require_not_paused(env)?;
 
-        let mut wf = load_workflow(&env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+        let mut wf = load_workflow(env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+        let targets = target_legs(env, &wf, leg, WorkflowStatus::Dispatched)?;
 
-        if !wf.delivery_confirmed || wf.status != WorkflowStatus::Delivered {
-            return Err(CoordinatorError::DeliveryNotConfirmed);
+        let req_addr: Address = peer(env, &DataKey::RequestContract).unwrap();
+        let request = RequestContractClient::new(env, &req_addr)
+            .try_get_request(&request_id)
+            .map_err(|_| CoordinatorError::RequestNotFound)?
+            .map_err(|_| CoordinatorError::RequestNotFound)?;
+        if *caller != request.hospital_id {
+            return Err(CoordinatorError::Unauthorized);
         }
 
-        let pay_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::PaymentContract)
-            .unwrap();
-        let pay_client = PaymentContractClient::new(&env, &pay_addr);
-
-        let payment = pay_client
-            .try_get_payment(&wf.payment_id)
-            .map_err(|_| CoordinatorError::PaymentNotFound)?
-            .map_err(|_| CoordinatorError::PaymentNotFound)?;
-
-        if payment.status != PaymentStatus::Locked {
-            return Err(CoordinatorError::InvalidPaymentState);
+        let checks = Self::get_delivery_checks(env.clone());
+        if let Some(proof_addr) = checks.proof_contract {
+            let proof = IdentityContractClient::new(env, &proof_addr)
+                .try_get_delivery(&request_id)
+                .ok()
+                .and_then(|r| r.ok())
+                .flatten();
+            match proof {
+                Some(p) if p.verified => {}
+                _ => return Err(CoordinatorError::DeliveryProofMissing),
+            }
+        }
+        if let Some(compliance_addr) = checks.compliance_contract {
+            let attestation = DeliveryContractClient::new(env, &compliance_addr)
+                .try_get_compliance_attestation(&request_id);
+            match attestation {
+                Ok(Ok((_, true))) => {}
+                _ => return Err(CoordinatorError::TemperatureNonCompliant),
+            }
         }
 
-        pay_client
-            .try_update_status(&wf.payment_id, &PaymentStatus::Released)
-            .map_err(|_| CoordinatorError::PaymentUpdateFailed)?
-            .map_err(|_| CoordinatorError::PaymentUpdateFailed)?;
-
-        wf.status = WorkflowStatus::Settled;
-        save_workflow(&env, &wf);
+        let inv_addr: Address = peer(env, &DataKey::InventoryContract).unwrap();
+        let inv_client = InventoryContractClient::new(env, &inv_addr);
+        let inv_admin = inv_client.get_admin();
+        let location = soroban_sdk::String::from_str(env, "delivered");
+
+        for idx in targets.iter() {
+            let mut leg = wf.legs.get(idx).unwrap();
+            for uid in leg.unit_ids.iter() {
+                inv_client
+                    .try_mark_delivered(&uid, &inv_admin, &location)
+                    .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
+                    .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
+            }
+            leg.status = WorkflowStatus::Delivered;
+            wf.legs.set(idx, leg);
+        }
+        Self::sync_workflow(env, &mut wf, caller)?;
 
         env.events().publish(
             (
                 symbol_short!("coord"),
-                symbol_short!("settld"),
+                symbol_short!("dlvrd"),
                 symbol_short!("v1"),
             ),
-            (request_id, wf.payment_id),
+            (request_id, caller.clone()),
         );
 
         Ok(())
     }
 
-    /// Rollback – admin only. Releases units and refunds payment.
-    pub fn rollback(env: Env, request_id: u64) -> Result<(), CoordinatorError> {
-        get_admin(&env).require_auth();
-        Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+    /// Runs the rollback compensations for one leg, or for every active leg
+    /// when `leg` is `None`, recording `actor` on the timeline. Shared by the
+    /// rollback entry points and `enforce_slas`.
+    fn compensate(
+        env: &Env,
+        request_id: u64,
+        leg: Option<u32>,
+        actor: &Address,
+    ) -> Result<Vec<CompensationEntry>, CoordinatorError> {
+        let mut wf = load_workflow(env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+
+        let targets = match leg {
+            Some(idx) => {
+                let leg = wf.legs.get(idx).ok_or(CoordinatorError::LegNotFound)?;
+                match leg.status {
+                    WorkflowStatus::Settled => return Err(CoordinatorError::CannotRollbackSettled),
+                    WorkflowStatus::Allocated
+                    | WorkflowStatus::Dispatched
+                    | WorkflowStatus::Delivered => {}
+                    _ => return Err(CoordinatorError::InvalidWorkflowState),
+                }
+                Vec::from_array(env, [idx])
+            }
+            None => {
+                match wf.status {
+                    WorkflowStatus::Settled => return Err(CoordinatorError::CannotRollbackSettled),
+                    WorkflowStatus::Allocated
+                    | WorkflowStatus::Dispatched
+                    | WorkflowStatus::Delivered => {}
+                    _ => return Err(CoordinatorError::InvalidWorkflowState),
+                }
+                let mut targets: Vec<u32> = Vec::new(env);
+                for (idx, leg) in wf.legs.iter().enumerate() {
+                    match leg.status {
+                        WorkflowStatus::Settled => {
+                            return Err(CoordinatorError::CannotRollbackSettled)
+                        }
+                        WorkflowStatus::RolledBack => {}
+                        _ => targets.push_back(idx as u32),
+                    }
+                }
+                targets
+            }
+        };
+
+        let mut log: Vec<CompensationEntry> = Vec::new(env);
+
+        let inv_addr: Address = peer(env, &DataKey::InventoryContract).unwrap();
+        let inv_client = InventoryContractClient::new(env, &inv_addr);
+        let pay_addr: Address = peer(env, &DataKey::PaymentContract).unwrap();
+        let pay_client = PaymentContractClient::new(env, &pay_addr);
+
+        for idx in targets.iter() {
+            let mut leg = wf.legs.get(idx).unwrap();
+
+            // Inventory
+            let unit_step = match leg.status {
+                WorkflowStatus::Allocated => {
+                    Some((CompensationStep::ReleaseUnit, BloodStatus::Available))
+                }
+                WorkflowStatus::Dispatched => {
+                    Some((CompensationStep::RecallUnit, BloodStatus::Compromised))
+                }
+                _ => None,
+            };
+            if let Some((step, target)) = unit_step {
+                let inv_admin = inv_client.get_admin();
+                let reason = Some(String::from_str(env, "workflow rollback"));
+
+                for uid in leg.unit_ids.iter() {
+                    let ok = matches!(
+                        inv_client.try_update_status(&uid, &target, &inv_admin, &reason),
+                        Ok(Ok(_))
+                    );
+                    log.push_back(CompensationEntry {
+                        step,
+                        target_id: uid,
+                        mandatory: true,
+                        success: ok,
+                    });
+                }
+            }
 
-        let mut wf = load_workflow(&env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+            // Payment: refund a Locked escrow; a delivered leg records it as a
+            // credit note to the hospital. Disputed payments are attempted but
+            // left to the dispute process if the payments contract refuses.
+            // Escrowed tokens only move back through `refund_escrow`.
+            if let Ok(Ok(payment)) = pay_client.try_get_payment(&leg.payment_id) {
+                if matches!(
+                    payment.status,
+                    PaymentStatus::Locked | PaymentStatus::Disputed
+                ) {
+                    let ok = if payment.token.is_some() {
+                        matches!(
+                            pay_client.try_refund_escrow(
+                                &env.current_contract_address(),
+                                &leg.payment_id
+                            ),
+                            Ok(Ok(()))
+                        )
+                    } else {
+                        matches!(
+                            pay_client.try_update_status(
+                                &env.current_contract_address(),
+                                &leg.payment_id,
+                                &PaymentStatus::Refunded,
+                            ),
+                            Ok(Ok(()))
+                        )
+                    };
+                    log.push_back(CompensationEntry {
+                        step: if leg.status == WorkflowStatus::Delivered {
+                            CompensationStep::CreditNote
+                        } else {
+                            CompensationStep::RefundPayment
+                        },
+                        target_id: leg.payment_id,
+                        mandatory: payment.status == PaymentStatus::Locked,
+                        success: ok,
+                    });
+                }
+            }
 
-        if wf.status == WorkflowStatus::Settled {
-            return Err(CoordinatorError::CannotRollbackSettled);
+            leg.status = WorkflowStatus::RolledBack;
+            wf.legs.set(idx, leg);
         }
 
-        let inv_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::InventoryContract)
-            .unwrap();
-        let inv_client = InventoryContractClient::new(&env, &inv_addr);
-        let inv_admin = inv_client.get_admin();
-
-        for i in 0..wf.unit_ids.len() {
-            let uid = wf.unit_ids.get(i).unwrap();
-            let _ = inv_client.try_update_status(&uid, &BloodStatus::Available, &inv_admin, &None);
+        // Request: cancelled once no leg is left, unless the hospital already
+        // cancelled it.
+        if wf
+            .legs
+            .iter()
+            .all(|leg| leg.status == WorkflowStatus::RolledBack)
+        {
+            let req_addr: Address = peer(env, &DataKey::RequestContract).unwrap();
+            let req_client = RequestContractClient::new(env, &req_addr);
+            let already_cancelled = matches!(
+                req_client.try_get_request(&request_id),
+                Ok(Ok(ref r)) if r.status == RequestStatus::Cancelled
+            );
+            if !already_cancelled {
+                let ok =
+                    Self::set_request_status(env, request_id, RequestStatus::Cancelled).is_ok();
+                log.push_back(CompensationEntry {
+                    step: CompensationStep::CancelRequest,
+                    target_id: request_id,
+                    mandatory: true,
+                    success: ok,
+                });
+            }
         }
 
-        let pay_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::PaymentContract)
-            .unwrap();
-        let pay_client = PaymentContractClient::new(&env, &pay_addr);
-        if let Ok(Ok(payment)) = pay_client.try_get_payment(&wf.payment_id) {
-            if payment.status == PaymentStatus::Locked {
-                let _ = pay_client.try_update_status(&wf.payment_id, &PaymentStatus::Refunded);
+        for entry in log.iter() {
+            if entry.mandatory && !entry.success {
+                return Err(CoordinatorError::CompensationFailed);
             }
         }
 
-        wf.status = WorkflowStatus::RolledBack;
-        save_workflow(&env, &wf);
+        let mut history = Self::get_compensation_log(env.clone(), request_id);
+        history.append(&log);
+        env.storage()
+            .persistent()
+            .set(&DataKey::CompensationLog(request_id), &history);
+
+        Self::sync_workflow(env, &mut wf, actor)?;
 
         env.events().publish(
             (
@@ -484,72 +1665,378 @@ impl CoordinatorContract {
                 symbol_short!("rollbk"),
                 symbol_short!("v1"),
             ),
-            request_id,
+            (request_id, log.len()),
         );
 
-        Ok(())
+        Ok(log)
     }
 
-    pub fn get_workflow(env: Env, request_id: u64) -> Result<WorkflowRecord, CoordinatorError> {
-        load_workflow(&env, request_id).ok_or(CoordinatorError::WorkflowNotFound)
+    fn settle(
+        env: &Env,
+        request_id: u64,
+        leg: Option<u32>,
+        caller: &Address,
+        accepted_units: Option<Vec<u64>>,
+    ) -> Result<i128, CoordinatorError> {
+        caller.require_auth();
+        Self::require_initialized(env)?;
+        Self::require_not_paused(env)?;
+
+        let mut wf = load_workflow(env, request_id).ok_or(CoordinatorError::WorkflowNotFound)?;
+
+        let targets = match leg {
+            Some(idx) => {
+                let leg = wf.legs.get(idx).ok_or(CoordinatorError::LegNotFound)?;
+                if leg.status != WorkflowStatus::Delivered {
+                    return Err(CoordinatorError::DeliveryNotConfirmed);
+                }
+                Vec::from_array(env, [idx])
+            }
+            None => {
+                if !wf.delivery_confirmed || wf.status != WorkflowStatus::Delivered {
+                    return Err(CoordinatorError::DeliveryNotConfirmed);
+                }
+                target_legs(env, &wf, None, WorkflowStatus::Delivered)?
+            }
+        };
+
+        let accepted = match &accepted_units {
+            Some(units) => {
+                if *caller != wf.hospital && *caller != get_admin(env) {
+                    return Err(CoordinatorError::Unauthorized);
+                }
+                let mut seen: Vec<u64> = Vec::new(env);
+                for unit_id in units.iter() {
+                    if !wf.unit_ids.contains(unit_id) || seen.contains(unit_id) {
+                        return Err(CoordinatorError::InvalidSettlementUnits);
+                    }
+                    seen.push_back(unit_id);
+                }
+                if seen.is_empty() {
+                    return Err(CoordinatorError::InvalidSettlementUnits);
+                }
+                Some(seen)
+            }
+            None => None,
+        };
+
+        let pay_addr: Address = peer(env, &DataKey::PaymentContract).unwrap();
+        let pay_client = PaymentContractClient::new(env, &pay_addr);
+        let this = env.current_contract_address();
+
+        let mut total: i128 = 0;
+        for idx in targets.iter() {
+            let mut leg = wf.legs.get(idx).unwrap();
+            let payment = pay_client
+                .try_get_payment(&leg.payment_id)
+                .map_err(|_| CoordinatorError::PaymentNotFound)?
+                .map_err(|_| CoordinatorError::PaymentNotFound)?;
+
+            if payment.status != PaymentStatus::Locked {
+                // A resolved dispute, such as one raised by a temperature
+                // breach, has already paid out the escrow; the leg only
+                // needs closing.
+                let resolved = payment.dispute_resolved
+                    && matches!(
+                        payment.status,
+                        PaymentStatus::Released | PaymentStatus::Refunded
+                    );
+                if !resolved {
+                    return Err(CoordinatorError::InvalidPaymentState);
+                }
+                env.events().publish(
+                    (
+                        symbol_short!("coord"),
+                        symbol_short!("settld"),
+                        symbol_short!("v1"),
+                    ),
+                    (request_id, leg.payment_id),
+                );
+                leg.status = WorkflowStatus::Settled;
+                wf.legs.set(idx, leg);
+                total += payment.released_amount;
+                continue;
+            }
+
+            // Milestones may already have paid out part of the escrow.
+            let remaining = payment.amount - payment.released_amount;
+            let leg_units = leg.unit_ids.len();
+            let leg_accepted = match &accepted {
+                Some(seen) => leg.unit_ids.iter().filter(|uid| seen.contains(uid)).count() as u32,
+                None => leg_units,
+            };
+            // A leg whose every unit was breached away with no replacement
+            // delivered nothing, so its escrow goes back to the hospital.
+            let amount = if leg_units == 0 {
+                0
+            } else {
+                remaining * leg_accepted as i128 / leg_units as i128
+            };
+            let released = if leg_units > 0 && leg_accepted == leg_units {
+                pay_client
+                    .try_release_escrow(&this, &leg.payment_id)
+                    .map_err(|_| CoordinatorError::PaymentUpdateFailed)?
+                    .map_err(|_| CoordinatorError::PaymentUpdateFailed)?;
+                remaining
+            } else if amount == 0 {
+                pay_client
+                    .try_refund_escrow(&this, &leg.payment_id)
+                    .map_err(|_| CoordinatorError::PaymentUpdateFailed)?
+                    .map_err(|_| CoordinatorError::PaymentUpdateFailed)?;
+                0
+            } else {
+                pay_client
+                    .try_release_partial(&this, &leg.payment_id, &amount)
+                    .map_err(|_| CoordinatorError::PaymentUpdateFailed)?
+                    .map_err(|_| CoordinatorError::PaymentUpdateFailed)?;
+                amount
+            };
+
+            env.events().publish(
+                (
+                    symbol_short!("coord"),
+                    symbol_short!("settld"),
+                    symbol_short!("v1"),
+                ),
+                (request_id, leg.payment_id),
+            );
+            if released < remaining {
+                env.events().publish(
+                    (symbol_short!("coord"), symbol_short!("prt_stl")),
+                    (request_id, released, remaining - released),
+                );
+            }
+
+            leg.status = WorkflowStatus::Settled;
+            wf.legs.set(idx, leg);
+            total += released;
+        }
+        Self::sync_workflow(env, &mut wf, caller)?;
+
+        Ok(total)
     }
 
-    /// Flag a temperature breach: transitions the linked payment from Locked → Disputed.
-    ///
-    /// Called by the temperature contract when a sustained excursion is detected.
-    ///
-    /// # Errors
-    /// - `PaymentNotFound`     - No payment with this ID
-    /// - `InvalidPaymentState` - Payment is not in Locked status
-    /// - `PaymentFlagFailed`   - Cross-contract call to payments failed
-    pub fn flag_temperature_breach(
-        env: Env,
-        caller: Address,
-        payment_id: u64,
-        excursion_summary: ExcursionSummary,
+    /// Save leg changes and move the workflow to the status its legs imply.
+    /// The request becomes Fulfilled once no leg is left undelivered.
+    fn sync_workflow(
+        env: &Env,
+        wf: &mut WorkflowRecord,
+        actor: &Address,
     ) -> Result<(), CoordinatorError> {
-        caller.require_auth();
-        Self::require_initialized(&env)?;
-        Self::require_not_paused(&env)?;
+        let status = aggregate_status(wf);
+        if matches!(status, WorkflowStatus::Delivered | WorkflowStatus::Settled)
+            && !wf.delivery_confirmed
+        {
+            Self::set_request_status(env, wf.request_id, RequestStatus::Fulfilled)?;
+            wf.delivery_confirmed = true;
+        }
+        if status == wf.status {
+            save_workflow(env, wf);
+        } else {
+            transition(env, wf, status, actor);
+        }
+        Ok(())
+    }
 
-        let pay_addr: Address = env
+    fn set_request_status(
+        env: &Env,
+        request_id: u64,
+        status: RequestStatus,
+    ) -> Result<(), CoordinatorError> {
+        let req_addr: Address = peer(env, &DataKey::RequestContract).unwrap();
+        RequestContractClient::new(env, &req_addr)
+            .try_update_request_status(&env.current_contract_address(), &request_id, &status)
+            .map_err(|_| CoordinatorError::RequestUpdateFailed)?
+            .map_err(|_| CoordinatorError::RequestUpdateFailed)?;
+        Ok(())
+    }
+
+    /// Up to `max` request IDs from the Allocated and Dispatched indexes,
+    /// read from the position the previous `enforce_slas` sweep saved and
+    /// wrapping around to the start.
+    fn next_sla_candidates(env: &Env, max: u32) -> Vec<u64> {
+        let (mut status, mut pos): (WorkflowStatus, u32) = env
             .storage()
             .instance()
-            .get(&DataKey::PaymentContract)
-            .unwrap();
-        let pay_client = PaymentContractClient::new(&env, &pay_addr);
+            .get(&DataKey::SlaCursor)
+            .unwrap_or((WorkflowStatus::Allocated, 0));
+
+        let mut candidates: Vec<u64> = Vec::new(env);
+        // Rest of the current index, the other one, then the current one
+        // again from its start.
+        for _ in 0..3 {
+            let room = max - candidates.len();
+            if room == 0 {
+                break;
+            }
+            let next = index_scan(env, &WorkflowIndex::Status(status), pos, room, |id| {
+                if !candidates.contains(id) {
+                    candidates.push_back(id);
+                }
+                true
+            });
+            match next {
+                Some(next) => pos = next,
+                None => {
+                    status = if status == WorkflowStatus::Allocated {
+                        WorkflowStatus::Dispatched
+                    } else {
+                        WorkflowStatus::Allocated
+                    };
+                    pos = 0;
+                }
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SlaCursor, &(status, pos));
+        candidates
+    }
+
+    /// Check that rolling back leg `idx` will go through before any contract
+    /// is touched, so a sweep that has to skip the leg leaves no unit
+    /// released or escrow refunded behind a leg still marked Allocated.
+    fn check_leg_rollback(
+        env: &Env,
+        wf: &WorkflowRecord,
+        idx: u32,
+    ) -> Result<(), CoordinatorError> {
+        let leg = wf.legs.get(idx).ok_or(CoordinatorError::LegNotFound)?;
+
+        let inv_addr: Address = peer(env, &DataKey::InventoryContract).unwrap();
+        let inv_client = InventoryContractClient::new(env, &inv_addr);
+        for uid in leg.unit_ids.iter() {
+            let unit = inv_client
+                .try_get_blood_unit(&uid)
+                .map_err(|_| CoordinatorError::UnitNotFound)?
+                .map_err(|_| CoordinatorError::UnitNotFound)?;
+            if unit.status != BloodStatus::Reserved {
+                return Err(CoordinatorError::CompensationFailed);
+            }
+        }
+
+        // The last live leg also cancels the request.
+        let last_leg = wf
+            .legs
+            .iter()
+            .enumerate()
+            .all(|(i, other)| i as u32 == idx || other.status == WorkflowStatus::RolledBack);
+        if last_leg {
+            let req_addr: Address = peer(env, &DataKey::RequestContract).unwrap();
+            RequestContractClient::new(env, &req_addr)
+                .try_get_request(&wf.request_id)
+                .map_err(|_| CoordinatorError::RequestUpdateFailed)?
+                .map_err(|_| CoordinatorError::RequestUpdateFailed)?;
+        }
+        Ok(())
+    }
 
+    /// Dispute a late leg's Locked payment as `LateDelivery`.
+    fn dispute_late_delivery(env: &Env, leg: &WorkflowLeg) -> Result<(), CoordinatorError> {
+        let pay_addr: Address = peer(env, &DataKey::PaymentContract).unwrap();
+        let pay_client = PaymentContractClient::new(env, &pay_addr);
         let payment = pay_client
-            .try_get_payment(&payment_id)
+            .try_get_payment(&leg.payment_id)
             .map_err(|_| CoordinatorError::PaymentNotFound)?
             .map_err(|_| CoordinatorError::PaymentNotFound)?;
-
-        if payment.status != PaymentStatus::Locked {
-            return Err(CoordinatorError::InvalidPaymentState);
+        if payment.status == PaymentStatus::Locked {
+            pay_client
+                .try_record_dispute(
+                    &env.current_contract_address(),
+                    &leg.payment_id,
+                    &DisputeReason::LateDelivery,
+                    &String::from_str(env, "SLA-LATE-DELIVERY"),
+                )
+                .map_err(|_| CoordinatorError::PaymentFlagFailed)?
+                .map_err(|_| CoordinatorError::PaymentFlagFailed)?;
         }
+        Ok(())
+    }
 
-        let case_id = String::from_str(&env, "TEMP-EXCURSION");
-
-        pay_client
-            .try_record_dispute(
-                &payment_id,
-                &payment_client::DisputeReason::TemperatureExcursion,
-                &case_id,
-            )
-            .map_err(|_| CoordinatorError::PaymentFlagFailed)?
-            .map_err(|_| CoordinatorError::PaymentFlagFailed)?;
+    /// Report a leg's SLA breach against its bank. Best-effort: a missing or
+    /// failing reputation contract never blocks enforcement.
+    fn report_sla_breach(env: &Env, request_id: u64, leg: &WorkflowLeg) {
+        let rep_addr: Option<Address> = peer(env, &DataKey::ReputationContract);
+        let entity_id: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BankEntity(leg.bank.clone()));
+        if let (Some(rep_addr), Some(entity_id)) = (rep_addr, entity_id) {
+            let _ = ReputationContractClient::new(env, &rep_addr)
+                .try_report_sla_breach(&env.current_contract_address(), &entity_id);
+        }
 
-        let now = env.ledger().timestamp();
         env.events().publish(
-            (symbol_short!("coord"), symbol_short!("tmp_brch")),
-            (payment_id, excursion_summary.unit_id, now),
+            (
+                symbol_short!("coord"),
+                symbol_short!("sla_brch"),
+                symbol_short!("v1"),
+            ),
+            (request_id, leg.bank.clone(), leg.status),
         );
+    }
 
+    fn require_breach_reporter(env: &Env, caller: &Address) -> Result<(), CoordinatorError> {
+        let temperature: Option<Address> = peer(env, &DataKey::TemperatureContract);
+        let is_oracle = env
+            .storage()
+            .persistent()
+            .has(&DataKey::BreachOracle(caller.clone()));
+        if temperature.as_ref() != Some(caller) && !is_oracle {
+            return Err(CoordinatorError::Unauthorized);
+        }
         Ok(())
     }
 
-    pub fn is_initialized(env: Env) -> bool {
-        env.storage().instance().has(&DataKey::Admin)
+    /// Reserve a replacement for a breached `unit` from the leg's bank, moving
+    /// it on to InTransit when the leg is already dispatched. Returns `None`
+    /// when re-matching is off or the matching contract finds nothing.
+    fn rematch(
+        env: &Env,
+        wf: &WorkflowRecord,
+        leg: &WorkflowLeg,
+        unit: &BloodUnit,
+        inv_client: &InventoryContractClient,
+        inv_admin: &Address,
+    ) -> Result<Option<u64>, CoordinatorError> {
+        let Some(matching_addr) = peer(env, &DataKey::MatchingContract) else {
+            return Ok(None);
+        };
+        let req_addr: Address = peer(env, &DataKey::RequestContract).unwrap();
+        let request = RequestContractClient::new(env, &req_addr)
+            .try_get_request(&wf.request_id)
+            .map_err(|_| CoordinatorError::RequestNotFound)?
+            .map_err(|_| CoordinatorError::RequestNotFound)?;
+
+        let mut exclude = wf.unit_ids.clone();
+        exclude.append(&leg.breached_units);
+        let replacement = MatchingContractClient::new(env, &matching_addr)
+            .try_find_replacement(
+                &request.blood_type,
+                &wf.urgency,
+                &unit.quantity_ml,
+                &leg.bank,
+                &exclude,
+            )
+            .ok()
+            .and_then(|r| r.ok())
+            .flatten();
+        let Some(replacement) = replacement else {
+            return Ok(None);
+        };
+
+        let mut steps = Vec::from_array(env, [BloodStatus::Reserved]);
+        if leg.status == WorkflowStatus::Dispatched {
+            steps.push_back(BloodStatus::InTransit);
+        }
+        for status in steps.iter() {
+            inv_client
+                .try_update_status(&replacement, &status, inv_admin, &None)
+                .map_err(|_| CoordinatorError::InventoryUpdateFailed)?
+                .map_err(|_| CoordinatorError::InventoryUpdateFailed)?;
+        }
+        Ok(Some(replacement))
     }
 
     fn require_initialized(env: &Env) -> Result<(), CoordinatorError> {
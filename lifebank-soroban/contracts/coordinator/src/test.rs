@@ -6,13 +6,18 @@
 //! alongside the coordinator in a single Soroban test environment, then
 //! drives the full request → allocation → delivery → settlement sequence.
 
+use lifebank_types::testutils::upload_placeholder_wasm;
 use soroban_sdk::{
-    contract, contractimpl, contracttype, testutils::Address as _, vec, Address, Env, String, Vec,
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, Env, Map, String, Vec,
 };
 
 use super::{
-    BloodRequest, BloodStatus, BloodUnit, CoordinatorContract, CoordinatorContractClient,
-    CoordinatorError, Payment, PaymentStatus, RequestStatus, WorkflowStatus,
+    BloodComponent, BloodRequest, BloodStatus, BloodType, BloodUnit, CompensationStep,
+    CoordinatorContract, CoordinatorContractClient, CoordinatorError, DataKey, DeliveryChecks,
+    DeliveryProof, FeeBreakdown, LegAllocation, Payment, PaymentStatus, RequestStatus, SlaConfig,
+    Urgency, WorkflowFilter, WorkflowIndex, WorkflowRecord, WorkflowStatus,
 };
 
 // ── Mock: Request contract ────────────────────────────────────────────────────
@@ -21,6 +26,8 @@ use super::{
 enum ReqKey {
     Request(u64),
     Counter,
+    Assigned(u64),
+    Reject,
 }
 
 #[contract]
@@ -28,10 +35,24 @@ struct MockRequestContract;
 
 #[contractimpl]
 impl MockRequestContract {
-    pub fn seed_request(env: Env, id: u64, status: RequestStatus) {
+    pub fn seed_request(env: Env, id: u64, hospital: Address, status: RequestStatus) {
+        let request = BloodRequest {
+            id,
+            hospital_id: hospital,
+            blood_type: BloodType::OPositive,
+            component: BloodComponent::WholeBlood,
+            quantity_ml: 450,
+            urgency: Urgency::Urgent,
+            created_timestamp: 0,
+            required_by_timestamp: 3600,
+            status,
+            assigned_units: Vec::new(&env),
+            fulfilled_quantity_ml: 0,
+            reservation_id: None,
+        };
         env.storage()
             .persistent()
-            .set(&ReqKey::Request(id), &BloodRequest { id, status });
+            .set(&ReqKey::Request(id), &request);
     }
 
     pub fn get_request(env: Env, request_id: u64) -> BloodRequest {
@@ -40,6 +61,47 @@ impl MockRequestContract {
             .get(&ReqKey::Request(request_id))
             .unwrap()
     }
+
+    /// Make every subsequent status update panic, simulating a rejection.
+    pub fn reject_updates(env: Env) {
+        env.storage().instance().set(&ReqKey::Reject, &true);
+    }
+
+    pub fn assign_units(env: Env, caller: Address, request_id: u64, unit_ids: Vec<u64>) {
+        Self::update_request_status(env.clone(), caller, request_id, RequestStatus::Approved);
+        env.storage()
+            .persistent()
+            .set(&ReqKey::Assigned(request_id), &unit_ids);
+    }
+
+    pub fn update_assigned_units(env: Env, _caller: Address, request_id: u64, unit_ids: Vec<u64>) {
+        env.storage()
+            .persistent()
+            .set(&ReqKey::Assigned(request_id), &unit_ids);
+    }
+
+    pub fn update_request_status(
+        env: Env,
+        _caller: Address,
+        request_id: u64,
+        new_status: RequestStatus,
+    ) {
+        if env.storage().instance().has(&ReqKey::Reject) {
+            panic!("request update rejected");
+        }
+        let mut req = Self::get_request(env.clone(), request_id);
+        req.status = new_status;
+        env.storage()
+            .persistent()
+            .set(&ReqKey::Request(request_id), &req);
+    }
+
+    pub fn get_assigned_units(env: Env, request_id: u64) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&ReqKey::Assigned(request_id))
+            .unwrap_or(Vec::new(&env))
+    }
 }
 
 // ── Mock: Inventory contract ──────────────────────────────────────────────────
@@ -66,6 +128,11 @@ impl MockInventoryContract {
     }
 
     pub fn register_unit(env: Env) -> u64 {
+        let bank = Self::get_admin(env.clone());
+        Self::register_unit_for(env, bank)
+    }
+
+    pub fn register_unit_for(env: Env, bank: Address) -> u64 {
         let id: u64 = env
             .storage()
             .instance()
@@ -77,7 +144,14 @@ impl MockInventoryContract {
             &InvKey::Unit(id),
             &BloodUnit {
                 id,
+                blood_type: BloodType::OPositive,
+                quantity_ml: 450,
+                bank_id: bank,
+                donor_id: None,
+                donation_timestamp: 0,
+                expiration_timestamp: 35 * 86_400,
                 status: BloodStatus::Available,
+                metadata: Map::new(&env),
             },
         );
         id
@@ -102,6 +176,12 @@ impl MockInventoryContract {
             .persistent()
             .get(&InvKey::Unit(unit_id))
             .unwrap();
+        // Mirror inventory's forward-only lifecycle for the cases rollback relies on.
+        if unit.status == BloodStatus::Delivered
+            || (unit.status == BloodStatus::InTransit && new_status == BloodStatus::Available)
+        {
+            panic!("invalid status transition");
+        }
         unit.status = new_status;
         env.storage().persistent().set(&InvKey::Unit(unit_id), &unit);
         unit
@@ -123,6 +203,7 @@ impl MockInventoryContract {
 enum PayKey {
     Payment(u64),
     Counter,
+    RejectDisputes,
 }
 
 #[contract]
@@ -130,7 +211,13 @@ struct MockPaymentContract;
 
 #[contractimpl]
 impl MockPaymentContract {
-    pub fn create_payment(env: Env, request_id: u64, status: PaymentStatus) -> u64 {
+    pub fn create_payment(
+        env: Env,
+        request_id: u64,
+        payer: Address,
+        payee: Address,
+        status: PaymentStatus,
+    ) -> u64 {
         let id: u64 = env
             .storage()
             .instance()
@@ -138,10 +225,25 @@ impl MockPaymentContract {
             .unwrap_or(0u64)
             + 1;
         env.storage().instance().set(&PayKey::Counter, &id);
-        env.storage().persistent().set(
-            &PayKey::Payment(id),
-            &Payment { id, request_id, status },
-        );
+        let payment = Payment {
+            id,
+            request_id,
+            payer,
+            payee,
+            amount: 1_000,
+            status,
+            created_at: env.ledger().timestamp(),
+            updated_at: env.ledger().timestamp(),
+            dispute_reason_code: None,
+            dispute_case_id: None,
+            dispute_resolved: false,
+            token: None,
+            released_amount: 0,
+            fee: FeeBreakdown::none(),
+        };
+        env.storage()
+            .persistent()
+            .set(&PayKey::Payment(id), &payment);
         id
     }
 
@@ -152,7 +254,7 @@ impl MockPaymentContract {
             .unwrap()
     }
 
-    pub fn update_status(env: Env, payment_id: u64, status: PaymentStatus) {
+    pub fn update_status(env: Env, _caller: Address, payment_id: u64, status: PaymentStatus) {
         let mut p: Payment = env
             .storage()
             .persistent()
@@ -164,7 +266,78 @@ impl MockPaymentContract {
             .set(&PayKey::Payment(payment_id), &p);
     }
 
-    pub fn record_dispute(env: Env, payment_id: u64, _reason: super::payment_client::DisputeReason, _case_id: String) {
+    pub fn release_escrow(env: Env, caller: Address, payment_id: u64) {
+        let p: Payment = env
+            .storage()
+            .persistent()
+            .get(&PayKey::Payment(payment_id))
+            .unwrap();
+        Self::release_partial(env, caller, payment_id, p.amount - p.released_amount);
+    }
+
+    pub fn refund_escrow(env: Env, _caller: Address, payment_id: u64) {
+        let mut p: Payment = env
+            .storage()
+            .persistent()
+            .get(&PayKey::Payment(payment_id))
+            .unwrap();
+        assert_eq!(p.status, PaymentStatus::Locked);
+        p.status = PaymentStatus::Refunded;
+        env.storage()
+            .persistent()
+            .set(&PayKey::Payment(payment_id), &p);
+    }
+
+    pub fn release_partial(env: Env, _caller: Address, payment_id: u64, amount: i128) {
+        let mut p: Payment = env
+            .storage()
+            .persistent()
+            .get(&PayKey::Payment(payment_id))
+            .unwrap();
+        assert_eq!(p.status, PaymentStatus::Locked);
+        p.status = PaymentStatus::Released;
+        p.released_amount += amount;
+        env.storage()
+            .persistent()
+            .set(&PayKey::Payment(payment_id), &p);
+    }
+
+    /// Close a dispute the way payments' `resolve_dispute` does, releasing
+    /// `released` to the payee.
+    pub fn resolve_dispute(env: Env, payment_id: u64, released: i128) {
+        let mut p: Payment = env
+            .storage()
+            .persistent()
+            .get(&PayKey::Payment(payment_id))
+            .unwrap();
+        assert_eq!(p.status, PaymentStatus::Disputed);
+        p.status = if released > 0 {
+            PaymentStatus::Released
+        } else {
+            PaymentStatus::Refunded
+        };
+        p.released_amount = released;
+        p.dispute_resolved = true;
+        env.storage()
+            .persistent()
+            .set(&PayKey::Payment(payment_id), &p);
+    }
+
+    /// Make every subsequent `record_dispute` panic, simulating a rejection.
+    pub fn reject_disputes(env: Env) {
+        env.storage().instance().set(&PayKey::RejectDisputes, &true);
+    }
+
+    pub fn record_dispute(
+        env: Env,
+        _caller: Address,
+        payment_id: u64,
+        _reason: super::DisputeReason,
+        _case_id: String,
+    ) {
+        if env.storage().instance().has(&PayKey::RejectDisputes) {
+            panic!("dispute rejected");
+        }
         let mut p: Payment = env
             .storage()
             .persistent()
@@ -177,11 +350,124 @@ impl MockPaymentContract {
     }
 }
 
+// ── Mock: Identity (delivery proof) and Delivery (compliance) contracts ─────────
+
+#[contracttype]
+enum ProofKey {
+    Proof(u64),
+    Attestation(u64),
+}
+
+#[contract]
+struct MockIdentityContract;
+
+#[contractimpl]
+impl MockIdentityContract {
+    pub fn seed_proof(env: Env, request_id: u64, recipient: Address, verified: bool) {
+        env.storage().persistent().set(
+            &ProofKey::Proof(request_id),
+            &DeliveryProof {
+                request_id,
+                org_id: recipient.clone(),
+                recipient,
+                quantity_delivered: 450,
+                temperature_ok: true,
+                delivered_at: 0,
+                verified,
+                verified_at: None,
+            },
+        );
+    }
+
+    pub fn get_delivery(env: Env, request_id: u64) -> Option<DeliveryProof> {
+        env.storage().persistent().get(&ProofKey::Proof(request_id))
+    }
+}
+
+#[contract]
+struct MockDeliveryContract;
+
+#[contractimpl]
+impl MockDeliveryContract {
+    pub fn seed_attestation(env: Env, delivery_id: u64, is_compliant: bool) {
+        env.storage().persistent().set(
+            &ProofKey::Attestation(delivery_id),
+            &(Bytes::from_array(&env, &[7u8; 32]), is_compliant),
+        );
+    }
+
+    pub fn get_compliance_attestation(env: Env, delivery_id: u64) -> (Bytes, bool) {
+        env.storage()
+            .persistent()
+            .get(&ProofKey::Attestation(delivery_id))
+            .unwrap()
+    }
+}
+
+// ── Mock: Reputation contract ─────────────────────────────────────────────────
+
+#[contracttype]
+enum RepKey {
+    Breaches,
+}
+
+#[contract]
+struct MockReputationContract;
+
+#[contractimpl]
+impl MockReputationContract {
+    pub fn report_sla_breach(env: Env, _reporter: Address, entity_id: u64) {
+        let mut breaches = Self::breaches(env.clone());
+        breaches.push_back(entity_id);
+        env.storage().instance().set(&RepKey::Breaches, &breaches);
+    }
+
+    pub fn breaches(env: Env) -> Vec<u64> {
+        env.storage()
+            .instance()
+            .get(&RepKey::Breaches)
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+// ── Mock: Matching contract ───────────────────────────────────────────────────
+
+#[contracttype]
+enum MatchKey {
+    Replacement,
+}
+
+#[contract]
+struct MockMatchingContract;
+
+#[contractimpl]
+impl MockMatchingContract {
+    pub fn set_replacement(env: Env, unit_id: u64) {
+        env.storage()
+            .instance()
+            .set(&MatchKey::Replacement, &unit_id);
+    }
+
+    pub fn find_replacement(
+        env: Env,
+        _blood_type: BloodType,
+        _urgency: Urgency,
+        _quantity_ml: u32,
+        _bank: Address,
+        exclude: Vec<u64>,
+    ) -> Option<u64> {
+        let unit_id: Option<u64> = env.storage().instance().get(&MatchKey::Replacement);
+        unit_id.filter(|id| !exclude.contains(id))
+    }
+}
+
 // ── Harness ───────────────────────────────────────────────────────────────────
 
 struct Harness<'a> {
     env: Env,
     admin: Address,
+    hospital: Address,
+    rider: Address,
     coord: CoordinatorContractClient<'a>,
     req_id: Address,
     inv_id: Address,
@@ -191,12 +477,16 @@ struct Harness<'a> {
 fn setup<'a>() -> Harness<'a> {
     let env = Env::default();
     env.mock_all_auths();
+    let pay_id = env.register(MockPaymentContract, ());
+    setup_with_payments(env, pay_id)
+}
 
+/// Build the harness around an already registered payments contract.
+fn setup_with_payments<'a>(env: Env, pay_id: Address) -> Harness<'a> {
     let admin = Address::generate(&env);
 
     let req_id = env.register(MockRequestContract, ());
     let inv_id = env.register(MockInventoryContract, ());
-    let pay_id = env.register(MockPaymentContract, ());
     let coord_id = env.register(CoordinatorContract, ());
 
     // Initialize inventory mock with admin
@@ -206,21 +496,57 @@ fn setup<'a>() -> Harness<'a> {
     let coord = CoordinatorContractClient::new(&env, &coord_id);
     coord.initialize(&admin, &req_id, &inv_id, &pay_id);
 
-    Harness { env, admin, coord, req_id, inv_id, pay_id }
+    let hospital = Address::generate(&env);
+    let rider = Address::generate(&env);
+
+    Harness {
+        env,
+        admin,
+        hospital,
+        rider,
+        coord,
+        req_id,
+        inv_id,
+        pay_id,
+    }
 }
 
 fn seed_pending_request(h: &Harness, id: u64) {
+    MockRequestContractClient::new(&h.env, &h.req_id).seed_request(
+        &id,
+        &h.hospital,
+        &RequestStatus::Pending,
+    );
+}
+
+fn dispatch_units(h: &Harness, request_id: u64) {
+    h.coord
+        .dispatch(&request_id, &h.rider, &(h.env.ledger().timestamp() + 3600));
+}
+
+fn request_status(h: &Harness, id: u64) -> RequestStatus {
     MockRequestContractClient::new(&h.env, &h.req_id)
-        .seed_request(&id, &RequestStatus::Pending);
+        .get_request(&id)
+        .status
 }
 
 fn register_unit(h: &Harness) -> u64 {
     MockInventoryContractClient::new(&h.env, &h.inv_id).register_unit()
 }
 
+/// A Locked payment from the test hospital to the default bank.
 fn create_locked_payment(h: &Harness, request_id: u64) -> u64 {
-    MockPaymentContractClient::new(&h.env, &h.pay_id)
-        .create_payment(&request_id, &PaymentStatus::Locked)
+    create_payment_to(h, request_id, &h.admin, PaymentStatus::Locked)
+}
+
+/// A payment from the test hospital to `bank` for `request_id`.
+fn create_payment_to(h: &Harness, request_id: u64, bank: &Address, status: PaymentStatus) -> u64 {
+    MockPaymentContractClient::new(&h.env, &h.pay_id).create_payment(
+        &request_id,
+        &h.hospital,
+        bank,
+        &status,
+    )
 }
 
 // ── Happy path ────────────────────────────────────────────────────────────────
@@ -240,8 +566,15 @@ fn test_full_happy_path() {
 
     let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
     assert_eq!(unit.status, BloodStatus::Reserved);
+    assert_eq!(request_status(&h, 1), RequestStatus::Approved);
+    assert_eq!(
+        MockRequestContractClient::new(&h.env, &h.req_id).get_assigned_units(&1u64),
+        vec![&h.env, unit_id]
+    );
 
-    h.coord.confirm_delivery(&1u64, &h.admin);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(request_status(&h, 1), RequestStatus::Fulfilled);
 
     let wf = h.coord.get_workflow(&1u64);
     assert_eq!(wf.status, WorkflowStatus::Delivered);
@@ -322,11 +655,11 @@ fn test_settle_blocked_for_pending_payment() {
     seed_pending_request(&h, 1);
     let unit_id = register_unit(&h);
     // Payment left Pending (not Locked)
-    let payment_id = MockPaymentContractClient::new(&h.env, &h.pay_id)
-        .create_payment(&1u64, &PaymentStatus::Pending);
+    let payment_id = create_payment_to(&h, 1, &h.admin, PaymentStatus::Pending);
 
     h.coord.allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
-    h.coord.confirm_delivery(&1u64, &h.admin);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
 
     let result = h.coord.try_settle_payment(&1u64, &h.admin);
     assert_eq!(result, Err(Ok(CoordinatorError::InvalidPaymentState)));
@@ -335,7 +668,7 @@ fn test_settle_blocked_for_pending_payment() {
 #[test]
 fn test_confirm_delivery_blocked_before_allocation() {
     let h = setup();
-    let result = h.coord.try_confirm_delivery(&99u64, &h.admin);
+    let result = h.coord.try_confirm_delivery(&99u64, &h.hospital);
     assert_eq!(result, Err(Ok(CoordinatorError::WorkflowNotFound)));
 }
 
@@ -343,8 +676,11 @@ fn test_confirm_delivery_blocked_before_allocation() {
 fn test_allocate_blocked_for_non_pending_request() {
     let h = setup();
     // Seed request with Approved status (not Pending)
-    MockRequestContractClient::new(&h.env, &h.req_id)
-        .seed_request(&1u64, &RequestStatus::Approved);
+    MockRequestContractClient::new(&h.env, &h.req_id).seed_request(
+        &1u64,
+        &h.hospital,
+        &RequestStatus::Approved,
+    );
     let unit_id = register_unit(&h);
     let payment_id = create_locked_payment(&h, 1);
 
@@ -357,141 +693,1148 @@ fn test_allocate_blocked_for_non_pending_request() {
     assert_eq!(result, Err(Ok(CoordinatorError::InvalidRequestState)));
 }
 
-// ── Rollback ──────────────────────────────────────────────────────────────────
+// ── Dispatch and delivery confirmation ────────────────────────────────────────
+
+fn allocate_one(h: &Harness, request_id: u64) -> u64 {
+    seed_pending_request(h, request_id);
+    let unit_id = register_unit(h);
+    let payment_id = create_locked_payment(h, request_id);
+    h.coord
+        .allocate_units(&request_id, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    unit_id
+}
 
 #[test]
-fn test_rollback_releases_units_and_refunds_payment() {
+fn test_dispatch_moves_units_in_transit_and_records_custody() {
     let h = setup();
-    seed_pending_request(&h, 1);
-    let unit_id = register_unit(&h);
-    let payment_id = create_locked_payment(&h, 1);
+    h.env.ledger().set_timestamp(1_000);
+    let unit_id = allocate_one(&h, 1);
 
-    h.coord.allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
-    h.coord.rollback(&1u64);
+    h.coord.dispatch(&1u64, &h.rider, &4_600u64);
 
     let wf = h.coord.get_workflow(&1u64);
-    assert_eq!(wf.status, WorkflowStatus::RolledBack);
+    assert_eq!(wf.status, WorkflowStatus::Dispatched);
+    let leg = wf.legs.get(0).unwrap();
+    assert_eq!(leg.status, WorkflowStatus::Dispatched);
+    assert_eq!(leg.rider, Some(h.rider.clone()));
+    assert_eq!(leg.eta, Some(4_600));
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::InTransit);
+}
+
+#[test]
+fn test_dispatch_requires_bank_signature() {
+    use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+    use soroban_sdk::IntoVal;
 
+    let h = setup();
+    h.env.ledger().set_timestamp(1_000);
+    let unit_id = allocate_one(&h, 1);
+
+    // The rider alone cannot take the bank's units.
+    h.env.mock_auths(&[MockAuth {
+        address: &h.rider,
+        invoke: &MockAuthInvoke {
+            contract: &h.coord.address,
+            fn_name: "dispatch",
+            args: (1u64, h.rider.clone(), 4_600u64).into_val(&h.env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(h.coord.try_dispatch(&1u64, &h.rider, &4_600u64).is_err());
     let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
-    assert_eq!(unit.status, BloodStatus::Available);
+    assert_eq!(unit.status, BloodStatus::Reserved);
 
-    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
-    assert_eq!(payment.status, PaymentStatus::Refunded);
+    h.env.mock_all_auths();
+    h.coord.dispatch(&1u64, &h.rider, &4_600u64);
+    let signers: Vec<Address> = Vec::from_iter(&h.env, h.env.auths().into_iter().map(|(a, _)| a));
+    assert!(signers.contains(&h.rider));
+    assert!(
+        signers.contains(&h.admin),
+        "the leg's bank signs the handoff"
+    );
 }
 
 #[test]
-fn test_rollback_blocked_after_settlement() {
+fn test_dispatch_rejects_past_eta_and_repeat_dispatch() {
     let h = setup();
-    seed_pending_request(&h, 1);
-    let unit_id = register_unit(&h);
-    let payment_id = create_locked_payment(&h, 1);
+    h.env.ledger().set_timestamp(1_000);
+    allocate_one(&h, 1);
 
-    h.coord.allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
-    h.coord.confirm_delivery(&1u64, &h.admin);
-    h.coord.settle_payment(&1u64, &h.admin);
+    let result = h.coord.try_dispatch(&1u64, &h.rider, &1_000u64);
+    assert_eq!(result, Err(Ok(CoordinatorError::InvalidEta)));
 
-    let result = h.coord.try_rollback(&1u64);
-    assert_eq!(result, Err(Ok(CoordinatorError::CannotRollbackSettled)));
+    h.coord.dispatch(&1u64, &h.rider, &2_000u64);
+    let result = h.coord.try_dispatch(&1u64, &h.rider, &2_000u64);
+    assert_eq!(result, Err(Ok(CoordinatorError::InvalidWorkflowState)));
 }
 
-// ── Circuit breaker tests ─────────────────────────────────────────────────────
-
 #[test]
-fn test_coordinator_pause_blocks_allocate_units() {
+fn test_confirm_delivery_requires_dispatch() {
     let h = setup();
-    h.coord.pause(&h.admin);
-    assert!(h.coord.is_paused());
-
-    seed_pending_request(&h, 1);
-    let unit_id = register_unit(&h);
-    let pay_id = create_locked_payment(&h, 1);
+    allocate_one(&h, 1);
 
-    let result = h.coord.try_allocate_units(&1u64, &vec![&h.env, unit_id], &pay_id, &h.admin);
-    assert!(result.is_err());
+    let result = h.coord.try_confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(result, Err(Ok(CoordinatorError::InvalidWorkflowState)));
 }
 
 #[test]
-fn test_coordinator_pause_allows_get_workflow() {
+fn test_confirm_delivery_restricted_to_receiving_hospital() {
     let h = setup();
+    allocate_one(&h, 1);
+    dispatch_units(&h, 1);
 
-    // Create a workflow first
-    seed_pending_request(&h, 10);
-    let unit_id = register_unit(&h);
-    let pay_id = create_locked_payment(&h, 10);
-    h.coord.allocate_units(&10u64, &vec![&h.env, unit_id], &pay_id, &h.admin);
-
-    h.coord.pause(&h.admin);
+    let result = h.coord.try_confirm_delivery(&1u64, &h.admin);
+    assert_eq!(result, Err(Ok(CoordinatorError::Unauthorized)));
+    let result = h.coord.try_confirm_delivery(&1u64, &h.rider);
+    assert_eq!(result, Err(Ok(CoordinatorError::Unauthorized)));
 
-    // Read still works
-    let wf = h.coord.get_workflow(&10u64);
-    assert_eq!(wf.request_id, 10);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Delivered
+    );
 }
 
 #[test]
-fn test_coordinator_unpause_restores_writes() {
+fn test_confirm_delivery_enforces_configured_checks() {
     let h = setup();
-    h.coord.pause(&h.admin);
-    h.coord.unpause(&h.admin);
-    assert!(!h.coord.is_paused());
-
-    seed_pending_request(&h, 20);
-    let unit_id = register_unit(&h);
-    let pay_id = create_locked_payment(&h, 20);
-    h.coord.allocate_units(&20u64, &vec![&h.env, unit_id], &pay_id, &h.admin);
-    assert_eq!(h.coord.get_workflow(&20u64).status, WorkflowStatus::Allocated);
+    let identity_id = h.env.register(MockIdentityContract, ());
+    let delivery_id = h.env.register(MockDeliveryContract, ());
+    h.coord.set_delivery_checks(
+        &h.admin,
+        &DeliveryChecks {
+            proof_contract: Some(identity_id.clone()),
+            compliance_contract: Some(delivery_id.clone()),
+        },
+    );
+    let identity = MockIdentityContractClient::new(&h.env, &identity_id);
+    let delivery = MockDeliveryContractClient::new(&h.env, &delivery_id);
+
+    allocate_one(&h, 1);
+    dispatch_units(&h, 1);
+
+    // No proof recorded yet
+    let result = h.coord.try_confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(result, Err(Ok(CoordinatorError::DeliveryProofMissing)));
+
+    // Unverified proof is not enough
+    identity.seed_proof(&1u64, &h.hospital, &false);
+    let result = h.coord.try_confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(result, Err(Ok(CoordinatorError::DeliveryProofMissing)));
+
+    // Verified proof but no / failed attestation
+    identity.seed_proof(&1u64, &h.hospital, &true);
+    let result = h.coord.try_confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(result, Err(Ok(CoordinatorError::TemperatureNonCompliant)));
+    delivery.seed_attestation(&1u64, &false);
+    let result = h.coord.try_confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(result, Err(Ok(CoordinatorError::TemperatureNonCompliant)));
+
+    delivery.seed_attestation(&1u64, &true);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Delivered
+    );
 }
 
 #[test]
-#[should_panic]
-fn test_coordinator_non_admin_cannot_pause() {
+fn test_set_delivery_checks_admin_only() {
     let h = setup();
-    let attacker = Address::generate(&h.env);
-    h.coord.pause(&attacker);
+    let checks = DeliveryChecks {
+        proof_contract: None,
+        compliance_contract: None,
+    };
+    let result = h.coord.try_set_delivery_checks(&h.hospital, &checks);
+    assert_eq!(result, Err(Ok(CoordinatorError::Unauthorized)));
 }
 
-// ── Temperature excursion → dispute integration tests (issue #477) ────────────
+// ── Rollback ──────────────────────────────────────────────────────────────────
 
-use super::ExcursionSummary;
+#[test]
+fn test_rollback_releases_units_and_refunds_payment() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
 
-fn make_excursion(unit_id: u64) -> ExcursionSummary {
-    ExcursionSummary {
-        unit_id,
-        violation_count: 3,
-        peak_celsius_x100: 1200, // 12.00°C — above threshold
-        detected_at: 1000,
-    }
+    h.coord.allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    h.coord.rollback(&1u64);
+
+    let wf = h.coord.get_workflow(&1u64);
+    assert_eq!(wf.status, WorkflowStatus::RolledBack);
+
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::Available);
+
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Refunded);
+
+    assert_eq!(request_status(&h, 1), RequestStatus::Cancelled);
 }
 
-/// Full chain: flag_temperature_breach transitions Locked → Disputed.
 #[test]
-fn test_flag_temperature_breach_transitions_locked_to_disputed() {
+fn test_rollback_logs_each_compensation() {
     let h = setup();
-    let payment_id = create_locked_payment(&h, 99);
+    let unit_id = allocate_one(&h, 1);
+
+    let log = h.coord.rollback(&1u64);
+
+    assert_eq!(log.len(), 3);
+    let release = log.get(0).unwrap();
+    assert_eq!(release.step, CompensationStep::ReleaseUnit);
+    assert_eq!(release.target_id, unit_id);
+    assert!(release.mandatory && release.success);
+    assert_eq!(log.get(1).unwrap().step, CompensationStep::RefundPayment);
+    assert_eq!(log.get(2).unwrap().step, CompensationStep::CancelRequest);
+    assert_eq!(h.coord.get_compensation_log(&1u64), log);
+}
 
-    let excursion = make_excursion(42);
-    h.coord
-        .flag_temperature_breach(&h.admin, &payment_id, &excursion);
+#[test]
+fn test_rollback_dispatched_recalls_units_as_compromised() {
+    let h = setup();
+    let unit_id = allocate_one(&h, 1);
+    dispatch_units(&h, 1);
 
-    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    let log = h.coord.rollback(&1u64);
+
+    assert_eq!(log.get(0).unwrap().step, CompensationStep::RecallUnit);
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::Compromised);
+    assert_eq!(request_status(&h, 1), RequestStatus::Cancelled);
     assert_eq!(
-        payment.status,
-        PaymentStatus::Disputed,
-        "Payment must be Disputed after temperature breach"
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::RolledBack
     );
 }
 
-/// flag_temperature_breach on a non-Locked payment returns InvalidPaymentState.
 #[test]
-fn test_flag_temperature_breach_non_locked_payment_fails() {
+fn test_rollback_delivered_issues_credit_note() {
     let h = setup();
-    // Create a Released payment
-    let payment_id = MockPaymentContractClient::new(&h.env, &h.pay_id)
-        .create_payment(&1u64, &PaymentStatus::Released);
-
-    let excursion = make_excursion(1);
+    let unit_id = allocate_one(&h, 1);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    let payment_id = h.coord.get_workflow(&1u64).legs.get(0).unwrap().payment_id;
+
+    let log = h.coord.rollback(&1u64);
+
+    // Units stay with the hospital; only payment and request are compensated.
+    assert_eq!(log.len(), 2);
+    let credit = log.get(0).unwrap();
+    assert_eq!(credit.step, CompensationStep::CreditNote);
+    assert_eq!(credit.target_id, payment_id);
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::Delivered);
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Refunded);
+}
+
+#[test]
+fn test_rollback_rejected_when_unit_compensation_fails() {
+    let h = setup();
+    let unit_id = allocate_one(&h, 1);
+    let payment_id = h.coord.get_workflow(&1u64).legs.get(0).unwrap().payment_id;
+
+    // Unit moved out-of-band; it can no longer be released to Available.
+    MockInventoryContractClient::new(&h.env, &h.inv_id).update_status(
+        &unit_id,
+        &BloodStatus::InTransit,
+        &h.admin,
+        &None,
+    );
+
+    let result = h.coord.try_rollback(&1u64);
+    assert_eq!(result, Err(Ok(CoordinatorError::CompensationFailed)));
+
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Locked);
+    assert_eq!(request_status(&h, 1), RequestStatus::Approved);
+    assert_eq!(h.coord.get_compensation_log(&1u64).len(), 0);
+}
+
+#[test]
+fn test_rollback_twice_rejected() {
+    let h = setup();
+    allocate_one(&h, 1);
+    h.coord.rollback(&1u64);
+
+    let result = h.coord.try_rollback(&1u64);
+    assert_eq!(result, Err(Ok(CoordinatorError::InvalidWorkflowState)));
+}
+
+#[test]
+fn test_rollback_uninitialized_returns_not_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let coord = CoordinatorContractClient::new(&env, &env.register(CoordinatorContract, ()));
+
+    assert_eq!(
+        coord.try_rollback(&1u64),
+        Err(Ok(CoordinatorError::NotInitialized))
+    );
+    assert_eq!(
+        coord.try_rollback_leg(&1u64, &0u32),
+        Err(Ok(CoordinatorError::NotInitialized))
+    );
+}
+
+#[test]
+fn test_rollback_skips_request_already_cancelled() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+
+    h.coord
+        .allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    MockRequestContractClient::new(&h.env, &h.req_id).seed_request(
+        &1u64,
+        &h.hospital,
+        &RequestStatus::Cancelled,
+    );
+    MockRequestContractClient::new(&h.env, &h.req_id).reject_updates();
+
+    h.coord.rollback(&1u64);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::RolledBack
+    );
+}
+
+// ── Request status propagation failures ───────────────────────────────────────
+
+#[test]
+fn test_allocate_fails_when_request_update_fails() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+    MockRequestContractClient::new(&h.env, &h.req_id).reject_updates();
+
+    let result = h
+        .coord
+        .try_allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    assert_eq!(result, Err(Ok(CoordinatorError::RequestUpdateFailed)));
+
+    // Nothing from the failed step is persisted
+    assert!(h.coord.try_get_workflow(&1u64).is_err());
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::Available);
+}
+
+#[test]
+fn test_confirm_delivery_fails_when_request_update_fails() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+
+    h.coord
+        .allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    dispatch_units(&h, 1);
+    MockRequestContractClient::new(&h.env, &h.req_id).reject_updates();
+
+    let result = h.coord.try_confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(result, Err(Ok(CoordinatorError::RequestUpdateFailed)));
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Dispatched
+    );
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::InTransit);
+}
+
+#[test]
+fn test_rollback_fails_when_request_update_fails() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+
+    h.coord
+        .allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    MockRequestContractClient::new(&h.env, &h.req_id).reject_updates();
+
+    let result = h.coord.try_rollback(&1u64);
+    assert_eq!(result, Err(Ok(CoordinatorError::CompensationFailed)));
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Locked);
+}
+
+#[test]
+fn test_rollback_blocked_after_settlement() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+
+    h.coord.allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    h.coord.settle_payment(&1u64, &h.admin);
+
+    let result = h.coord.try_rollback(&1u64);
+    assert_eq!(result, Err(Ok(CoordinatorError::CannotRollbackSettled)));
+}
+
+// ── Deployment registry ───────────────────────────────────────────────────────
+
+#[test]
+fn test_peers_resolve_through_registry() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    seed_pending_request(&h, 2);
+    let registry_id = h.env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&h.env, &registry_id);
+    registry.initialize(&h.admin);
+    h.coord.set_registry(&h.admin, &registry_id);
+
+    // Only inventory is registered; the other peers fall back to local addresses.
+    let new_inv_id = h.env.register(MockInventoryContract, ());
+    let new_inv = MockInventoryContractClient::new(&h.env, &new_inv_id);
+    new_inv.initialize(&h.admin);
+    let inventory = soroban_sdk::Symbol::new(&h.env, "inventory");
+    registry.register_service(&inventory, &new_inv_id);
+
+    let unit_id = new_inv.register_unit();
+    let payment_id = create_locked_payment(&h, 1);
+    h.coord
+        .allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    assert_eq!(
+        new_inv.get_blood_unit(&unit_id).status,
+        BloodStatus::Reserved
+    );
+
+    // Rotating the service back redirects the coordinator without touching it.
+    registry.rotate_service(&inventory, &h.inv_id);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 2);
+    h.coord
+        .allocate_units(&2u64, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    let old_inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    assert_eq!(
+        old_inv.get_blood_unit(&unit_id).status,
+        BloodStatus::Reserved
+    );
+    assert_eq!(registry.get_service_history(&inventory).len(), 2);
+}
+
+// ── Multi-bank legs ───────────────────────────────────────────────────────────
+
+struct TwoLegs {
+    unit_a: u64,
+    unit_b: u64,
+    payment_a: u64,
+    payment_b: u64,
+}
+
+/// Allocate `request_id` across two banks, one unit and one payment each.
+fn allocate_two_banks(h: &Harness, request_id: u64) -> TwoLegs {
+    seed_pending_request(h, request_id);
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    let bank_a = Address::generate(&h.env);
+    let bank_b = Address::generate(&h.env);
+    let unit_a = inv.register_unit_for(&bank_a);
+    let unit_b = inv.register_unit_for(&bank_b);
+    let payment_a = create_payment_to(h, request_id, &bank_a, PaymentStatus::Locked);
+    let payment_b = create_payment_to(h, request_id, &bank_b, PaymentStatus::Locked);
+    h.coord.allocate_legs(
+        &request_id,
+        &vec![
+            &h.env,
+            LegAllocation {
+                unit_ids: vec![&h.env, unit_a],
+                payment_id: payment_a,
+            },
+            LegAllocation {
+                unit_ids: vec![&h.env, unit_b],
+                payment_id: payment_b,
+            },
+        ],
+        &h.admin,
+    );
+    TwoLegs {
+        unit_a,
+        unit_b,
+        payment_a,
+        payment_b,
+    }
+}
+
+fn payment_status(h: &Harness, payment_id: u64) -> PaymentStatus {
+    MockPaymentContractClient::new(&h.env, &h.pay_id)
+        .get_payment(&payment_id)
+        .status
+}
+
+#[test]
+fn test_multi_bank_workflow_completes_only_when_all_legs_do() {
+    let h = setup();
+    let legs = allocate_two_banks(&h, 1);
+    let eta = h.env.ledger().timestamp() + 3600;
+
+    let wf = h.coord.get_workflow(&1u64);
+    assert_eq!(wf.legs.len(), 2);
+    assert_eq!(wf.banks.len(), 2);
+    assert_eq!(wf.unit_ids, vec![&h.env, legs.unit_a, legs.unit_b]);
+
+    h.coord.dispatch_leg(&1u64, &0, &h.rider, &eta);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+    h.coord.dispatch_leg(&1u64, &1, &h.rider, &eta);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Dispatched
+    );
+
+    h.coord.confirm_leg_delivery(&1u64, &0, &h.hospital);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Dispatched
+    );
+    assert_eq!(request_status(&h, 1), RequestStatus::Approved);
+
+    // Settlement waits for every leg.
+    let result = h.coord.try_settle_payment(&1u64, &h.admin);
+    assert_eq!(result, Err(Ok(CoordinatorError::DeliveryNotConfirmed)));
+    let result = h.coord.try_settle_leg(&1u64, &1, &h.admin);
+    assert_eq!(result, Err(Ok(CoordinatorError::DeliveryNotConfirmed)));
+
+    h.coord.confirm_leg_delivery(&1u64, &1, &h.hospital);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Delivered
+    );
+    assert_eq!(request_status(&h, 1), RequestStatus::Fulfilled);
+
+    h.coord.settle_leg(&1u64, &0, &h.admin);
+    assert_eq!(payment_status(&h, legs.payment_a), PaymentStatus::Released);
+    assert_eq!(payment_status(&h, legs.payment_b), PaymentStatus::Locked);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Delivered
+    );
+
+    h.coord.settle_payment(&1u64, &h.admin);
+    assert_eq!(payment_status(&h, legs.payment_b), PaymentStatus::Released);
+    assert_eq!(h.coord.get_workflow(&1u64).status, WorkflowStatus::Settled);
+}
+
+#[test]
+fn test_allocate_legs_rejects_invalid_legs() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    let bank_a = Address::generate(&h.env);
+    let bank_b = Address::generate(&h.env);
+    let a1 = inv.register_unit_for(&bank_a);
+    let a2 = inv.register_unit_for(&bank_a);
+    let b1 = inv.register_unit_for(&bank_b);
+    create_payment_to(&h, 1, &bank_a, PaymentStatus::Locked);
+    create_payment_to(&h, 1, &bank_a, PaymentStatus::Locked);
+    let leg = |units: Vec<u64>, payment_id: u64| LegAllocation {
+        unit_ids: units,
+        payment_id,
+    };
+
+    let cases = [
+        Vec::new(&h.env),
+        vec![&h.env, leg(Vec::new(&h.env), 1)],
+        vec![&h.env, leg(vec![&h.env, a1, b1], 1)],
+        vec![&h.env, leg(vec![&h.env, a1], 1), leg(vec![&h.env, a2], 2)],
+        vec![&h.env, leg(vec![&h.env, a1], 1), leg(vec![&h.env, b1], 1)],
+    ];
+    for legs in cases {
+        let result = h.coord.try_allocate_legs(&1u64, &legs, &h.admin);
+        assert_eq!(result, Err(Ok(CoordinatorError::InvalidLegs)));
+    }
+
+    let result = h
+        .coord
+        .try_allocate_units(&1u64, &vec![&h.env, a1, b1], &1u64, &h.admin);
+    assert_eq!(result, Err(Ok(CoordinatorError::InvalidLegs)));
+}
+
+#[test]
+fn test_allocate_rejects_payment_for_another_request_payer_or_bank() {
+    let h = setup();
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let pay = MockPaymentContractClient::new(&h.env, &h.pay_id);
+    let stranger = Address::generate(&h.env);
+    let payments = [
+        create_locked_payment(&h, 2),
+        pay.create_payment(&1u64, &stranger, &h.admin, &PaymentStatus::Locked),
+        create_payment_to(&h, 1, &stranger, PaymentStatus::Locked),
+    ];
+    for payment_id in payments {
+        assert_eq!(
+            h.coord
+                .try_allocate_units(&1u64, &vec![&h.env, unit_id], &payment_id, &h.admin),
+            Err(Ok(CoordinatorError::PaymentMismatch))
+        );
+    }
+    assert_eq!(
+        h.coord
+            .try_allocate_units(&1u64, &vec![&h.env, unit_id], &99u64, &h.admin),
+        Err(Ok(CoordinatorError::PaymentNotFound))
+    );
+
+    h.coord.allocate_units(
+        &1u64,
+        &vec![&h.env, unit_id],
+        &create_locked_payment(&h, 1),
+        &h.admin,
+    );
+}
+
+#[test]
+fn test_rollback_leg_leaves_other_legs_to_complete() {
+    let h = setup();
+    let legs = allocate_two_banks(&h, 1);
+    dispatch_units(&h, 1);
+    h.coord.confirm_leg_delivery(&1u64, &0, &h.hospital);
+
+    let log = h.coord.rollback_leg(&1u64, &1);
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.get(0).unwrap().step, CompensationStep::RecallUnit);
+    assert_eq!(log.get(1).unwrap().step, CompensationStep::RefundPayment);
+
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    assert_eq!(
+        inv.get_blood_unit(&legs.unit_b).status,
+        BloodStatus::Compromised
+    );
+    assert_eq!(
+        inv.get_blood_unit(&legs.unit_a).status,
+        BloodStatus::Delivered
+    );
+    assert_eq!(payment_status(&h, legs.payment_b), PaymentStatus::Refunded);
+
+    // The remaining leg was already delivered, so the workflow is too.
+    let wf = h.coord.get_workflow(&1u64);
+    assert_eq!(wf.status, WorkflowStatus::Delivered);
+    assert_eq!(wf.legs.get(1).unwrap().status, WorkflowStatus::RolledBack);
+    assert_eq!(request_status(&h, 1), RequestStatus::Fulfilled);
+
+    h.coord.settle_payment(&1u64, &h.admin);
+    assert_eq!(h.coord.get_workflow(&1u64).status, WorkflowStatus::Settled);
+    assert_eq!(payment_status(&h, legs.payment_a), PaymentStatus::Released);
+
+    let result = h.coord.try_rollback_leg(&1u64, &0);
+    assert_eq!(result, Err(Ok(CoordinatorError::CannotRollbackSettled)));
+    let result = h.coord.try_rollback_leg(&1u64, &2);
+    assert_eq!(result, Err(Ok(CoordinatorError::LegNotFound)));
+}
+
+#[test]
+fn test_rolling_back_last_leg_cancels_request() {
+    let h = setup();
+    allocate_two_banks(&h, 1);
+
+    h.coord.rollback_leg(&1u64, &0);
+    assert_eq!(request_status(&h, 1), RequestStatus::Approved);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+
+    let log = h.coord.rollback_leg(&1u64, &1);
+    assert_eq!(
+        log.get(log.len() - 1).unwrap().step,
+        CompensationStep::CancelRequest
+    );
+    assert_eq!(request_status(&h, 1), RequestStatus::Cancelled);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::RolledBack
+    );
+    // Both legs' compensations are kept.
+    assert_eq!(h.coord.get_compensation_log(&1u64).len(), 5);
+}
+
+#[test]
+fn test_settle_partial_refunds_leg_without_accepted_units() {
+    let h = setup();
+    let legs = allocate_two_banks(&h, 1);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+
+    let released = h
+        .coord
+        .settle_partial(&1u64, &h.admin, &vec![&h.env, legs.unit_a]);
+    assert_eq!(released, 1_000);
+    assert_eq!(payment_status(&h, legs.payment_a), PaymentStatus::Released);
+    assert_eq!(payment_status(&h, legs.payment_b), PaymentStatus::Refunded);
+    assert_eq!(h.coord.get_workflow(&1u64).status, WorkflowStatus::Settled);
+}
+
+// ── Settlement ────────────────────────────────────────────────────────────────
+
+/// Allocate two units to `request_id` and take the workflow through delivery.
+fn deliver_two(h: &Harness, request_id: u64) -> (u64, u64, u64) {
+    seed_pending_request(h, request_id);
+    let unit_a = register_unit(h);
+    let unit_b = register_unit(h);
+    let payment_id = create_locked_payment(h, request_id);
+    h.coord.allocate_units(
+        &request_id,
+        &vec![&h.env, unit_a, unit_b],
+        &payment_id,
+        &h.admin,
+    );
+    dispatch_units(h, request_id);
+    h.coord.confirm_delivery(&request_id, &h.hospital);
+    (unit_a, unit_b, payment_id)
+}
+
+#[test]
+fn test_settle_partial_pays_for_accepted_units_only() {
+    let h = setup();
+    let (unit_a, _, payment_id) = deliver_two(&h, 1);
+
+    let released = h
+        .coord
+        .settle_partial(&1u64, &h.admin, &vec![&h.env, unit_a]);
+    assert_eq!(released, 500);
+
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Released);
+    assert_eq!(payment.released_amount, 500);
+    assert_eq!(h.coord.get_workflow(&1u64).status, WorkflowStatus::Settled);
+}
+
+#[test]
+fn test_settle_partial_with_every_unit_releases_in_full() {
+    let h = setup();
+    let (unit_a, unit_b, payment_id) = deliver_two(&h, 1);
+
+    let released = h
+        .coord
+        .settle_partial(&1u64, &h.admin, &vec![&h.env, unit_b, unit_a]);
+    assert_eq!(released, 1_000);
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.released_amount, 1_000);
+}
+
+#[test]
+fn test_settle_partial_restricted_to_hospital_or_admin() {
+    let h = setup();
+    let (unit_a, _, payment_id) = deliver_two(&h, 1);
+
+    let stranger = Address::generate(&h.env);
+    assert_eq!(
+        h.coord
+            .try_settle_partial(&1u64, &stranger, &vec![&h.env, unit_a]),
+        Err(Ok(CoordinatorError::Unauthorized))
+    );
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Locked);
+
+    let released = h
+        .coord
+        .settle_partial(&1u64, &h.hospital, &vec![&h.env, unit_a]);
+    assert_eq!(released, 500);
+}
+
+#[test]
+fn test_settle_partial_rejects_invalid_unit_selection() {
+    let h = setup();
+    let (unit_a, _, _) = deliver_two(&h, 1);
+    let foreign = register_unit(&h);
+
+    for units in [
+        Vec::new(&h.env),
+        vec![&h.env, foreign],
+        vec![&h.env, unit_a, unit_a],
+    ] {
+        let result = h.coord.try_settle_partial(&1u64, &h.admin, &units);
+        assert_eq!(result, Err(Ok(CoordinatorError::InvalidSettlementUnits)));
+    }
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Delivered
+    );
+}
+
+#[test]
+fn test_settlement_moves_escrowed_tokens() {
+    use payment_contract::{PaymentContract, PaymentContractClient};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let pay_id = env.register(PaymentContract, ());
+    let h = setup_with_payments(env, pay_id);
+    let env = &h.env;
+
+    let bank = Address::generate(env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(h.admin.clone())
+        .address();
+    soroban_sdk::token::StellarAssetClient::new(env, &token_id).mint(&h.hospital, &1_000);
+
+    let payments = PaymentContractClient::new(env, &h.pay_id);
+    payments.initialize(&h.admin, &None);
+    let payment_id = payments.create_escrow(&1u64, &h.hospital, &bank, &1_000, &token_id);
+
+    seed_pending_request(&h, 1);
+    let unit_id = MockInventoryContractClient::new(env, &h.inv_id).register_unit_for(&bank);
+    h.coord
+        .allocate_units(&1u64, &vec![env, unit_id], &payment_id, &h.admin);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+
+    // The coordinator is not yet an authorized settler.
+    let result = h.coord.try_settle_payment(&1u64, &h.admin);
+    assert_eq!(result, Err(Ok(CoordinatorError::PaymentUpdateFailed)));
+
+    payments.set_coordinator(&h.admin, &h.coord.address);
+    h.coord.settle_payment(&1u64, &h.admin);
+
+    let token = soroban_sdk::token::Client::new(env, &token_id);
+    assert_eq!(token.balance(&bank), 1_000);
+    assert_eq!(token.balance(&h.pay_id), 0);
+    assert_eq!(
+        payments.get_payment(&payment_id).status,
+        payment_contract::PaymentStatus::Released
+    );
+}
+
+#[test]
+fn test_rollback_refunds_escrowed_tokens() {
+    use payment_contract::{PaymentContract, PaymentContractClient};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let pay_id = env.register(PaymentContract, ());
+    let h = setup_with_payments(env, pay_id);
+    let env = &h.env;
+
+    let bank = Address::generate(env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(h.admin.clone())
+        .address();
+    soroban_sdk::token::StellarAssetClient::new(env, &token_id).mint(&h.hospital, &1_000);
+
+    let payments = PaymentContractClient::new(env, &h.pay_id);
+    payments.initialize(&h.admin, &None);
+    payments.set_coordinator(&h.admin, &h.coord.address);
+    let payment_id = payments.create_escrow(&1u64, &h.hospital, &bank, &1_000, &token_id);
+
+    seed_pending_request(&h, 1);
+    let unit_id = MockInventoryContractClient::new(env, &h.inv_id).register_unit_for(&bank);
+    h.coord
+        .allocate_units(&1u64, &vec![env, unit_id], &payment_id, &h.admin);
+    h.coord.rollback(&1u64);
+
+    let token = soroban_sdk::token::Client::new(env, &token_id);
+    assert_eq!(token.balance(&h.hospital), 1_000);
+    assert_eq!(token.balance(&h.pay_id), 0);
+    assert_eq!(
+        payments.get_payment(&payment_id).status,
+        payment_contract::PaymentStatus::Refunded
+    );
+}
+
+#[test]
+fn test_settle_partial_pays_from_remaining_escrow() {
+    use payment_contract::{MilestoneCondition, PaymentContract, PaymentContractClient};
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let pay_id = env.register(PaymentContract, ());
+    let h = setup_with_payments(env, pay_id);
+    let env = &h.env;
+
+    let bank = Address::generate(env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(h.admin.clone())
+        .address();
+    soroban_sdk::token::StellarAssetClient::new(env, &token_id).mint(&h.hospital, &1_000);
+
+    let payments = PaymentContractClient::new(env, &h.pay_id);
+    payments.initialize(&h.admin, &None);
+    payments.set_coordinator(&h.admin, &h.coord.address);
+    let payment_id = payments.create_escrow(&1u64, &h.hospital, &bank, &1_000, &token_id);
+    payments.set_milestones(
+        &h.admin,
+        &payment_id,
+        &vec![
+            env,
+            (400i128, MilestoneCondition::Manual),
+            (600i128, MilestoneCondition::Manual),
+        ],
+    );
+    payments.release_milestone(&h.admin, &payment_id, &0);
+
+    seed_pending_request(&h, 1);
+    let inv = MockInventoryContractClient::new(env, &h.inv_id);
+    let unit_a = inv.register_unit_for(&bank);
+    let unit_b = inv.register_unit_for(&bank);
+    h.coord
+        .allocate_units(&1u64, &vec![env, unit_a, unit_b], &payment_id, &h.admin);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+
+    // Half of the 600 still in escrow, not half of the original 1 000.
+    let released = h
+        .coord
+        .settle_partial(&1u64, &h.hospital, &vec![env, unit_a]);
+    assert_eq!(released, 300);
+
+    let token = soroban_sdk::token::Client::new(env, &token_id);
+    assert_eq!(token.balance(&bank), 700);
+    assert_eq!(token.balance(&h.hospital), 300);
+    assert_eq!(token.balance(&h.pay_id), 0);
+}
+
+// ── Timeline and queries ──────────────────────────────────────────────────────
+
+fn no_filter(env: &Env) -> WorkflowFilter {
+    WorkflowFilter {
+        statuses: Vec::new(env),
+        hospital: None,
+        bank: None,
+    }
+}
+
+#[test]
+fn test_timeline_records_each_transition_and_actor() {
+    let h = setup();
+    h.env.ledger().set_timestamp(100);
+    allocate_one(&h, 1);
+    h.env.ledger().set_timestamp(200);
+    dispatch_units(&h, 1);
+    h.env.ledger().set_timestamp(300);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    h.env.ledger().set_timestamp(400);
+    h.coord.settle_payment(&1u64, &h.admin);
+
+    let t = h.coord.get_workflow_timeline(&1u64);
+    assert_eq!((t.allocated_at, t.allocated_by), (100, h.admin.clone()));
+    assert_eq!(t.dispatched_at, Some(200));
+    assert_eq!(t.dispatched_by, Some(h.rider.clone()));
+    assert_eq!(t.delivered_at, Some(300));
+    assert_eq!(t.delivered_by, Some(h.hospital.clone()));
+    assert_eq!(t.settled_at, Some(400));
+    assert_eq!(t.settled_by, Some(h.admin.clone()));
+    assert_eq!(t.rolled_back_at, None);
+}
+
+#[test]
+fn test_list_workflows_by_status_hospital_and_bank() {
+    let h = setup();
+    let bank_a = Address::generate(&h.env);
+    let bank_b = Address::generate(&h.env);
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+
+    for (request_id, bank) in [(1u64, &bank_a), (2, &bank_b), (3, &bank_a)] {
+        seed_pending_request(&h, request_id);
+        let unit_id = inv.register_unit_for(bank);
+        let payment_id = create_payment_to(&h, request_id, bank, PaymentStatus::Locked);
+        h.coord
+            .allocate_units(&request_id, &vec![&h.env, unit_id], &payment_id, &h.admin);
+    }
+    dispatch_units(&h, 3);
+
+    let all = h.coord.list_workflows(&no_filter(&h.env), &0, &0);
+    assert_eq!(all.items.len(), 3);
+    assert_eq!(all.next_cursor, None);
+
+    let mut filter = no_filter(&h.env);
+    filter.bank = Some(bank_a.clone());
+    let page = h.coord.list_workflows(&filter, &0, &10);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items.get(0).unwrap().request_id, 1);
+    assert_eq!(page.items.get(1).unwrap().request_id, 3);
+
+    filter.statuses = vec![&h.env, WorkflowStatus::Allocated];
+    let page = h.coord.list_workflows(&filter, &0, &10);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().request_id, 1);
+
+    let mut filter = no_filter(&h.env);
+    filter.hospital = Some(h.hospital.clone());
+    let page = h.coord.list_workflows(&filter, &0, &2);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+    let page = h.coord.list_workflows(&filter, &2, &2);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().request_id, 3);
+    assert_eq!(page.next_cursor, None);
+
+    filter.hospital = Some(bank_b.clone());
+    assert!(h.coord.list_workflows(&filter, &0, &10).items.is_empty());
+}
+
+#[test]
+fn test_status_index_spans_pages_and_stays_dense() {
+    let h = setup();
+    for request_id in 1..=60u64 {
+        allocate_one(&h, request_id);
+    }
+    // Moving request 1 on fills its place with the last allocated one.
+    dispatch_units(&h, 1);
+
+    let mut filter = no_filter(&h.env);
+    filter.statuses = vec![&h.env, WorkflowStatus::Allocated];
+    let page = h.coord.list_workflows(&filter, &0, &50);
+    assert_eq!(page.items.len(), 50);
+    assert_eq!(page.items.get(0).unwrap().request_id, 60);
+    assert_eq!(page.next_cursor, Some(50));
+    let page = h.coord.list_workflows(&filter, &50, &50);
+    assert_eq!(page.items.len(), 9);
+    assert_eq!(page.items.get(8).unwrap().request_id, 59);
+    assert_eq!(page.next_cursor, None);
+
+    filter.statuses = vec![&h.env, WorkflowStatus::Dispatched];
+    let page = h.coord.list_workflows(&filter, &0, &0);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().request_id, 1);
+}
+
+#[test]
+fn test_get_stuck_workflows() {
+    let h = setup();
+    h.env.ledger().set_timestamp(1_000);
+    allocate_one(&h, 1);
+    allocate_one(&h, 2);
+    allocate_one(&h, 3);
+
+    h.env.ledger().set_timestamp(5_000);
+    dispatch_units(&h, 2);
+    h.coord.confirm_delivery(&2u64, &h.hospital);
+    h.coord.rollback(&3u64);
+
+    h.env.ledger().set_timestamp(8_000);
+    // Request 1 allocated 7000s ago; request 2 delivered 3000s ago.
+    let stuck = h.coord.get_stuck_workflows(&3_600u64);
+    assert_eq!(stuck.len(), 1);
+    assert_eq!(stuck.get(0).unwrap().request_id, 1);
+
+    let stuck = h.coord.get_stuck_workflows(&3_000u64);
+    assert_eq!(stuck.len(), 2);
+}
+
+// ── Circuit breaker tests ─────────────────────────────────────────────────────
+
+#[test]
+fn test_coordinator_pause_blocks_allocate_units() {
+    let h = setup();
+    h.coord.pause(&h.admin);
+    assert!(h.coord.is_paused());
+
+    seed_pending_request(&h, 1);
+    let unit_id = register_unit(&h);
+    let pay_id = create_locked_payment(&h, 1);
+
+    let result = h.coord.try_allocate_units(&1u64, &vec![&h.env, unit_id], &pay_id, &h.admin);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_coordinator_pause_allows_get_workflow() {
+    let h = setup();
+
+    // Create a workflow first
+    seed_pending_request(&h, 10);
+    let unit_id = register_unit(&h);
+    let pay_id = create_locked_payment(&h, 10);
+    h.coord.allocate_units(&10u64, &vec![&h.env, unit_id], &pay_id, &h.admin);
+
+    h.coord.pause(&h.admin);
+
+    // Read still works
+    let wf = h.coord.get_workflow(&10u64);
+    assert_eq!(wf.request_id, 10);
+}
+
+#[test]
+fn test_coordinator_unpause_restores_writes() {
+    let h = setup();
+    h.coord.pause(&h.admin);
+    h.coord.unpause(&h.admin);
+    assert!(!h.coord.is_paused());
+
+    seed_pending_request(&h, 20);
+    let unit_id = register_unit(&h);
+    let pay_id = create_locked_payment(&h, 20);
+    h.coord.allocate_units(&20u64, &vec![&h.env, unit_id], &pay_id, &h.admin);
+    assert_eq!(h.coord.get_workflow(&20u64).status, WorkflowStatus::Allocated);
+}
+
+#[test]
+#[should_panic]
+fn test_coordinator_non_admin_cannot_pause() {
+    let h = setup();
+    let attacker = Address::generate(&h.env);
+    h.coord.pause(&attacker);
+}
+
+// ── Temperature excursion → dispute integration tests (issue #477) ────────────
+
+use super::ExcursionSummary;
+
+fn make_excursion(unit_id: u64) -> ExcursionSummary {
+    ExcursionSummary {
+        unit_id,
+        violation_count: 3,
+        peak_celsius_x100: 1200, // 12.00°C — above threshold
+        detected_at: 1000,
+    }
+}
+
+/// Register a breach oracle allowed to call `flag_temperature_breach`.
+fn breach_oracle(h: &Harness) -> Address {
+    let oracle = Address::generate(&h.env);
+    h.coord.set_breach_oracle(&h.admin, &oracle, &true);
+    oracle
+}
+
+/// Full chain: flag_temperature_breach transitions Locked → Disputed.
+#[test]
+fn test_flag_temperature_breach_transitions_locked_to_disputed() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 99);
+
+    let excursion = make_excursion(unit_id);
+    h.coord
+        .flag_temperature_breach(&oracle, &payment_id, &excursion);
+
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(
+        payment.status,
+        PaymentStatus::Disputed,
+        "Payment must be Disputed after temperature breach"
+    );
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::Compromised);
+}
+
+/// flag_temperature_breach on a non-Locked payment returns InvalidPaymentState.
+#[test]
+fn test_flag_temperature_breach_non_locked_payment_fails() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    // Create a Released payment
+    let payment_id = create_payment_to(&h, 1, &h.admin, PaymentStatus::Released);
+
+    let excursion = make_excursion(register_unit(&h));
     let result = h
         .coord
-        .try_flag_temperature_breach(&h.admin, &payment_id, &excursion);
+        .try_flag_temperature_breach(&oracle, &payment_id, &excursion);
     assert_eq!(
         result,
         Err(Ok(CoordinatorError::InvalidPaymentState)),
@@ -503,10 +1846,11 @@ fn test_flag_temperature_breach_non_locked_payment_fails() {
 #[test]
 fn test_flag_temperature_breach_missing_payment_fails() {
     let h = setup();
+    let oracle = breach_oracle(&h);
     let excursion = make_excursion(1);
     let result = h
         .coord
-        .try_flag_temperature_breach(&h.admin, &9999u64, &excursion);
+        .try_flag_temperature_breach(&oracle, &9999u64, &excursion);
     assert_eq!(
         result,
         Err(Ok(CoordinatorError::PaymentNotFound)),
@@ -518,16 +1862,533 @@ fn test_flag_temperature_breach_missing_payment_fails() {
 #[test]
 fn test_flag_temperature_breach_blocked_when_paused() {
     let h = setup();
+    let oracle = breach_oracle(&h);
     let payment_id = create_locked_payment(&h, 1);
     h.coord.pause(&h.admin);
 
     let excursion = make_excursion(1);
     let result = h
         .coord
-        .try_flag_temperature_breach(&h.admin, &payment_id, &excursion);
+        .try_flag_temperature_breach(&oracle, &payment_id, &excursion);
     assert_eq!(result, Err(Ok(CoordinatorError::ContractPaused)));
 
     // Payment must remain Locked
     let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
     assert_eq!(payment.status, PaymentStatus::Locked);
 }
+
+#[test]
+fn test_flag_temperature_breach_restricted_to_temperature_contract_and_oracles() {
+    let h = setup();
+    let unit_id = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 99);
+    let excursion = make_excursion(unit_id);
+
+    for caller in [h.admin.clone(), Address::generate(&h.env)] {
+        let result = h
+            .coord
+            .try_flag_temperature_breach(&caller, &payment_id, &excursion);
+        assert_eq!(result, Err(Ok(CoordinatorError::Unauthorized)));
+    }
+
+    let oracle = breach_oracle(&h);
+    h.coord.set_breach_oracle(&h.admin, &oracle, &false);
+    let result = h
+        .coord
+        .try_flag_temperature_breach(&oracle, &payment_id, &excursion);
+    assert_eq!(result, Err(Ok(CoordinatorError::Unauthorized)));
+
+    let temperature = Address::generate(&h.env);
+    h.coord.set_temperature_contract(&h.admin, &temperature);
+    h.coord
+        .flag_temperature_breach(&temperature, &payment_id, &excursion);
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Disputed);
+}
+
+#[test]
+fn test_flag_temperature_breach_marks_leg_and_removes_unit() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    let unit_id = allocate_one(&h, 1);
+    let payment_id = h.coord.get_workflow(&1u64).legs.get(0).unwrap().payment_id;
+
+    h.coord
+        .flag_temperature_breach(&oracle, &payment_id, &make_excursion(unit_id));
+
+    let wf = h.coord.get_workflow(&1u64);
+    let leg = wf.legs.get(0).unwrap();
+    assert_eq!(leg.breached_units, vec![&h.env, unit_id]);
+    assert!(leg.unit_ids.is_empty());
+    assert!(wf.unit_ids.is_empty());
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    assert_eq!(
+        inv.get_blood_unit(&unit_id).status,
+        BloodStatus::Compromised
+    );
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Disputed);
+
+    // A repeated report for the same unit changes nothing.
+    h.coord
+        .flag_temperature_breach(&oracle, &payment_id, &make_excursion(unit_id));
+    assert_eq!(
+        h.coord
+            .get_workflow(&1u64)
+            .legs
+            .get(0)
+            .unwrap()
+            .breached_units
+            .len(),
+        1
+    );
+
+    // The unit must belong to the payment's leg.
+    let stray = register_unit(&h);
+    let result = h
+        .coord
+        .try_flag_temperature_breach(&oracle, &payment_id, &make_excursion(stray));
+    assert_eq!(result, Err(Ok(CoordinatorError::UnitNotInWorkflow)));
+}
+
+#[test]
+fn test_flag_temperature_breach_rematches_dispatched_unit() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    let matching_id = h.env.register(MockMatchingContract, ());
+    h.coord
+        .set_matching_contract(&h.admin, &Some(matching_id.clone()));
+
+    let unit_id = allocate_one(&h, 1);
+    dispatch_units(&h, 1);
+    let replacement = register_unit(&h);
+    MockMatchingContractClient::new(&h.env, &matching_id).set_replacement(&replacement);
+    let payment_id = h.coord.get_workflow(&1u64).legs.get(0).unwrap().payment_id;
+
+    h.coord
+        .flag_temperature_breach(&oracle, &payment_id, &make_excursion(unit_id));
+
+    let wf = h.coord.get_workflow(&1u64);
+    let leg = wf.legs.get(0).unwrap();
+    assert_eq!(leg.unit_ids, vec![&h.env, replacement]);
+    assert_eq!(wf.unit_ids, vec![&h.env, replacement]);
+    assert_eq!(leg.breached_units, vec![&h.env, unit_id]);
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    assert_eq!(
+        inv.get_blood_unit(&unit_id).status,
+        BloodStatus::Compromised
+    );
+    assert_eq!(
+        inv.get_blood_unit(&replacement).status,
+        BloodStatus::InTransit
+    );
+
+    // The request follows the swap and the escrow stays settleable.
+    assert_eq!(
+        MockRequestContractClient::new(&h.env, &h.req_id).get_assigned_units(&1u64),
+        vec![&h.env, replacement]
+    );
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Locked);
+
+    // The replacement is delivered with the rest of the leg.
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    assert_eq!(
+        inv.get_blood_unit(&replacement).status,
+        BloodStatus::Delivered
+    );
+    h.coord.settle_payment(&1u64, &h.hospital);
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Released);
+    assert_eq!(h.coord.get_workflow(&1u64).status, WorkflowStatus::Settled);
+}
+
+#[test]
+fn test_breached_leg_settles_once_dispute_is_resolved() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    seed_pending_request(&h, 1);
+    let hot = register_unit(&h);
+    let kept = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+    h.coord
+        .allocate_units(&1u64, &vec![&h.env, hot, kept], &payment_id, &h.admin);
+    dispatch_units(&h, 1);
+
+    h.coord
+        .flag_temperature_breach(&oracle, &payment_id, &make_excursion(hot));
+    assert_eq!(
+        MockRequestContractClient::new(&h.env, &h.req_id).get_assigned_units(&1u64),
+        vec![&h.env, kept]
+    );
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+
+    // An open dispute still blocks settlement.
+    assert_eq!(
+        h.coord.try_settle_payment(&1u64, &h.hospital),
+        Err(Ok(CoordinatorError::InvalidPaymentState))
+    );
+
+    MockPaymentContractClient::new(&h.env, &h.pay_id).resolve_dispute(&payment_id, &500);
+    h.coord.settle_payment(&1u64, &h.hospital);
+    let wf = h.coord.get_workflow(&1u64);
+    assert_eq!(wf.status, WorkflowStatus::Settled);
+    assert_eq!(wf.legs.get(0).unwrap().status, WorkflowStatus::Settled);
+}
+
+#[test]
+fn test_leg_emptied_by_breaches_refunds_on_settle() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    let unit_id = allocate_one(&h, 1);
+    dispatch_units(&h, 1);
+    let payment_id = h.coord.get_workflow(&1u64).legs.get(0).unwrap().payment_id;
+
+    h.coord
+        .flag_temperature_breach(&oracle, &payment_id, &make_excursion(unit_id));
+    assert!(h
+        .coord
+        .get_workflow(&1u64)
+        .legs
+        .get(0)
+        .unwrap()
+        .unit_ids
+        .is_empty());
+
+    // The dispute is dropped and the escrow locked again before settling.
+    MockPaymentContractClient::new(&h.env, &h.pay_id).update_status(
+        &h.admin,
+        &payment_id,
+        &PaymentStatus::Locked,
+    );
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    h.coord.settle_payment(&1u64, &h.hospital);
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Refunded);
+    assert_eq!(h.coord.get_workflow(&1u64).status, WorkflowStatus::Settled);
+}
+
+#[test]
+fn test_flag_temperature_breach_rejects_delivered_unit() {
+    let h = setup();
+    let oracle = breach_oracle(&h);
+    let unit_id = allocate_one(&h, 1);
+    dispatch_units(&h, 1);
+    h.coord.confirm_delivery(&1u64, &h.hospital);
+    let payment_id = h.coord.get_workflow(&1u64).legs.get(0).unwrap().payment_id;
+
+    assert_eq!(
+        h.coord
+            .try_flag_temperature_breach(&oracle, &payment_id, &make_excursion(unit_id)),
+        Err(Ok(CoordinatorError::UnitAlreadyDelivered))
+    );
+
+    let leg = h.coord.get_workflow(&1u64).legs.get(0).unwrap();
+    assert_eq!(leg.unit_ids, vec![&h.env, unit_id]);
+    assert!(leg.breached_units.is_empty());
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Locked);
+}
+
+// ── SLA enforcement ───────────────────────────────────────────────────────────
+
+/// Register a mock reputation contract with the admin's bank as entity 7.
+fn setup_reputation<'a>(h: &'a Harness) -> MockReputationContractClient<'a> {
+    let rep_id = h.env.register(MockReputationContract, ());
+    h.coord.set_reputation_contract(&h.admin, &rep_id);
+    h.coord.set_bank_entity(&h.admin, &h.admin, &7u64);
+    MockReputationContractClient::new(&h.env, &rep_id)
+}
+
+fn sla(dispatch_within_secs: u64, delivery_grace_secs: u64) -> SlaConfig {
+    SlaConfig {
+        dispatch_within_secs,
+        delivery_grace_secs,
+    }
+}
+
+#[test]
+fn test_enforce_slas_rolls_back_undispatched_allocation() {
+    let h = setup();
+    let rep = setup_reputation(&h);
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(600, 0));
+    h.env.ledger().set_timestamp(1_000);
+    let unit_id = allocate_one(&h, 1);
+
+    h.env.ledger().set_timestamp(1_600);
+    assert!(h.coord.enforce_slas(&10).is_empty());
+
+    h.env.ledger().set_timestamp(1_601);
+    assert_eq!(h.coord.enforce_slas(&10), vec![&h.env, 1u64]);
+
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::RolledBack
+    );
+    let unit = MockInventoryContractClient::new(&h.env, &h.inv_id).get_blood_unit(&unit_id);
+    assert_eq!(unit.status, BloodStatus::Available);
+    assert_eq!(request_status(&h, 1), RequestStatus::Cancelled);
+    assert_eq!(
+        h.coord.get_workflow_timeline(&1u64).rolled_back_by,
+        Some(h.coord.address.clone())
+    );
+    assert_eq!(rep.breaches(), vec![&h.env, 7u64]);
+}
+
+#[test]
+fn test_enforce_slas_skips_leg_whose_compensation_fails() {
+    let h = setup();
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(600, 0));
+    h.env.ledger().set_timestamp(1_000);
+    let stuck_unit = allocate_one(&h, 1);
+    allocate_one(&h, 2);
+    // Inventory refuses to release a delivered unit, so request 1 cannot be
+    // compensated.
+    MockInventoryContractClient::new(&h.env, &h.inv_id).update_status(
+        &stuck_unit,
+        &BloodStatus::Delivered,
+        &h.admin,
+        &None,
+    );
+
+    h.env.ledger().set_timestamp(1_601);
+    assert_eq!(h.coord.enforce_slas(&10), vec![&h.env, 2u64]);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+    assert_eq!(
+        h.coord.get_workflow(&2u64).status,
+        WorkflowStatus::RolledBack
+    );
+}
+
+#[test]
+fn test_enforce_slas_leaves_partly_releasable_leg_untouched() {
+    let h = setup();
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(600, 0));
+    h.env.ledger().set_timestamp(1_000);
+    seed_pending_request(&h, 1);
+    let free_unit = register_unit(&h);
+    let stuck_unit = register_unit(&h);
+    let payment_id = create_locked_payment(&h, 1);
+    h.coord.allocate_units(
+        &1u64,
+        &vec![&h.env, free_unit, stuck_unit],
+        &payment_id,
+        &h.admin,
+    );
+    let inv = MockInventoryContractClient::new(&h.env, &h.inv_id);
+    inv.update_status(&stuck_unit, &BloodStatus::Delivered, &h.admin, &None);
+
+    h.env.ledger().set_timestamp(1_601);
+    assert!(h.coord.enforce_slas(&10).is_empty());
+    assert_eq!(inv.get_blood_unit(&free_unit).status, BloodStatus::Reserved);
+    assert_eq!(payment_status(&h, payment_id), PaymentStatus::Locked);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+}
+
+#[test]
+fn test_enforce_slas_skips_late_leg_whose_dispute_fails() {
+    let h = setup();
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(600, 300));
+    h.env.ledger().set_timestamp(1_000);
+    allocate_one(&h, 1);
+    dispatch_units(&h, 1); // ETA t=4600
+    allocate_one(&h, 2);
+    MockPaymentContractClient::new(&h.env, &h.pay_id).reject_disputes();
+
+    h.env.ledger().set_timestamp(4_901);
+    assert_eq!(h.coord.enforce_slas(&10), vec![&h.env, 2u64]);
+    let leg = h.coord.get_workflow(&1u64).legs.get(0).unwrap();
+    assert!(!leg.sla_breached);
+    assert_eq!(payment_status(&h, leg.payment_id), PaymentStatus::Locked);
+}
+
+#[test]
+fn test_enforce_slas_counts_every_workflow_examined() {
+    let h = setup();
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(600, 0));
+    h.env.ledger().set_timestamp(1_500);
+    allocate_one(&h, 1);
+    h.env.ledger().set_timestamp(1_000);
+    allocate_one(&h, 2);
+
+    // Request 1 is not due yet but still uses up the sweep.
+    h.env.ledger().set_timestamp(1_601);
+    assert!(h.coord.enforce_slas(&1).is_empty());
+    assert_eq!(h.coord.enforce_slas(&1), vec![&h.env, 2u64]);
+    assert_eq!(
+        h.coord.get_workflow(&1u64).status,
+        WorkflowStatus::Allocated
+    );
+}
+
+#[test]
+fn test_enforce_slas_uses_request_deadline_without_config() {
+    let h = setup();
+    h.env.ledger().set_timestamp(100);
+    allocate_one(&h, 1);
+
+    // The mock request is required by t=3600.
+    h.env.ledger().set_timestamp(3_600);
+    assert!(h.coord.enforce_slas(&10).is_empty());
+    h.env.ledger().set_timestamp(3_601);
+    assert_eq!(h.coord.enforce_slas(&10), vec![&h.env, 1u64]);
+}
+
+#[test]
+fn test_enforce_slas_flags_late_delivery_once() {
+    let h = setup();
+    let rep = setup_reputation(&h);
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(600, 300));
+    h.env.ledger().set_timestamp(1_000);
+    allocate_one(&h, 1);
+    dispatch_units(&h, 1); // ETA t=4600
+
+    h.env.ledger().set_timestamp(4_900);
+    assert!(h.coord.enforce_slas(&10).is_empty());
+
+    h.env.ledger().set_timestamp(4_901);
+    assert_eq!(h.coord.enforce_slas(&10), vec![&h.env, 1u64]);
+
+    let wf = h.coord.get_workflow(&1u64);
+    assert_eq!(wf.status, WorkflowStatus::Dispatched);
+    let leg = wf.legs.get(0).unwrap();
+    assert!(leg.sla_breached);
+    let payment = MockPaymentContractClient::new(&h.env, &h.pay_id).get_payment(&leg.payment_id);
+    assert_eq!(payment.status, PaymentStatus::Disputed);
+    assert_eq!(rep.breaches(), vec![&h.env, 7u64]);
+
+    // Already flagged: a second sweep leaves it alone.
+    assert!(h.coord.enforce_slas(&10).is_empty());
+    assert_eq!(rep.breaches().len(), 1);
+}
+
+#[test]
+fn test_enforce_slas_stops_at_max() {
+    let h = setup();
+    h.coord
+        .set_sla_config(&h.admin, &Urgency::Urgent, &sla(10, 0));
+    h.env.ledger().set_timestamp(1_000);
+    for request_id in 1..=3u64 {
+        allocate_one(&h, request_id);
+    }
+
+    h.env.ledger().set_timestamp(2_000);
+    assert_eq!(h.coord.enforce_slas(&2), vec![&h.env, 1u64, 2]);
+    assert_eq!(h.coord.enforce_slas(&2), vec![&h.env, 3u64]);
+}
+
+// ── Upgrades ──────────────────────────────────────────────────────────────────
+
+/// Put the workflow indexes back in the schema 1 layout of one `Vec` each.
+fn rewind_to_schema_v1(h: &Harness, request_ids: &[u64]) {
+    h.env.as_contract(&h.coord.address, || {
+        let storage = h.env.storage().persistent();
+        for &id in request_ids {
+            let wf: WorkflowRecord = storage.get(&DataKey::Workflow(id)).unwrap();
+            let mut legacy = vec![
+                &h.env,
+                (DataKey::AllWorkflows, WorkflowIndex::All),
+                (
+                    DataKey::StatusIndex(wf.status),
+                    WorkflowIndex::Status(wf.status),
+                ),
+                (
+                    DataKey::HospitalIndex(wf.hospital.clone()),
+                    WorkflowIndex::Hospital(wf.hospital.clone()),
+                ),
+            ];
+            for bank in wf.banks.iter() {
+                legacy.push_back((DataKey::BankIndex(bank.clone()), WorkflowIndex::Bank(bank)));
+            }
+            for (key, index) in legacy.iter() {
+                let mut ids: Vec<u64> = storage.get(&key).unwrap_or(Vec::new(&h.env));
+                ids.push_back(id);
+                storage.set(&key, &ids);
+                storage.remove(&DataKey::IndexPages(index.clone()));
+                storage.remove(&DataKey::IndexPage(index.clone(), 0));
+                storage.remove(&DataKey::IndexSlot(index, id));
+            }
+        }
+        h.env
+            .storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &1u32);
+    });
+}
+
+#[test]
+fn test_upgrade_keeps_workflows_and_migrate_is_admin_only() {
+    let h = setup();
+    allocate_one(&h, 1);
+    let hash = upload_placeholder_wasm(&h.env);
+    let stranger = Address::generate(&h.env);
+    assert_eq!(
+        h.coord.try_upgrade(&stranger, &hash),
+        Err(Ok(CoordinatorError::Unauthorized))
+    );
+
+    h.coord.upgrade(&h.admin, &hash);
+    h.env.register_at(&h.coord.address, CoordinatorContract, ());
+
+    assert_eq!(h.coord.get_workflow(&1).status, WorkflowStatus::Allocated);
+    assert_eq!(h.coord.schema_version(), 2);
+    assert_eq!(
+        h.coord.try_migrate(&stranger, &2),
+        Err(Ok(CoordinatorError::Unauthorized))
+    );
+    assert!(h.coord.migrate(&h.admin, &2));
+}
+
+#[test]
+fn test_migrate_moves_workflow_indexes_into_pages() {
+    let h = setup();
+    for request_id in 1..=3u64 {
+        allocate_one(&h, request_id);
+    }
+    dispatch_units(&h, 2);
+    rewind_to_schema_v1(&h, &[1, 2, 3]);
+    assert!(h
+        .coord
+        .list_workflows(&no_filter(&h.env), &0, &0)
+        .items
+        .is_empty());
+
+    assert_eq!(
+        h.coord.try_migrate(&h.admin, &2),
+        Err(Ok(CoordinatorError::SchemaMismatch))
+    );
+    assert!(h.coord.migrate(&h.admin, &1));
+    assert_eq!(h.coord.schema_version(), 2);
+
+    assert_eq!(
+        h.coord
+            .list_workflows(&no_filter(&h.env), &0, &0)
+            .items
+            .len(),
+        3
+    );
+    let mut filter = no_filter(&h.env);
+    filter.hospital = Some(h.hospital.clone());
+    filter.statuses = vec![&h.env, WorkflowStatus::Dispatched];
+    let page = h.coord.list_workflows(&filter, &0, &0);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().request_id, 2);
+
+    // Indexes keep working for later transitions.
+    dispatch_units(&h, 1);
+    let mut filter = no_filter(&h.env);
+    filter.statuses = vec![&h.env, WorkflowStatus::Allocated];
+    let page = h.coord.list_workflows(&filter, &0, &0);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items.get(0).unwrap().request_id, 3);
+    h.env.as_contract(&h.coord.address, || {
+        assert!(!h.env.storage().persistent().has(&DataKey::AllWorkflows));
+    });
+}
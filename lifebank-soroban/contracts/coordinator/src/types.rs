@@ -1,4 +1,6 @@
-use soroban_sdk::{contracttype, Vec};
+pub use lifebank_types::ExcursionSummary;
+use lifebank_types::Urgency;
+use soroban_sdk::{contracttype, Address, Vec};
 
 /// Canonical workflow states — shared identifier across all contracts.
 #[contracttype]
@@ -8,11 +10,13 @@ pub enum WorkflowStatus {
     Pending,
     /// Units reserved, request approved.
     Allocated,
+    /// Rider has taken custody; units are InTransit.
+    Dispatched,
     /// All units delivered to hospital.
     Delivered,
     /// Payment released to blood bank.
     Settled,
-    /// Workflow rolled back; units released, payment refunded.
+    /// Workflow rolled back; stage compensations applied.
     RolledBack,
 }
 
@@ -23,22 +27,147 @@ pub enum WorkflowStatus {
 pub struct WorkflowRecord {
     /// Stable identifier shared across all contracts.
     pub request_id: u64,
-    /// Payment record ID in the payment contract.
-    pub payment_id: u64,
-    /// Inventory unit IDs allocated to this request.
+    /// Inventory unit IDs allocated across all legs; mirrored into the
+    /// request's `assigned_units`.
     pub unit_ids: Vec<u64>,
+    /// Least advanced status among the legs not rolled back.
     pub status: WorkflowStatus,
+    /// Set once every remaining leg has been delivered.
     pub delivery_confirmed: bool,
+    /// Hospital that raised the request.
+    pub hospital: Address,
+    /// Distinct banks supplying the allocated units, one per leg.
+    pub banks: Vec<Address>,
+    /// Urgency of the request; selects the SLA applied to this workflow.
+    pub urgency: Urgency,
+    /// The request's `required_by_timestamp`.
+    pub required_by: u64,
+    /// Per-bank fulfilment legs, in allocation order.
+    pub legs: Vec<WorkflowLeg>,
+}
+
+/// One bank's share of a workflow, dispatched, delivered, settled and
+/// rolled back independently of the other legs.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WorkflowLeg {
+    pub bank: Address,
+    pub unit_ids: Vec<u64>,
+    /// Escrow payment covering this leg's units.
+    pub payment_id: u64,
+    /// Allocated, Dispatched, Delivered, Settled or RolledBack.
+    pub status: WorkflowStatus,
+    /// Rider holding custody once dispatched.
+    pub rider: Option<Address>,
+    /// Expected delivery time given at dispatch.
+    pub eta: Option<u64>,
+    /// Set once `enforce_slas` has flagged a late delivery.
+    pub sla_breached: bool,
+    /// Units flagged by `flag_temperature_breach`. Undelivered ones are
+    /// removed from `unit_ids`.
+    pub breached_units: Vec<u64>,
+}
+
+/// Units and escrow payment for one leg passed to `allocate_legs`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegAllocation {
+    pub unit_ids: Vec<u64>,
+    pub payment_id: u64,
+}
+
+/// When each workflow transition happened and who drove it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkflowTimeline {
+    pub allocated_at: u64,
+    pub allocated_by: Address,
+    pub dispatched_at: Option<u64>,
+    pub dispatched_by: Option<Address>,
+    pub delivered_at: Option<u64>,
+    pub delivered_by: Option<Address>,
+    pub settled_at: Option<u64>,
+    pub settled_by: Option<Address>,
+    pub rolled_back_at: Option<u64>,
+    pub rolled_back_by: Option<Address>,
+}
+
+/// Criteria for `list_workflows`; unset fields match everything.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkflowFilter {
+    /// Accepted statuses; empty matches any status.
+    pub statuses: Vec<WorkflowStatus>,
+    pub hospital: Option<Address>,
+    pub bank: Option<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WorkflowPage {
+    pub items: Vec<WorkflowRecord>,
+    /// Cursor to pass to the next `list_workflows` call; `None` once the
+    /// index has been read to the end.
+    pub next_cursor: Option<u32>,
+}
+
+/// Secondary index over workflows, stored as pages of request IDs.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WorkflowIndex {
+    All,
+    Status(WorkflowStatus),
+    Hospital(Address),
+    Bank(Address),
+}
+
+/// Cross-contract compensation performed during a rollback.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompensationStep {
+    /// Reserved unit returned to Available.
+    ReleaseUnit,
+    /// In-transit unit recalled as Compromised.
+    RecallUnit,
+    /// Locked payment refunded to the hospital.
+    RefundPayment,
+    /// Payment for delivered units refunded as a credit note.
+    CreditNote,
+    /// Linked request moved to Cancelled.
+    CancelRequest,
+}
+
+/// One line of a rollback's compensation log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompensationEntry {
+    pub step: CompensationStep,
+    /// Unit, payment or request ID the step acted on.
+    pub target_id: u64,
+    /// A failed mandatory step rejects the whole rollback.
+    pub mandatory: bool,
+    pub success: bool,
+}
+
+/// Optional pre-conditions checked by `confirm_delivery`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryChecks {
+    /// Identity contract that must hold a verified delivery proof.
+    pub proof_contract: Option<Address>,
+    /// Delivery contract that must hold a compliant temperature attestation.
+    pub compliance_contract: Option<Address>,
 }
 
-/// Summary of a sustained temperature excursion (mirrors temperature contract type).
+/// Service levels enforced by `enforce_slas` for one request urgency.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct ExcursionSummary {
-    pub unit_id: u64,
-    pub violation_count: u32,
-    pub peak_celsius_x100: i32,
-    pub detected_at: u64,
+pub struct SlaConfig {
+    /// Seconds an allocation may wait for dispatch. The request deadline
+    /// still applies if it falls earlier.
+    pub dispatch_within_secs: u64,
+    /// Seconds past the dispatch ETA before a delivery counts as late.
+    pub delivery_grace_secs: u64,
 }
 
 #[contracttype]
@@ -50,4 +179,37 @@ pub enum DataKey {
     PaymentContract,
     Workflow(u64),
     Paused,
+    DeliveryChecks,
+    CompensationLog(u64),
+    Timeline(u64),
+    /// Schema 1 indexes, each a single `Vec` of request IDs; only read by
+    /// `migrate`.
+    AllWorkflows,
+    StatusIndex(WorkflowStatus),
+    HospitalIndex(Address),
+    BankIndex(Address),
+    /// Number of pages in a workflow index.
+    IndexPages(WorkflowIndex),
+    /// One page of request IDs in a workflow index.
+    IndexPage(WorkflowIndex, u32),
+    /// Page of a workflow index holding a request ID.
+    IndexSlot(WorkflowIndex, u64),
+    SlaConfig(Urgency),
+    ReputationContract,
+    /// Reputation entity ID of a bank, used when reporting SLA breaches.
+    BankEntity(Address),
+    TemperatureContract,
+    /// Oracle allowed to call `flag_temperature_breach` directly.
+    BreachOracle(Address),
+    /// Matching contract used to replace breached units; unset disables it.
+    MatchingContract,
+    /// Deployment registry consulted before the peer addresses above.
+    Registry,
+    /// Storage schema version in effect; absent means 1.
+    SchemaVersion,
+    /// Position reached by an unfinished `migrate` step.
+    MigrationCursor,
+    /// Status index and position where the next `enforce_slas` sweep
+    /// resumes.
+    SlaCursor,
 }
@@ -1,7 +1,8 @@
 #![no_std]
 
+use lifebank_types::registry::resolve_service;
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, Env,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
 };
 
 const DEFAULT_MIN_TEMPERATURE_C: i32 = 2;
@@ -14,6 +15,8 @@ pub enum Error {
     AlreadyInitialized = 700,
     NotInitialized = 701,
     DeliveryNotFound = 702,
+    SchemaMismatch = 703,
+    Unauthorized = 704,
 }
 
 #[contracttype]
@@ -40,8 +43,14 @@ pub enum DataKey {
     TemperatureThresholds,
     ProofRequirements,
     ComplianceAttestation(u64),
+    Registry,
+    SchemaVersion,
 }
 
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
+
 #[contract]
 pub struct DeliveryContract;
 
@@ -83,9 +92,60 @@ impl DeliveryContract {
             (admin, request_contract),
         );
 
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        if admin != Self::get_admin(env.clone())? {
+            return Err(Error::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("upgraded"), symbol_short!("v1")),
+            new_wasm_hash,
+        );
         Ok(())
     }
 
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate delivery records out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, Error> {
+        admin.require_auth();
+        if admin != Self::get_admin(env.clone())? {
+            return Err(Error::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(Error::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
     pub fn is_initialized(env: Env) -> bool {
         env.storage().instance().has(&DataKey::Admin)
     }
@@ -97,10 +157,20 @@ impl DeliveryContract {
             .ok_or(Error::NotInitialized)
     }
 
+    /// Resolve the request contract through a deployment registry, falling
+    /// back to the address given at initialization. Admin only.
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), Error> {
+        Self::get_admin(env.clone())?.require_auth();
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        Ok(())
+    }
+
+    /// The registry's `requests` entry when a registry is set, otherwise the
+    /// address given at initialization.
     pub fn get_request_contract(env: Env) -> Result<Address, Error> {
-        env.storage()
-            .instance()
-            .get(&DataKey::RequestContract)
+        let registry = env.storage().instance().get(&DataKey::Registry);
+        resolve_service(&env, registry, "requests")
+            .or_else(|| env.storage().instance().get(&DataKey::RequestContract))
             .ok_or(Error::NotInitialized)
     }
 
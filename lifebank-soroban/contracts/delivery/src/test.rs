@@ -1,9 +1,10 @@
 #![cfg(test)]
 
 use super::*;
+use lifebank_types::testutils::upload_placeholder_wasm;
 use soroban_sdk::{
     testutils::{Address as _, Events as _},
-    Address, Env,
+    Address, Env, Symbol,
 };
 
 fn create_uninitialized_contract<'a>() -> (Env, DeliveryContractClient<'a>, Address) {
@@ -105,3 +106,44 @@ fn test_getters_fail_before_initialization() {
         Err(Ok(Error::NotInitialized))
     );
 }
+
+#[test]
+fn test_request_contract_resolves_through_registry() {
+    let (env, client, _contract_id, admin, request_contract) = create_initialized_contract();
+    let registry_id = env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+
+    // Until the service is registered the initialized address is used.
+    client.set_registry(&registry_id);
+    assert_eq!(client.get_request_contract(), request_contract);
+
+    let requests = Symbol::new(&env, "requests");
+    let rotated = Address::generate(&env);
+    registry.register_service(&requests, &request_contract);
+    registry.rotate_service(&requests, &rotated);
+    assert_eq!(client.get_request_contract(), rotated);
+}
+
+#[test]
+fn test_upgrade_keeps_configuration_and_migrate_is_admin_only() {
+    let (env, client, contract_id, admin, request_contract) = create_initialized_contract();
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    env.register_at(&contract_id, DeliveryContract, ());
+
+    assert_eq!(client.get_request_contract(), request_contract);
+    assert_eq!(client.get_delivery_counter(), 0);
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.migrate(&admin, &1));
+}
@@ -26,19 +26,15 @@ pub enum Error {
     AlreadyVerified = 211,
     AlreadyUnverified = 212,
     ContractPaused = 213,
+    RareDonorAlreadyRegistered = 214,
+    RareDonorNotFound = 215,
+    SchemaMismatch = 216,
 }
 
 // ---------------------------------------------------------------------------
 // Enums
 // ---------------------------------------------------------------------------
 
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum OrgType {
-    BloodBank,
-    Hospital,
-}
-
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Role {
@@ -78,20 +74,6 @@ pub enum BadgeType {
 // Structs
 // ---------------------------------------------------------------------------
 
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct Organization {
-    pub id: Address,
-    pub org_type: OrgType,
-    pub name: String,
-    pub license_number: String,
-    pub verified: bool,
-    pub verified_timestamp: Option<u64>,
-    pub rating: u32,
-    pub total_ratings: u32,
-    pub location_hash: BytesN<32>,
-}
-
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct OrganizationRegistered {
@@ -127,17 +109,18 @@ pub struct BadgeRecord {
     pub awarded_by: Address,
 }
 
+pub use lifebank_types::{BloodType, DeliveryProof, OrgType, Organization, RarePhenotype};
+
+/// A donor carrying a rare phenotype, kept so they can be recalled nationally.
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct DeliveryProof {
-    pub request_id: u64,
-    pub org_id: Address,
-    pub recipient: Address,
-    pub quantity_delivered: u32,
-    pub temperature_ok: bool,
-    pub delivered_at: u64,
-    pub verified: bool,
-    pub verified_at: Option<u64>,
+pub struct RareDonorRecord {
+    pub donor: Address,
+    pub blood_type: BloodType,
+    pub phenotype: RarePhenotype,
+    pub registered_by: Address,
+    pub registered_at: u64,
+    pub active: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -171,12 +154,21 @@ pub enum DataKey {
     // Fine-grained permission scopes (Issue #374)
     AddressScopes(Address),
     Paused,
+    // Rare donor registry
+    RareDonor(Address),
+    RareDonorIndex(RarePhenotype),
+    // Upgrades
+    SchemaVersion,
 }
 
 // ---------------------------------------------------------------------------
 // IdentityContract
 // ---------------------------------------------------------------------------
 
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
+
 #[contract]
 pub struct IdentityContract;
 
@@ -195,9 +187,70 @@ impl IdentityContract {
         env.events()
             .publish((symbol_short!("init"), symbol_short!("v1")), admin);
 
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::Unauthorized)?;
+        if admin != stored {
+            return Err(Error::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("upgraded"), symbol_short!("v1")),
+            new_wasm_hash,
+        );
         Ok(())
     }
 
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate organizations and rare donors out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::Unauthorized)?;
+        if admin != stored {
+            return Err(Error::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(Error::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
     /// Pause all state-mutating functions. Admin only.
     pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
         admin.require_auth();
@@ -874,6 +927,115 @@ impl IdentityContract {
             .get(&DataKey::Delivery(request_id))
     }
 
+    // -----------------------------------------------------------------------
+    // Rare donor registry
+    // -----------------------------------------------------------------------
+
+    /// Register a donor carrying a rare phenotype. Admin only.
+    ///
+    /// The donor is granted `Role::Donor` and added to the phenotype index so
+    /// they can be located when no matching unit exists in any inventory.
+    pub fn register_rare_donor(
+        env: Env,
+        admin: Address,
+        donor: Address,
+        blood_type: BloodType,
+        phenotype: RarePhenotype,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::require_role(&env, &admin, Role::Admin)?;
+
+        let key = DataKey::RareDonor(donor.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::RareDonorAlreadyRegistered);
+        }
+
+        let record = RareDonorRecord {
+            donor: donor.clone(),
+            blood_type,
+            phenotype,
+            registered_by: admin,
+            registered_at: env.ledger().timestamp(),
+            active: true,
+        };
+        env.storage().persistent().set(&key, &record);
+
+        let index_key = DataKey::RareDonorIndex(phenotype);
+        let mut donors: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&index_key)
+            .unwrap_or(Vec::new(&env));
+        donors.push_back(donor.clone());
+        env.storage().persistent().set(&index_key, &donors);
+
+        Self::grant_role(env.clone(), donor.clone(), Role::Donor);
+
+        env.events().publish(
+            (symbol_short!("rare_dnr"), symbol_short!("v1")),
+            (donor, blood_type, phenotype),
+        );
+
+        Ok(())
+    }
+
+    /// Mark a rare donor as (in)active, e.g. deferred or withdrawn. Admin only.
+    pub fn set_rare_donor_active(
+        env: Env,
+        admin: Address,
+        donor: Address,
+        active: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::require_role(&env, &admin, Role::Admin)?;
+
+        let key = DataKey::RareDonor(donor);
+        let mut record: RareDonorRecord = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::RareDonorNotFound)?;
+        record.active = active;
+        env.storage().persistent().set(&key, &record);
+
+        Ok(())
+    }
+
+    /// Get a rare donor record
+    pub fn get_rare_donor(env: Env, donor: Address) -> Option<RareDonorRecord> {
+        env.storage().persistent().get(&DataKey::RareDonor(donor))
+    }
+
+    /// Return up to `max_results` active donors carrying `phenotype`
+    pub fn get_rare_donors_by_phenotype(
+        env: Env,
+        phenotype: RarePhenotype,
+        max_results: u32,
+    ) -> Vec<RareDonorRecord> {
+        let donors: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RareDonorIndex(phenotype))
+            .unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        for donor in donors.iter() {
+            if result.len() >= max_results {
+                break;
+            }
+            let record: Option<RareDonorRecord> =
+                env.storage().persistent().get(&DataKey::RareDonor(donor));
+            if let Some(record) = record {
+                if record.active {
+                    result.push_back(record);
+                }
+            }
+        }
+        result
+    }
+
     // -----------------------------------------------------------------------
     // Internal helpers
     // -----------------------------------------------------------------------
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use super::*;
+use lifebank_types::testutils::upload_placeholder_wasm;
 use soroban_sdk::{
     symbol_short,
     testutils::{Address as _, Events as _, Ledger as _},
@@ -1664,3 +1665,127 @@ fn test_identity_non_admin_cannot_pause() {
     let attacker = Address::generate(&env);
     client.pause(&attacker);
 }
+
+// ── Rare donor registry ───────────────────────────────────────────────────────
+
+#[test]
+fn test_register_rare_donor_grants_donor_role_and_indexes() {
+    let (env, client, admin) = setup_identity();
+    let donor = Address::generate(&env);
+
+    client.register_rare_donor(
+        &admin,
+        &donor,
+        &BloodType::OPositive,
+        &RarePhenotype::Bombay,
+    );
+
+    let record = client.get_rare_donor(&donor).unwrap();
+    assert_eq!(record.phenotype, RarePhenotype::Bombay);
+    assert_eq!(record.registered_by, admin);
+    assert!(record.active);
+    assert!(client.has_role(&donor, &Role::Donor));
+
+    let found = client.get_rare_donors_by_phenotype(&RarePhenotype::Bombay, &10);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found.get(0).unwrap().donor, donor);
+    assert_eq!(
+        client
+            .get_rare_donors_by_phenotype(&RarePhenotype::RhNull, &10)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_register_rare_donor_rejects_duplicate_and_non_admin() {
+    let (env, client, admin) = setup_identity();
+    let donor = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(
+        client.try_register_rare_donor(
+            &stranger,
+            &donor,
+            &BloodType::ONegative,
+            &RarePhenotype::RhNull
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.register_rare_donor(
+        &admin,
+        &donor,
+        &BloodType::ONegative,
+        &RarePhenotype::RhNull,
+    );
+    assert_eq!(
+        client.try_register_rare_donor(
+            &admin,
+            &donor,
+            &BloodType::ONegative,
+            &RarePhenotype::RhNull
+        ),
+        Err(Ok(Error::RareDonorAlreadyRegistered))
+    );
+}
+
+#[test]
+fn test_inactive_rare_donor_excluded_from_lookup() {
+    let (env, client, admin) = setup_identity();
+    let donor = Address::generate(&env);
+    client.register_rare_donor(
+        &admin,
+        &donor,
+        &BloodType::APositive,
+        &RarePhenotype::KellNull,
+    );
+
+    client.set_rare_donor_active(&admin, &donor, &false);
+    assert_eq!(
+        client
+            .get_rare_donors_by_phenotype(&RarePhenotype::KellNull, &10)
+            .len(),
+        0
+    );
+    assert_eq!(
+        client.try_set_rare_donor_active(&admin, &Address::generate(&env), &true),
+        Err(Ok(Error::RareDonorNotFound))
+    );
+}
+
+#[test]
+fn test_upgrade_keeps_organizations_and_migrate_is_admin_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(IdentityContract, ());
+    let client = IdentityContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let org_id = client.register_organization(
+        &Address::generate(&env),
+        &OrgType::BloodBank,
+        &String::from_str(&env, "City Blood Bank"),
+        &String::from_str(&env, "L12345"),
+        &BytesN::from_array(&env, &[0u8; 32]),
+        &vec![&env, BytesN::from_array(&env, &[1u8; 32])],
+    );
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    env.register_at(&contract_id, IdentityContract, ());
+
+    assert!(client.get_organization(&org_id).is_some());
+    assert_eq!(client.get_role(&org_id), Some(Role::BloodBank));
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.migrate(&admin, &1));
+}
@@ -42,4 +42,7 @@ pub enum ContractError {
 
     // Circuit breaker (160)
     ContractPaused = 160,
+
+    // Upgrades (170-179)
+    SchemaMismatch = 170,
 }
@@ -1,5 +1,7 @@
-use crate::types::{AuditEvent, BloodRegisteredEvent, BloodStatus, BloodType, StatusChangeEvent};
-use soroban_sdk::{symbol_short, Address, Env, String, Symbol};
+use crate::types::{
+    AuditEvent, BloodRegisteredEvent, BloodStatus, BloodType, RarePhenotype, StatusChangeEvent,
+};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
 
 /// Emit a BloodRegistered event
 ///
@@ -105,3 +107,32 @@ pub fn emit_reservation_released(env: &Env, reservation_id: u64) {
         reservation_id,
     );
 }
+
+pub fn emit_rare_blood_registered(
+    env: &Env,
+    blood_unit_id: u64,
+    bank_id: &Address,
+    blood_type: BloodType,
+    phenotype: RarePhenotype,
+) {
+    env.events().publish(
+        (Symbol::new(env, "rare_registered"), symbol_short!("v1")),
+        (blood_unit_id, bank_id.clone(), blood_type, phenotype),
+    );
+}
+
+/// Emit a contract_upgraded event carrying the new WASM hash
+pub fn emit_upgraded(env: &Env, new_wasm_hash: &BytesN<32>) {
+    env.events().publish(
+        (Symbol::new(env, "contract_upgraded"), symbol_short!("v1")),
+        new_wasm_hash.clone(),
+    );
+}
+
+/// Emit a schema_migrated event once a migration step has finished
+pub fn emit_schema_migrated(env: &Env, from_version: u32, to_version: u32) {
+    env.events().publish(
+        (Symbol::new(env, "schema_migrated"), symbol_short!("v1")),
+        (from_version, to_version),
+    );
+}
@@ -6,10 +6,24 @@ mod storage;
 mod types;
 mod validation;
 
+pub use crate::types::ALLOWED_BLOOD_STATUS_TRANSITIONS;
+
 use crate::error::ContractError;
-use crate::types::{is_valid_transition, BloodStatus, BloodType, BloodUnit, DataKey, Reservation};
+use crate::types::{
+    is_valid_transition, BloodComponent, BloodStatus, BloodType, BloodUnit, DataKey, RarePhenotype,
+    Reservation, UnitValidation,
+};
+
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Map, String, Vec};
+
+/// Storage schema version written by this build. Bump it together with a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 2;
+
+/// Blood units visited per `migrate` call, so a step stays within ledger
+/// limits however large the inventory is.
+const MIGRATION_BATCH_SIZE: u64 = 50;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, Map, String, Vec};
 #[contract]
 pub struct InventoryContract;
 
@@ -33,6 +47,7 @@ impl InventoryContract {
 
         // Set admin
         storage::set_admin(&env, &admin);
+        storage::set_schema_version(&env, SCHEMA_VERSION);
 
         Ok(())
     }
@@ -68,6 +83,116 @@ impl InventoryContract {
             .unwrap_or(false)
     }
 
+    /// Get the admin address set at initialization
+    ///
+    /// # Errors
+    /// - `NotInitialized`: Contract not initialized
+    pub fn get_admin(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::NotInitialized)
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Only the admin
+    /// can call this.
+    ///
+    /// Storage is left untouched. If the new build raises the schema version,
+    /// call `migrate` afterwards until it reports completion.
+    ///
+    /// # Events
+    /// Emits `contract_upgraded` with the new WASM hash
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if admin != storage::get_admin(&env) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::emit_upgraded(&env, &new_wasm_hash);
+        Ok(())
+    }
+
+    /// Returns the storage schema version currently in effect.
+    pub fn schema_version(env: Env) -> u32 {
+        storage::get_schema_version(&env)
+    }
+
+    /// Run one bounded batch of the data migration out of `from_version`.
+    /// Only the admin can call this.
+    ///
+    /// Each call visits at most `MIGRATION_BATCH_SIZE` blood units and saves
+    /// its position, so a migration over a large inventory is resumed by
+    /// calling again with the same `from_version`. Steps never overwrite data
+    /// already in the new shape, and calls made once the schema is current
+    /// are no-ops.
+    ///
+    /// Steps:
+    /// - 1 -> 2: record `WholeBlood` as the component of every existing unit
+    ///
+    /// # Returns
+    /// `true` once the schema has reached the version of this build
+    ///
+    /// # Errors
+    /// - `SchemaMismatch`: `from_version` is not the stored schema version
+    ///
+    /// # Events
+    /// Emits `schema_migrated` each time a step finishes
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, ContractError> {
+        admin.require_auth();
+        if admin != storage::get_admin(&env) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let current = storage::get_schema_version(&env);
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(ContractError::SchemaMismatch);
+        }
+
+        let finished = match current {
+            1 => Self::migrate_unit_components(&env),
+            _ => return Err(ContractError::SchemaMismatch),
+        };
+        if !finished {
+            return Ok(false);
+        }
+
+        storage::clear_migration_cursor(&env);
+        storage::set_schema_version(&env, current + 1);
+        events::emit_schema_migrated(&env, current, current + 1);
+        Ok(current + 1 >= SCHEMA_VERSION)
+    }
+
+    /// Migration 1 -> 2: backfill the component of units registered before
+    /// components were tracked. Returns `true` when every unit has been visited.
+    fn migrate_unit_components(env: &Env) -> bool {
+        let last_id = storage::get_blood_unit_counter(env);
+        let start = storage::get_migration_cursor(env);
+        let end = last_id.min(start + MIGRATION_BATCH_SIZE - 1);
+
+        for unit_id in start..=end {
+            if storage::blood_unit_exists(env, unit_id)
+                && storage::get_unit_component(env, unit_id).is_none()
+            {
+                storage::set_unit_component(env, unit_id, BloodComponent::WholeBlood);
+            }
+        }
+
+        if end >= last_id {
+            return true;
+        }
+        storage::set_migration_cursor(env, end + 1);
+        false
+    }
+
     fn require_not_paused(env: &Env) -> Result<(), ContractError> {
         if env
             .storage()
@@ -190,6 +315,7 @@ impl InventoryContract {
 
         // Store blood unit — only reaches here if the ID slot was empty.
         storage::set_blood_unit(&env, &blood_unit);
+        storage::set_unit_component(&env, blood_unit_id, BloodComponent::WholeBlood);
 
         // Update indexes for efficient querying
         storage::add_to_blood_type_index(&env, &blood_unit);
@@ -210,6 +336,131 @@ impl InventoryContract {
         Ok(blood_unit_id)
     }
 
+    /// Register a donation carrying a rare red-cell phenotype
+    ///
+    /// Behaves exactly like `register_blood`, then records the phenotype and
+    /// adds the unit to the nationwide phenotype index so matching can find it
+    /// regardless of which bank holds it.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `bank_id` - Blood bank's address (must be authorized)
+    /// * `blood_type` - ABO/Rh type of the unit
+    /// * `phenotype` - Rare phenotype carried by the unit
+    /// * `quantity_ml` - Quantity in milliliters (100-600ml)
+    /// * `donor_id` - Optional donor address (None for anonymous)
+    ///
+    /// # Returns
+    /// Unique ID of the registered blood unit
+    ///
+    /// # Errors
+    /// Same as `register_blood`
+    ///
+    /// # Events
+    /// Emits `BloodRegistered` followed by `rare_registered`
+    pub fn register_rare_blood(
+        env: Env,
+        bank_id: Address,
+        blood_type: BloodType,
+        phenotype: RarePhenotype,
+        quantity_ml: u32,
+        donor_id: Option<Address>,
+    ) -> Result<u64, ContractError> {
+        bank_id.require_auth();
+
+        let blood_unit_id = Self::register_blood_after_auth(
+            env.clone(),
+            bank_id.clone(),
+            blood_type,
+            quantity_ml,
+            donor_id,
+        )?;
+
+        storage::set_unit_phenotype(&env, blood_unit_id, phenotype);
+        storage::add_to_phenotype_index(&env, blood_unit_id, phenotype);
+
+        events::emit_rare_blood_registered(&env, blood_unit_id, &bank_id, blood_type, phenotype);
+
+        Ok(blood_unit_id)
+    }
+
+    /// Get the rare phenotype recorded for a blood unit, if any
+    pub fn get_unit_phenotype(env: Env, blood_unit_id: u64) -> Option<RarePhenotype> {
+        storage::get_unit_phenotype(&env, blood_unit_id)
+    }
+
+    /// Get the IDs of every Available or Reserved unit carrying `phenotype`,
+    /// across all banks
+    ///
+    /// Units leave the index once delivered, expired, compromised or
+    /// disposed. Prefer `get_units_by_phenotype_page` on-chain.
+    pub fn get_units_by_phenotype(env: Env, phenotype: RarePhenotype) -> Vec<u64> {
+        storage::get_units_by_phenotype(&env, phenotype)
+    }
+
+    /// Get up to `limit` unit IDs carrying `phenotype`, starting at position
+    /// `start` of the index
+    ///
+    /// Like `get_units_by_phenotype`, only Available or Reserved units are
+    /// listed, in no particular order. An empty page means the index is
+    /// exhausted.
+    pub fn get_units_by_phenotype_page(
+        env: Env,
+        phenotype: RarePhenotype,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64> {
+        storage::get_units_by_phenotype_page(&env, phenotype, start, limit)
+    }
+
+    /// Get the IDs of every unit of `blood_type`, across all banks
+    ///
+    /// The list includes units in any status; callers filter on `status`.
+    pub fn get_units_by_blood_type(env: Env, blood_type: BloodType) -> Vec<u64> {
+        storage::get_units_by_blood_type(&env, blood_type)
+    }
+
+    /// Get the blood component recorded for a unit
+    ///
+    /// # Errors
+    /// - `NotFound`: Blood unit doesn't exist or has no component recorded
+    ///   (a pre-version-2 unit the migration has not reached yet)
+    pub fn get_unit_component(
+        env: Env,
+        blood_unit_id: u64,
+    ) -> Result<BloodComponent, ContractError> {
+        storage::get_unit_component(&env, blood_unit_id).ok_or(ContractError::NotFound)
+    }
+
+    /// Record the component a unit was processed into. New units default to
+    /// `WholeBlood`; the owning bank sets the real component after
+    /// separation, while the unit is still `Available`.
+    ///
+    /// # Errors
+    /// - `NotFound`: Blood unit doesn't exist
+    /// - `Unauthorized`: Caller is not the bank holding the unit
+    /// - `BloodUnitNotAvailable`: Unit has left `Available`
+    pub fn set_unit_component(
+        env: Env,
+        bank_id: Address,
+        blood_unit_id: u64,
+        component: BloodComponent,
+    ) -> Result<(), ContractError> {
+        bank_id.require_auth();
+        Self::require_not_paused(&env)?;
+
+        let unit = storage::get_blood_unit(&env, blood_unit_id).ok_or(ContractError::NotFound)?;
+        if unit.bank_id != bank_id {
+            return Err(ContractError::Unauthorized);
+        }
+        if unit.status != BloodStatus::Available {
+            return Err(ContractError::BloodUnitNotAvailable);
+        }
+
+        storage::set_unit_component(&env, blood_unit_id, component);
+        Ok(())
+    }
+
     /// Get blood unit details by ID
     ///
     /// # Arguments
@@ -278,6 +529,7 @@ impl InventoryContract {
         // Keep status index consistent: remove from old bucket, add to new bucket.
         storage::remove_from_status_index(&env, unit_id, old_status);
         storage::add_to_status_index(&env, &blood_unit);
+        storage::prune_phenotype_index(&env, &blood_unit);
 
         storage::record_status_change(
             &env,
@@ -403,6 +655,7 @@ impl InventoryContract {
             // Keep status index consistent for each unit.
             storage::remove_from_status_index(&env, unit_id, old_status);
             storage::add_to_status_index(&env, &blood_unit);
+            storage::prune_phenotype_index(&env, &blood_unit);
 
             storage::record_status_change(
                 &env,
@@ -1,4 +1,6 @@
-use crate::types::{BloodStatus, BloodUnit, DataKey, StatusChangeHistory};
+use crate::types::{
+    BloodComponent, BloodStatus, BloodType, BloodUnit, DataKey, RarePhenotype, StatusChangeHistory,
+};
 use soroban_sdk::{Address, Env, String, Vec};
 
 pub const SECONDS_PER_DAY: u64 = 86400;
@@ -8,6 +10,9 @@ pub const BLOOD_SHELF_LIFE_DAYS: u64 = 35;
 /// a single read never loads the entire history of a high-traffic unit.
 const HISTORY_PAGE_SIZE: u32 = 50;
 
+/// Maximum unit IDs per page of a rare phenotype index.
+const PHENOTYPE_PAGE_SIZE: u32 = 50;
+
 // ── Admin ──────────────────────────────────────────────────────────────────────
 
 pub fn get_admin(env: &Env) -> Address {
@@ -60,6 +65,13 @@ pub fn add_to_blood_type_index(env: &Env, blood_unit: &BloodUnit) {
     env.storage().persistent().set(&key, &units);
 }
 
+pub fn get_units_by_blood_type(env: &Env, blood_type: BloodType) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BloodTypeIndex(blood_type))
+        .unwrap_or(Vec::new(env))
+}
+
 pub fn add_to_bank_index(env: &Env, blood_unit: &BloodUnit) {
     let key = DataKey::BankIndex(blood_unit.bank_id.clone());
     let mut units: Vec<u64> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
@@ -98,6 +110,197 @@ pub fn add_to_donor_index(env: &Env, blood_unit: &BloodUnit) {
     }
 }
 
+// ── Blood components ───────────────────────────────────────────────────────────
+
+pub fn set_unit_component(env: &Env, blood_unit_id: u64, component: BloodComponent) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::UnitComponent(blood_unit_id), &component);
+}
+
+pub fn get_unit_component(env: &Env, blood_unit_id: u64) -> Option<BloodComponent> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UnitComponent(blood_unit_id))
+}
+
+// ── Schema version ─────────────────────────────────────────────────────────────
+
+/// Deployments that predate schema versioning have no stored version and
+/// are treated as version 1.
+pub fn get_schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(1)
+}
+
+pub fn set_schema_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::SchemaVersion, &version);
+}
+
+pub fn get_migration_cursor(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MigrationCursor)
+        .unwrap_or(1)
+}
+
+pub fn set_migration_cursor(env: &Env, cursor: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MigrationCursor, &cursor);
+}
+
+pub fn clear_migration_cursor(env: &Env) {
+    env.storage().instance().remove(&DataKey::MigrationCursor);
+}
+
+// ── Rare phenotype registry ────────────────────────────────────────────────────
+
+pub fn set_unit_phenotype(env: &Env, blood_unit_id: u64, phenotype: RarePhenotype) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::UnitPhenotype(blood_unit_id), &phenotype);
+}
+
+pub fn get_unit_phenotype(env: &Env, blood_unit_id: u64) -> Option<RarePhenotype> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UnitPhenotype(blood_unit_id))
+}
+
+// The phenotype index only holds units that can still be matched, as pages:
+//   DataKey::PhenotypePages(phenotype)   → number of pages (u32)
+//   DataKey::PhenotypePage(phenotype, p) → Vec<u64> for page p
+//   DataKey::PhenotypeSlot(unit_id)      → page holding the unit
+//
+// Each page holds at most PHENOTYPE_PAGE_SIZE IDs. A unit that leaves the
+// index is replaced by the index's last ID, so every page but the last is
+// full and a position maps straight to its page.
+
+fn phenotype_pages(env: &Env, phenotype: RarePhenotype) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PhenotypePages(phenotype))
+        .unwrap_or(0)
+}
+
+fn phenotype_page(env: &Env, phenotype: RarePhenotype, page: u32) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PhenotypePage(phenotype, page))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Store the last page of the index, dropping it once empty.
+fn set_last_phenotype_page(env: &Env, phenotype: RarePhenotype, page: u32, units: &Vec<u64>) {
+    if units.is_empty() {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PhenotypePage(phenotype, page));
+        env.storage()
+            .persistent()
+            .set(&DataKey::PhenotypePages(phenotype), &page);
+    } else {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PhenotypePage(phenotype, page), units);
+    }
+}
+
+pub fn add_to_phenotype_index(env: &Env, blood_unit_id: u64, phenotype: RarePhenotype) {
+    let pages = phenotype_pages(env, phenotype);
+    let mut page = pages.saturating_sub(1);
+    let mut units = phenotype_page(env, phenotype, page);
+    if pages == 0 || units.len() >= PHENOTYPE_PAGE_SIZE {
+        page = pages;
+        units = Vec::new(env);
+        env.storage()
+            .persistent()
+            .set(&DataKey::PhenotypePages(phenotype), &(pages + 1));
+    }
+    units.push_back(blood_unit_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PhenotypePage(phenotype, page), &units);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PhenotypeSlot(blood_unit_id), &page);
+}
+
+/// Drop a rare unit from its phenotype index once it is no longer Available
+/// or Reserved. Common units and units already dropped are left alone.
+pub fn prune_phenotype_index(env: &Env, blood_unit: &BloodUnit) {
+    if matches!(
+        blood_unit.status,
+        BloodStatus::Available | BloodStatus::Reserved
+    ) {
+        return;
+    }
+    let slot = DataKey::PhenotypeSlot(blood_unit.id);
+    let (Some(page), Some(phenotype)) = (
+        env.storage().persistent().get::<_, u32>(&slot),
+        get_unit_phenotype(env, blood_unit.id),
+    ) else {
+        return;
+    };
+    env.storage().persistent().remove(&slot);
+
+    let last = phenotype_pages(env, phenotype) - 1;
+    let mut units = phenotype_page(env, phenotype, page);
+    let pos = units.first_index_of(blood_unit.id).unwrap();
+    if page == last {
+        units.remove(pos);
+        set_last_phenotype_page(env, phenotype, last, &units);
+        return;
+    }
+    let mut tail = phenotype_page(env, phenotype, last);
+    let moved = tail.pop_back().unwrap();
+    units.set(pos, moved);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PhenotypePage(phenotype, page), &units);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PhenotypeSlot(moved), &page);
+    set_last_phenotype_page(env, phenotype, last, &tail);
+}
+
+/// Every unit in the phenotype index, page by page.
+pub fn get_units_by_phenotype(env: &Env, phenotype: RarePhenotype) -> Vec<u64> {
+    let mut all: Vec<u64> = Vec::new(env);
+    for p in 0..phenotype_pages(env, phenotype) {
+        all.append(&phenotype_page(env, phenotype, p));
+    }
+    all
+}
+
+/// Up to `limit` units from position `start` of the phenotype index. Only
+/// the pages covering the range are read.
+pub fn get_units_by_phenotype_page(
+    env: &Env,
+    phenotype: RarePhenotype,
+    start: u32,
+    limit: u32,
+) -> Vec<u64> {
+    let mut units: Vec<u64> = Vec::new(env);
+    let mut pos = start;
+    while units.len() < limit {
+        let page = phenotype_page(env, phenotype, pos / PHENOTYPE_PAGE_SIZE);
+        let offset = pos % PHENOTYPE_PAGE_SIZE;
+        if offset >= page.len() {
+            break;
+        }
+        let take = (page.len() - offset).min(limit - units.len());
+        units.append(&page.slice(offset..offset + take));
+        pos += take;
+    }
+    units
+}
+
 // ── Paginated status history ───────────────────────────────────────────────────
 //
 // History is stored as a sequence of fixed-size pages:
@@ -1,6 +1,8 @@
+use crate::error::ContractError;
 use crate::storage;
-use crate::types::{BloodStatus, BloodType};
+use crate::types::{BloodComponent, BloodStatus, BloodType, DataKey, RarePhenotype};
 use crate::{InventoryContract, InventoryContractClient};
+use lifebank_types::testutils::upload_placeholder_wasm;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
     vec, Address, Env, String,
@@ -33,6 +35,18 @@ fn test_initialize_success() {
     assert_eq!(stored_admin, admin);
 }
 
+#[test]
+fn test_get_admin() {
+    let (env, admin, client, _contract_id) = create_test_contract();
+    assert_eq!(client.get_admin(), admin);
+
+    let uninitialized = InventoryContractClient::new(&env, &env.register(InventoryContract, ()));
+    assert_eq!(
+        uninitialized.try_get_admin(),
+        Err(Ok(ContractError::NotInitialized))
+    );
+}
+
 #[test]
 #[should_panic(expected = "Error(Contract, #100)")]
 fn test_initialize_already_initialized() {
@@ -1658,3 +1672,275 @@ fn test_batch_register_blood_unauthorized_bank() {
     let entries = vec![&env, (BloodType::APositive, 450u32, None::<Address>)];
     client.batch_register_blood(&unauthorized, &entries);
 }
+
+#[test]
+fn test_get_units_by_blood_type() {
+    let (env, admin, client, _) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    let first = client.register_blood(&admin, &BloodType::ONegative, &450u32, &None);
+    client.register_blood(&admin, &BloodType::APositive, &450u32, &None);
+    let second = client.register_blood(&admin, &BloodType::ONegative, &300u32, &None);
+
+    assert_eq!(
+        client.get_units_by_blood_type(&BloodType::ONegative),
+        vec![&env, first, second]
+    );
+    assert_eq!(
+        client.get_units_by_blood_type(&BloodType::ABNegative).len(),
+        0
+    );
+}
+
+// ── Rare phenotype registry ─────────────────────────────────────────────────
+
+#[test]
+fn test_register_rare_blood_indexes_phenotype() {
+    let (env, admin, client, _) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    let common = client.register_blood(&admin, &BloodType::OPositive, &450u32, &None);
+    let rare = client.register_rare_blood(
+        &admin,
+        &BloodType::OPositive,
+        &RarePhenotype::Bombay,
+        &450u32,
+        &None,
+    );
+
+    assert_eq!(client.get_unit_phenotype(&common), None);
+    assert_eq!(
+        client.get_unit_phenotype(&rare),
+        Some(RarePhenotype::Bombay)
+    );
+    assert_eq!(
+        client.get_units_by_phenotype(&RarePhenotype::Bombay),
+        vec![&env, rare]
+    );
+    assert_eq!(
+        client.get_units_by_phenotype(&RarePhenotype::RhNull).len(),
+        0
+    );
+
+    // The rare unit is still a normal unit for every other query
+    let unit = client.get_blood_unit(&rare);
+    assert_eq!(unit.status, BloodStatus::Available);
+    assert_eq!(unit.blood_type, BloodType::OPositive);
+}
+
+#[test]
+fn test_units_by_phenotype_page() {
+    let (env, admin, client, _) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    let mut rare = soroban_sdk::Vec::new(&env);
+    for _ in 0..5 {
+        rare.push_back(client.register_rare_blood(
+            &admin,
+            &BloodType::ONegative,
+            &RarePhenotype::RhNull,
+            &450u32,
+            &None,
+        ));
+    }
+
+    assert_eq!(
+        client.get_units_by_phenotype_page(&RarePhenotype::RhNull, &0, &2),
+        rare.slice(0..2)
+    );
+    assert_eq!(
+        client.get_units_by_phenotype_page(&RarePhenotype::RhNull, &4, &2),
+        rare.slice(4..5)
+    );
+    assert_eq!(
+        client
+            .get_units_by_phenotype_page(&RarePhenotype::RhNull, &5, &2)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_phenotype_index_drops_units_that_leave_stock() {
+    let (env, admin, client, _) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    let mut rare = soroban_sdk::Vec::new(&env);
+    for _ in 0..55 {
+        rare.push_back(client.register_rare_blood(
+            &admin,
+            &BloodType::ONegative,
+            &RarePhenotype::RhNull,
+            &450u32,
+            &None,
+        ));
+    }
+    let first = rare.get(0).unwrap();
+    let last = rare.get(54).unwrap();
+
+    client.update_status(&first, &BloodStatus::Reserved, &admin, &None);
+    assert_eq!(
+        client.get_units_by_phenotype(&RarePhenotype::RhNull).len(),
+        55
+    );
+
+    // The last unit fills the place of the one that left.
+    client.update_status(&first, &BloodStatus::Compromised, &admin, &None);
+    let page = client.get_units_by_phenotype_page(&RarePhenotype::RhNull, &0, &50);
+    assert_eq!(page.get(0).unwrap(), last);
+    assert!(!page.contains(first));
+    assert_eq!(
+        client.get_units_by_phenotype_page(&RarePhenotype::RhNull, &48, &10),
+        rare.slice(48..54)
+    );
+    assert_eq!(
+        client.get_units_by_phenotype(&RarePhenotype::RhNull).len(),
+        54
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #132)")]
+fn test_register_rare_blood_unauthorized_bank() {
+    let (env, _admin, client, _) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    let unauthorized = Address::generate(&env);
+    client.register_rare_blood(
+        &unauthorized,
+        &BloodType::ONegative,
+        &RarePhenotype::RhNull,
+        &450u32,
+        &None,
+    );
+}
+
+// ── Upgrades and schema migrations ────────────────────────────────────────────
+
+/// Rewind storage to the version 1 layout: no schema version and no
+/// component records.
+fn rewind_to_schema_v1(env: &Env, contract_id: &Address, unit_ids: &soroban_sdk::Vec<u64>) {
+    env.as_contract(contract_id, || {
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+        for unit_id in unit_ids.iter() {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::UnitComponent(unit_id));
+        }
+    });
+}
+
+#[test]
+fn test_initialize_sets_current_schema_version() {
+    let (_env, admin, client, _) = create_test_contract();
+    assert_eq!(client.schema_version(), 2);
+    assert!(client.migrate(&admin, &2));
+}
+
+#[test]
+fn test_unit_component_defaults_and_updates() {
+    let (env, admin, client, _) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    let unit = client.register_blood(&admin, &BloodType::APositive, &450u32, &None);
+    assert_eq!(client.get_unit_component(&unit), BloodComponent::WholeBlood);
+
+    client.set_unit_component(&admin, &unit, &BloodComponent::Plasma);
+    assert_eq!(client.get_unit_component(&unit), BloodComponent::Plasma);
+
+    let other_bank = Address::generate(&env);
+    assert_eq!(
+        client.try_set_unit_component(&other_bank, &unit, &BloodComponent::Platelets),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let (env, _admin, client, _) = create_test_contract();
+    let hash = upload_placeholder_wasm(&env);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_upgrade_then_migrate_backfills_live_units() {
+    let (env, admin, client, contract_id) = create_test_contract();
+    env.ledger().set_timestamp(1000u64);
+
+    // 120 live units span three migration batches of 50
+    let mut unit_ids = soroban_sdk::Vec::new(&env);
+    for _ in 0..120 {
+        unit_ids.push_back(client.register_blood(&admin, &BloodType::OPositive, &450u32, &None));
+    }
+    let reserved = unit_ids.get(0).unwrap();
+    client.update_status(&reserved, &BloodStatus::Reserved, &admin, &None);
+    rewind_to_schema_v1(&env, &contract_id, &unit_ids);
+
+    // A component the bank already set must survive the backfill
+    let plasma = unit_ids.get(60).unwrap();
+    client.set_unit_component(&admin, &plasma, &BloodComponent::Plasma);
+
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_get_unit_component(&unit_ids.get(1).unwrap()),
+        Err(Ok(ContractError::NotFound))
+    );
+
+    let hash = upload_placeholder_wasm(&env);
+    client.upgrade(&admin, &hash);
+    env.register_at(&contract_id, InventoryContract, ());
+
+    assert_eq!(
+        client.try_migrate(&admin, &0),
+        Err(Ok(ContractError::SchemaMismatch))
+    );
+
+    // Bounded and resumable: each call covers one batch
+    assert!(!client.migrate(&admin, &1));
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.get_unit_component(&unit_ids.get(49).unwrap()),
+        BloodComponent::WholeBlood
+    );
+    assert_eq!(
+        client.try_get_unit_component(&unit_ids.get(50).unwrap()),
+        Err(Ok(ContractError::NotFound))
+    );
+    assert!(!client.migrate(&admin, &1));
+    assert!(client.migrate(&admin, &1));
+    assert_eq!(client.schema_version(), 2);
+
+    // Idempotent once current
+    assert!(client.migrate(&admin, &1));
+    assert!(client.migrate(&admin, &2));
+
+    for unit_id in unit_ids.iter() {
+        let expected = if unit_id == plasma {
+            BloodComponent::Plasma
+        } else {
+            BloodComponent::WholeBlood
+        };
+        assert_eq!(client.get_unit_component(&unit_id), expected);
+    }
+
+    // Existing data is untouched and the contract keeps working
+    assert_eq!(
+        client.get_blood_unit(&reserved).status,
+        BloodStatus::Reserved
+    );
+    let fresh = client.register_blood(&admin, &BloodType::ANegative, &450u32, &None);
+    assert_eq!(fresh, 121);
+    assert_eq!(
+        client.get_unit_component(&fresh),
+        BloodComponent::WholeBlood
+    );
+}
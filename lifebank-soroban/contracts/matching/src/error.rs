@@ -12,6 +12,8 @@ pub enum MatchingError {
     // Request errors (610-619)
     RequestNotFound    = 610,
     InvalidRequest     = 611,
+    /// The request has no rare phenotype recorded.
+    PhenotypeNotSet = 612,
 
     // Inventory errors (620-629)
     InventoryCallFailed = 620,
@@ -19,4 +21,7 @@ pub enum MatchingError {
 
     // Circuit breaker (630)
     ContractPaused = 630,
+
+    // Upgrades (640)
+    SchemaMismatch = 640,
 }
@@ -11,31 +11,42 @@ pub use error::MatchingError;
 pub use matching::{compatible_donor_types, is_compatible, score_unit, select_units, sort_by_expiration};
 pub use types::{
     BloodComponent, BloodRequest, BloodStatus, BloodType, BloodUnit, DataKey, MatchKind,
-    MatchResult, MatchedUnit, RequestStatus, Urgency,
+    MatchResult, MatchedUnit, RarePhenotype, RequestStatus, Urgency,
 };
 
-use soroban_sdk::{contract, contractclient, contractimpl, Address, Env, Vec};
+use lifebank_types::clients::{InventoryContractClient, RequestContractClient};
+use lifebank_types::registry::resolve_service;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Vec};
+
+/// Address of the peer contract stored under `key`, looked up in the
+/// registry first.
+fn peer(env: &Env, key: &DataKey) -> Address {
+    let service = match key {
+        DataKey::InventoryContract => "inventory",
+        DataKey::RequestsContract => "requests",
+        _ => return env.storage().instance().get(key).unwrap(),
+    };
+    resolve_service(
+        env,
+        env.storage().instance().get(&DataKey::Registry),
+        service,
+    )
+    .unwrap_or_else(|| env.storage().instance().get(key).unwrap())
+}
 
 // ---------------------------------------------------------------------------
-// Cross-contract client interfaces
+// Contract
 // ---------------------------------------------------------------------------
 
-/// Minimal interface we need from the inventory contract.
-#[contractclient(name = "InventoryContractClient")]
-pub trait InventoryContractInterface {
-    fn get_blood_unit(env: Env, blood_unit_id: u64) -> BloodUnit;
-    fn get_units_by_blood_type(env: Env, blood_type: BloodType) -> Vec<u64>;
-}
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
 
-/// Minimal interface we need from the requests contract.
-#[contractclient(name = "RequestsContractClient")]
-pub trait RequestsContractInterface {
-    fn get_request(env: Env, request_id: u64) -> BloodRequest;
-}
+/// Unit IDs read from the inventory's phenotype index per call.
+const RARE_PAGE_SIZE: u32 = 25;
 
-// ---------------------------------------------------------------------------
-// Contract
-// ---------------------------------------------------------------------------
+/// Pages of the phenotype index `match_rare_phenotype` reads at most.
+const MAX_RARE_PAGES: u32 = 8;
 
 #[contract]
 pub struct MatchingContract;
@@ -65,9 +76,74 @@ impl MatchingContract {
             .set(&DataKey::RequestsContract, &requests_contract);
         env.storage().instance().set(&DataKey::Initialized, &true);
 
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), MatchingError> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(MatchingError::Unauthorized)?;
+        if admin != stored {
+            return Err(MatchingError::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("match"), symbol_short!("upgraded")),
+            new_wasm_hash,
+        );
         Ok(())
     }
 
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate matching configuration out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, MatchingError> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(MatchingError::Unauthorized)?;
+        if admin != stored {
+            return Err(MatchingError::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(MatchingError::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
     /// Pause all state-mutating functions. Admin only.
     pub fn pause(env: Env, admin: Address) -> Result<(), MatchingError> {
         admin.require_auth();
@@ -98,6 +174,22 @@ impl MatchingContract {
         Ok(())
     }
 
+    /// Resolve the inventory and requests contracts through a deployment
+    /// registry, falling back to the initialized addresses. Admin only.
+    pub fn set_registry(env: Env, admin: Address, registry: Address) -> Result<(), MatchingError> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(MatchingError::Unauthorized)?;
+        if admin != stored {
+            return Err(MatchingError::Unauthorized);
+        }
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        Ok(())
+    }
+
     /// Returns whether the contract is currently paused.
     pub fn is_paused(env: Env) -> bool {
         env.storage()
@@ -140,12 +232,8 @@ impl MatchingContract {
         Self::require_not_paused(&env)?;
 
         // Load request
-        let req_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::RequestsContract)
-            .unwrap();
-        let req_client = RequestsContractClient::new(&env, &req_addr);
+        let req_addr = peer(&env, &DataKey::RequestsContract);
+        let req_client = RequestContractClient::new(&env, &req_addr);
         let request = req_client
             .try_get_request(&request_id)
             .map_err(|_| MatchingError::RequestNotFound)?
@@ -156,11 +244,7 @@ impl MatchingContract {
         }
 
         // Collect all candidate units across compatible blood types
-        let inv_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::InventoryContract)
-            .unwrap();
+        let inv_addr = peer(&env, &DataKey::InventoryContract);
         let inv_client = InventoryContractClient::new(&env, &inv_addr);
 
         let compatible_types =
@@ -193,23 +277,158 @@ impl MatchingContract {
             now,
         );
 
-        let total_matched_ml: u32 = {
-            let mut sum = 0u32;
-            for i in 0..matched.len() {
-                sum = sum.saturating_add(matched.get(i).unwrap().quantity_ml);
+        Ok(Self::build_match_result(
+            request_id,
+            matched,
+            request.quantity_ml,
+        ))
+    }
+
+    /// Nationwide match for a recipient with a rare red-cell phenotype.
+    ///
+    /// Unlike `match_request`, candidates come from the inventory's global
+    /// phenotype index rather than the blood-type index, and proximity is
+    /// ignored — any bank holding a compatible unit is eligible, since the
+    /// alternative for these patients is usually no unit at all.
+    ///
+    /// The phenotype is the one the hospital recorded on the request. The
+    /// index is read a page at a time, and reading stops once the compatible
+    /// available units cover the request or after `MAX_RARE_PAGES` pages.
+    ///
+    /// When the whole index was read and no unit matched, an empty result is
+    /// returned and a `(match, rare_none)` alert event is emitted so
+    /// coordinators can recall registered rare donors. A scan cut short by
+    /// `MAX_RARE_PAGES` raises no alert, since unread units may still match.
+    pub fn match_rare_phenotype(env: Env, request_id: u64) -> Result<MatchResult, MatchingError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+
+        let req_addr = peer(&env, &DataKey::RequestsContract);
+        let req_client = RequestContractClient::new(&env, &req_addr);
+        let request = req_client
+            .try_get_request(&request_id)
+            .map_err(|_| MatchingError::RequestNotFound)?
+            .map_err(|_| MatchingError::RequestNotFound)?;
+
+        if request.status != RequestStatus::Pending {
+            return Err(MatchingError::InvalidRequest);
+        }
+        let phenotype = req_client
+            .try_get_rare_phenotype(&request_id)
+            .ok()
+            .and_then(|phenotype| phenotype.ok())
+            .flatten()
+            .ok_or(MatchingError::PhenotypeNotSet)?;
+
+        let inv_addr = peer(&env, &DataKey::InventoryContract);
+        let inv_client = InventoryContractClient::new(&env, &inv_addr);
+
+        let mut candidates: Vec<BloodUnit> = Vec::new(&env);
+        let mut usable_ml: u32 = 0;
+        let mut exhausted = false;
+        for page in 0..MAX_RARE_PAGES {
+            let unit_ids = inv_client
+                .try_get_units_by_phenotype_page(
+                    &phenotype,
+                    &(page * RARE_PAGE_SIZE),
+                    &RARE_PAGE_SIZE,
+                )
+                .unwrap_or(Ok(Vec::new(&env)))
+                .unwrap_or(Vec::new(&env));
+            for uid in unit_ids.iter() {
+                if let Ok(Ok(unit)) = inv_client.try_get_blood_unit(&uid) {
+                    if unit.status == BloodStatus::Available
+                        && is_compatible(unit.blood_type, request.blood_type)
+                    {
+                        usable_ml = usable_ml.saturating_add(unit.quantity_ml);
+                    }
+                    candidates.push_back(unit);
+                }
             }
-            sum
-        };
-        let remaining_ml = request.quantity_ml.saturating_sub(total_matched_ml);
-        let partial_fulfillment = total_matched_ml > 0 && remaining_ml > 0;
+            if unit_ids.len() < RARE_PAGE_SIZE {
+                exhausted = true;
+                break;
+            }
+            if usable_ml >= request.quantity_ml {
+                break;
+            }
+        }
 
-        Ok(MatchResult {
+        let now = env.ledger().timestamp();
+        let matched = select_units(
+            &env,
+            candidates,
+            request.blood_type,
+            request.urgency,
+            request.quantity_ml,
+            None,
+            now,
+        );
+
+        if matched.is_empty() && exhausted {
+            env.events().publish(
+                (symbol_short!("match"), symbol_short!("rare_none")),
+                (request_id, phenotype, request.hospital_id, request.urgency),
+            );
+        }
+
+        Ok(Self::build_match_result(
             request_id,
-            matched_units: matched,
-            total_matched_ml,
-            remaining_ml,
-            partial_fulfillment,
-        })
+            matched,
+            request.quantity_ml,
+        ))
+    }
+
+    /// Pick a single replacement for a unit lost after allocation, such as
+    /// one compromised by a temperature excursion.
+    ///
+    /// Only Available units held by `bank` and not listed in `exclude` are
+    /// considered, ranked as in `match_request` for `quantity_ml`. Returns the
+    /// best unit, or `None` when the bank has nothing compatible.
+    pub fn find_replacement(
+        env: Env,
+        blood_type: BloodType,
+        urgency: Urgency,
+        quantity_ml: u32,
+        bank: Address,
+        exclude: Vec<u64>,
+    ) -> Result<Option<u64>, MatchingError> {
+        Self::require_initialized(&env)?;
+        Self::require_not_paused(&env)?;
+
+        let inv_addr = peer(&env, &DataKey::InventoryContract);
+        let inv_client = InventoryContractClient::new(&env, &inv_addr);
+
+        let compatible_types = compatible_donor_types(&env, blood_type);
+        let mut candidates: Vec<BloodUnit> = Vec::new(&env);
+        for bt in compatible_types.iter() {
+            let unit_ids = inv_client
+                .try_get_units_by_blood_type(&bt)
+                .unwrap_or(Ok(Vec::new(&env)))
+                .unwrap_or(Vec::new(&env));
+            for uid in unit_ids.iter() {
+                if exclude.contains(uid) {
+                    continue;
+                }
+                if let Ok(Ok(unit)) = inv_client.try_get_blood_unit(&uid) {
+                    if unit.bank_id == bank {
+                        candidates.push_back(unit);
+                    }
+                }
+            }
+        }
+
+        let now = env.ledger().timestamp();
+        let matched = select_units(
+            &env,
+            candidates,
+            blood_type,
+            urgency,
+            quantity_ml,
+            Some(&bank),
+            now,
+        );
+        Ok(matched.first().map(|m| m.unit_id))
     }
 
     /// Match multiple requests in urgency-priority order.
@@ -228,12 +447,8 @@ impl MatchingContract {
         Self::require_initialized(&env)?;
         Self::require_not_paused(&env)?;
 
-        let req_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::RequestsContract)
-            .unwrap();
-        let req_client = RequestsContractClient::new(&env, &req_addr);
+        let req_addr = peer(&env, &DataKey::RequestsContract);
+        let req_client = RequestContractClient::new(&env, &req_addr);
 
         // Load all requests in one pass
         let mut requests: Vec<BloodRequest> = Vec::new(&env);
@@ -310,6 +525,27 @@ impl MatchingContract {
 
     // ── Internal ─────────────────────────────────────────────────────────────
 
+    fn build_match_result(
+        request_id: u64,
+        matched: Vec<MatchedUnit>,
+        requested_ml: u32,
+    ) -> MatchResult {
+        let mut total_matched_ml = 0u32;
+        for i in 0..matched.len() {
+            total_matched_ml = total_matched_ml.saturating_add(matched.get(i).unwrap().quantity_ml);
+        }
+        let remaining_ml = requested_ml.saturating_sub(total_matched_ml);
+        let partial_fulfillment = total_matched_ml > 0 && remaining_ml > 0;
+
+        MatchResult {
+            request_id,
+            matched_units: matched,
+            total_matched_ml,
+            remaining_ml,
+            partial_fulfillment,
+        }
+    }
+
     fn require_initialized(env: &Env) -> Result<(), MatchingError> {
         if !env.storage().instance().has(&DataKey::Initialized) {
             return Err(MatchingError::NotInitialized);
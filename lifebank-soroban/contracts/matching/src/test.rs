@@ -600,3 +600,466 @@ mod circuit_breaker_tests {
         client.pause(&attacker);
     }
 }
+
+// ── Nationwide rare-phenotype matching ────────────────────────────────────────
+
+#[cfg(test)]
+mod rare_phenotype_tests {
+    use soroban_sdk::{
+        contract, contractimpl, contracttype,
+        testutils::{Address as _, Events as _},
+        Address, Env, Map, Vec,
+    };
+
+    use crate::{
+        BloodComponent, BloodRequest, BloodStatus, BloodType, BloodUnit, MatchingContract,
+        MatchingContractClient, MatchingError, RarePhenotype, RequestStatus, Urgency,
+    };
+
+    #[contracttype]
+    enum MockKey {
+        Unit(u64),
+        Phenotype(RarePhenotype),
+        Request(u64),
+        RequestPhenotype(u64),
+    }
+
+    #[contract]
+    struct MockInventory;
+
+    #[contractimpl]
+    impl MockInventory {
+        pub fn seed_unit(
+            env: Env,
+            id: u64,
+            bank: Address,
+            blood_type: BloodType,
+            phenotype: RarePhenotype,
+            status: BloodStatus,
+        ) {
+            let unit = BloodUnit {
+                id,
+                blood_type,
+                quantity_ml: 450,
+                bank_id: bank,
+                donor_id: None,
+                donation_timestamp: 0,
+                expiration_timestamp: 1_000_000,
+                status,
+                metadata: Map::new(&env),
+            };
+            env.storage().persistent().set(&MockKey::Unit(id), &unit);
+            let key = MockKey::Phenotype(phenotype);
+            let mut ids: Vec<u64> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(&env));
+            ids.push_back(id);
+            env.storage().persistent().set(&key, &ids);
+        }
+
+        pub fn get_blood_unit(env: Env, blood_unit_id: u64) -> BloodUnit {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Unit(blood_unit_id))
+                .unwrap()
+        }
+
+        pub fn get_units_by_blood_type(env: Env, _blood_type: BloodType) -> Vec<u64> {
+            Vec::new(&env)
+        }
+
+        pub fn get_units_by_phenotype(env: Env, phenotype: RarePhenotype) -> Vec<u64> {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Phenotype(phenotype))
+                .unwrap_or(Vec::new(&env))
+        }
+
+        pub fn get_units_by_phenotype_page(
+            env: Env,
+            phenotype: RarePhenotype,
+            start: u32,
+            limit: u32,
+        ) -> Vec<u64> {
+            let ids = Self::get_units_by_phenotype(env, phenotype);
+            let end = (start + limit).min(ids.len());
+            ids.slice(start.min(end)..end)
+        }
+    }
+
+    #[contract]
+    struct MockRequests;
+
+    #[contractimpl]
+    impl MockRequests {
+        pub fn seed_request(
+            env: Env,
+            id: u64,
+            hospital: Address,
+            blood_type: BloodType,
+            quantity_ml: u32,
+        ) {
+            let request = BloodRequest {
+                id,
+                hospital_id: hospital,
+                blood_type,
+                component: BloodComponent::RedCells,
+                quantity_ml,
+                urgency: Urgency::Critical,
+                created_timestamp: 0,
+                required_by_timestamp: 3600,
+                status: RequestStatus::Pending,
+                assigned_units: Vec::new(&env),
+                fulfilled_quantity_ml: 0,
+                reservation_id: None,
+            };
+            env.storage()
+                .persistent()
+                .set(&MockKey::Request(id), &request);
+        }
+
+        pub fn get_request(env: Env, request_id: u64) -> BloodRequest {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Request(request_id))
+                .unwrap()
+        }
+
+        pub fn set_rare_phenotype(env: Env, request_id: u64, phenotype: RarePhenotype) {
+            env.storage()
+                .persistent()
+                .set(&MockKey::RequestPhenotype(request_id), &phenotype);
+        }
+
+        pub fn get_rare_phenotype(env: Env, request_id: u64) -> Option<RarePhenotype> {
+            env.storage()
+                .persistent()
+                .get(&MockKey::RequestPhenotype(request_id))
+        }
+    }
+
+    fn setup<'a>() -> (
+        Env,
+        MatchingContractClient<'a>,
+        MockInventoryClient<'a>,
+        MockRequestsClient<'a>,
+    ) {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let inv_id = env.register(MockInventory, ());
+        let req_id = env.register(MockRequests, ());
+        let contract_id = env.register(MatchingContract, ());
+        let client = MatchingContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &inv_id, &req_id);
+
+        (
+            env.clone(),
+            client,
+            MockInventoryClient::new(&env, &inv_id),
+            MockRequestsClient::new(&env, &req_id),
+        )
+    }
+
+    #[test]
+    fn matches_units_from_any_bank() {
+        let (env, client, inventory, requests) = setup();
+        let hospital = Address::generate(&env);
+        let far_bank_a = Address::generate(&env);
+        let far_bank_b = Address::generate(&env);
+
+        inventory.seed_unit(
+            &1,
+            &far_bank_a,
+            &BloodType::OPositive,
+            &RarePhenotype::Bombay,
+            &BloodStatus::Available,
+        );
+        inventory.seed_unit(
+            &2,
+            &far_bank_b,
+            &BloodType::ONegative,
+            &RarePhenotype::Bombay,
+            &BloodStatus::Available,
+        );
+        inventory.seed_unit(
+            &3,
+            &far_bank_b,
+            &BloodType::OPositive,
+            &RarePhenotype::Bombay,
+            &BloodStatus::Reserved,
+        );
+        inventory.seed_unit(
+            &4,
+            &far_bank_a,
+            &BloodType::OPositive,
+            &RarePhenotype::RhNull,
+            &BloodStatus::Available,
+        );
+        requests.seed_request(&10, &hospital, &BloodType::OPositive, &900);
+        requests.set_rare_phenotype(&10, &RarePhenotype::Bombay);
+
+        let result = client.match_rare_phenotype(&10);
+
+        assert_eq!(result.matched_units.len(), 2);
+        assert_eq!(result.total_matched_ml, 900);
+        assert_eq!(result.remaining_ml, 0);
+        let banks: Vec<Address> =
+            Vec::from_iter(&env, result.matched_units.iter().map(|m| m.bank_id));
+        assert!(banks.contains(&far_bank_a));
+        assert!(banks.contains(&far_bank_b));
+    }
+
+    #[test]
+    fn emits_alert_when_no_unit_exists_anywhere() {
+        let (env, client, inventory, requests) = setup();
+        let hospital = Address::generate(&env);
+        inventory.seed_unit(
+            &1,
+            &Address::generate(&env),
+            &BloodType::OPositive,
+            &RarePhenotype::Bombay,
+            &BloodStatus::Expired,
+        );
+        requests.seed_request(&10, &hospital, &BloodType::OPositive, &450);
+        requests.set_rare_phenotype(&10, &RarePhenotype::Bombay);
+
+        let result = client.match_rare_phenotype(&10);
+
+        assert_eq!(result.matched_units.len(), 0);
+        assert_eq!(result.remaining_ml, 450);
+        assert!(!result.partial_fulfillment);
+        assert_eq!(env.events().all().len(), 1);
+    }
+
+    #[test]
+    fn requires_phenotype_recorded_on_request() {
+        let (env, client, _, requests) = setup();
+        requests.seed_request(&10, &Address::generate(&env), &BloodType::OPositive, &450);
+
+        assert_eq!(
+            client.try_match_rare_phenotype(&10).err(),
+            Some(Ok(MatchingError::PhenotypeNotSet))
+        );
+    }
+
+    #[test]
+    fn reads_index_pages_until_request_is_covered() {
+        let (env, client, inventory, requests) = setup();
+        let bank = Address::generate(&env);
+        // The first full page holds only reserved units.
+        for id in 1..=25u64 {
+            inventory.seed_unit(
+                &id,
+                &bank,
+                &BloodType::OPositive,
+                &RarePhenotype::RhNull,
+                &BloodStatus::Reserved,
+            );
+        }
+        for id in 26..=30u64 {
+            inventory.seed_unit(
+                &id,
+                &bank,
+                &BloodType::OPositive,
+                &RarePhenotype::RhNull,
+                &BloodStatus::Available,
+            );
+        }
+        requests.seed_request(&10, &Address::generate(&env), &BloodType::OPositive, &900);
+        requests.set_rare_phenotype(&10, &RarePhenotype::RhNull);
+
+        let result = client.match_rare_phenotype(&10);
+
+        assert_eq!(result.matched_units.len(), 2);
+        assert_eq!(result.remaining_ml, 0);
+    }
+
+    #[test]
+    fn no_alert_when_scan_stops_at_page_cap() {
+        let (env, client, inventory, requests) = setup();
+        let bank = Address::generate(&env);
+        // Eight full pages of reserved units hide the available one behind them.
+        for id in 1..=200u64 {
+            inventory.seed_unit(
+                &id,
+                &bank,
+                &BloodType::OPositive,
+                &RarePhenotype::RhNull,
+                &BloodStatus::Reserved,
+            );
+        }
+        inventory.seed_unit(
+            &201,
+            &bank,
+            &BloodType::OPositive,
+            &RarePhenotype::RhNull,
+            &BloodStatus::Available,
+        );
+        requests.seed_request(&10, &Address::generate(&env), &BloodType::OPositive, &450);
+        requests.set_rare_phenotype(&10, &RarePhenotype::RhNull);
+
+        let result = client.match_rare_phenotype(&10);
+
+        assert_eq!(result.matched_units.len(), 0);
+        assert_eq!(env.events().all().len(), 0);
+    }
+}
+
+// ── Replacement for a lost unit ───────────────────────────────────────────────
+
+#[cfg(test)]
+mod replacement_tests {
+    use soroban_sdk::{
+        contract, contractimpl, contracttype, testutils::Address as _, vec, Address, Env, Map, Vec,
+    };
+
+    use crate::{
+        BloodStatus, BloodType, BloodUnit, MatchingContract, MatchingContractClient, RarePhenotype,
+        Urgency,
+    };
+
+    #[contracttype]
+    enum MockKey {
+        Unit(u64),
+        Type(BloodType),
+    }
+
+    #[contract]
+    struct MockInventory;
+
+    #[contractimpl]
+    impl MockInventory {
+        pub fn seed_unit(
+            env: Env,
+            id: u64,
+            bank: Address,
+            blood_type: BloodType,
+            expiration_timestamp: u64,
+        ) {
+            let unit = BloodUnit {
+                id,
+                blood_type,
+                quantity_ml: 450,
+                bank_id: bank,
+                donor_id: None,
+                donation_timestamp: 0,
+                expiration_timestamp,
+                status: BloodStatus::Available,
+                metadata: Map::new(&env),
+            };
+            env.storage().persistent().set(&MockKey::Unit(id), &unit);
+            let key = MockKey::Type(blood_type);
+            let mut ids: Vec<u64> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(&env));
+            ids.push_back(id);
+            env.storage().persistent().set(&key, &ids);
+        }
+
+        pub fn get_blood_unit(env: Env, blood_unit_id: u64) -> BloodUnit {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Unit(blood_unit_id))
+                .unwrap()
+        }
+
+        pub fn get_units_by_blood_type(env: Env, blood_type: BloodType) -> Vec<u64> {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Type(blood_type))
+                .unwrap_or(Vec::new(&env))
+        }
+
+        pub fn get_units_by_phenotype(env: Env, _phenotype: RarePhenotype) -> Vec<u64> {
+            Vec::new(&env)
+        }
+    }
+
+    #[test]
+    fn picks_compatible_unit_from_the_same_bank() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let inv_id = env.register(MockInventory, ());
+        let inventory = MockInventoryClient::new(&env, &inv_id);
+        let client = MatchingContractClient::new(&env, &env.register(MatchingContract, ()));
+        client.initialize(&Address::generate(&env), &inv_id, &Address::generate(&env));
+
+        let bank = Address::generate(&env);
+        let other_bank = Address::generate(&env);
+        inventory.seed_unit(&1, &bank, &BloodType::OPositive, &5_000);
+        inventory.seed_unit(&2, &other_bank, &BloodType::OPositive, &1_000);
+        inventory.seed_unit(&3, &bank, &BloodType::ONegative, &2_000);
+        inventory.seed_unit(&4, &bank, &BloodType::OPositive, &9_000);
+        inventory.seed_unit(&5, &bank, &BloodType::APositive, &1_000);
+
+        // Exact matches first, oldest first; unit 1 is excluded.
+        let found = client.find_replacement(
+            &BloodType::OPositive,
+            &Urgency::Critical,
+            &450,
+            &bank,
+            &vec![&env, 1u64],
+        );
+        assert_eq!(found, Some(4));
+
+        let found = client.find_replacement(
+            &BloodType::OPositive,
+            &Urgency::Critical,
+            &450,
+            &bank,
+            &vec![&env, 1u64, 4u64],
+        );
+        assert_eq!(found, Some(3));
+
+        let found = client.find_replacement(
+            &BloodType::ONegative,
+            &Urgency::Critical,
+            &450,
+            &other_bank,
+            &Vec::new(&env),
+        );
+        assert_eq!(found, None);
+    }
+}
+
+#[cfg(test)]
+mod upgrade_tests {
+    use lifebank_types::testutils::upload_placeholder_wasm;
+    use soroban_sdk::{testutils::Address as _, Address, Env};
+
+    use crate::{MatchingContract, MatchingContractClient, MatchingError};
+
+    #[test]
+    fn keeps_pause_state_and_migrate_is_admin_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(MatchingContract, ());
+        let client = MatchingContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin, &Address::generate(&env), &Address::generate(&env));
+        client.pause(&admin);
+        let hash = upload_placeholder_wasm(&env);
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_upgrade(&stranger, &hash),
+            Err(Ok(MatchingError::Unauthorized))
+        );
+
+        client.upgrade(&admin, &hash);
+        env.register_at(&contract_id, MatchingContract, ());
+
+        assert!(client.is_paused());
+        assert_eq!(client.get_admin(), admin);
+        assert_eq!(client.schema_version(), 1);
+        assert_eq!(
+            client.try_migrate(&stranger, &1),
+            Err(Ok(MatchingError::Unauthorized))
+        );
+        assert!(client.migrate(&admin, &1));
+    }
+}
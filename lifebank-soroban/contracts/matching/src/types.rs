@@ -1,112 +1,8 @@
-use soroban_sdk::{contracttype, Address, Map, String, Symbol, Vec};
-
-// ---------------------------------------------------------------------------
-// Shared domain types (must stay in sync with inventory/requests contracts)
-// ---------------------------------------------------------------------------
-
-/// All eight ABO/Rh blood groups.
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum BloodType {
-    APositive,
-    ANegative,
-    BPositive,
-    BNegative,
-    ABPositive,
-    ABNegative,
-    OPositive,
-    ONegative,
-}
-
-/// Urgency levels for a blood request.
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum Urgency {
-    Critical,
-    Urgent,
-    Routine,
-    Scheduled,
-}
-
-impl Urgency {
-    /// Numeric priority — higher is more urgent.
-    pub fn priority(self) -> u32 {
-        match self {
-            Self::Critical  => 4,
-            Self::Urgent    => 3,
-            Self::Routine   => 2,
-            Self::Scheduled => 1,
-        }
-    }
-}
-
-/// Blood unit status — mirrors inventory contract's `BloodStatus`.
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum BloodStatus {
-    Available,
-    Reserved,
-    InTransit,
-    Delivered,
-    Expired,
-    Compromised,
-    Disposed,
-}
-
-/// Blood unit view returned by the inventory contract.
-/// Must match `inventory_contract::types::BloodUnit` exactly.
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct BloodUnit {
-    pub id: u64,
-    pub blood_type: BloodType,
-    pub quantity_ml: u32,
-    pub bank_id: Address,
-    pub donor_id: Option<Address>,
-    pub donation_timestamp: u64,
-    pub expiration_timestamp: u64,
-    pub status: BloodStatus,
-    pub metadata: Map<Symbol, String>,
-}
-
-/// Blood component type — mirrors requests contract's `BloodComponent`.
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum BloodComponent {
-    WholeBlood,
-    RedCells,
-    Plasma,
-    Platelets,
-    Cryoprecipitate,
-}
-
-/// Blood request view returned by the requests contract.
-/// Must match `request_contract::types::BloodRequest` exactly.
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct BloodRequest {
-    pub id: u64,
-    pub hospital_id: Address,
-    pub blood_type: BloodType,
-    pub component: BloodComponent,
-    pub quantity_ml: u32,
-    pub urgency: Urgency,
-    pub created_timestamp: u64,
-    pub required_by_timestamp: u64,
-    pub status: RequestStatus,
-    pub assigned_units: Vec<u64>,
-    pub fulfilled_quantity_ml: u32,
-}
-
-/// Request status — mirrors requests contract's `RequestStatus`.
-#[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum RequestStatus {
-    Pending,
-    Approved,
-    Fulfilled,
-    Cancelled,
-}
+pub use lifebank_types::{
+    BloodComponent, BloodRequest, BloodStatus, BloodType, BloodUnit, RarePhenotype, RequestStatus,
+    Urgency,
+};
+use soroban_sdk::{contracttype, Address, Vec};
 
 // ---------------------------------------------------------------------------
 // Matching-specific types
@@ -166,4 +62,8 @@ pub enum DataKey {
     RequestsContract,
     Initialized,
     Paused,
+    /// Deployment registry consulted before the contract addresses above.
+    Registry,
+    /// Storage schema version in effect; absent means 1.
+    SchemaVersion,
 }
@@ -1,52 +1,13 @@
 #![no_std]
 use soroban_sdk::token;
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Map, String,
-    Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, Address,
+    BytesN, Env, Map, String, Symbol, Val, Vec,
 };
 
 // ── Types ──────────────────────────────────────────────────────────────────────
 
-#[contracttype]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum PaymentStatus {
-    Pending,
-    Locked,
-    Released,
-    Refunded,
-    Disputed,
-    Cancelled,
-}
-
-#[contracttype]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum DisputeReason {
-    FailedDelivery,
-    TemperatureExcursion,
-    PaymentContested,
-    WrongItem,
-    DamagedGoods,
-    LateDelivery,
-    Other,
-}
-
-#[contracttype]
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Payment {
-    pub id: u64,
-    pub request_id: u64,
-    pub payer: Address,
-    pub payee: Address,
-    pub amount: i128,
-    pub status: PaymentStatus,
-    pub created_at: u64,
-    pub updated_at: u64,
-    pub dispute_reason_code: Option<u32>,
-    pub dispute_case_id: Option<String>,
-    pub dispute_resolved: bool,
-    /// Token contract address — set only for escrow-backed payments.
-    pub token: Option<Address>,
-}
+pub use lifebank_types::{BloodComponent, DisputeReason, FeeBreakdown, Payment, PaymentStatus};
 
 fn dispute_reason_to_code(reason: DisputeReason) -> u32 {
     match reason {
@@ -60,6 +21,10 @@ fn dispute_reason_to_code(reason: DisputeReason) -> u32 {
     }
 }
 
+/// Payment totals. Contract-wide stats are raw sums of every token's
+/// smallest units, so their amounts are only meaningful while a single
+/// token is in use; `get_token_statistics` keeps them apart per escrow token
+/// and `get_normalized_statistics` values them in one currency.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PaymentStats {
@@ -80,6 +45,15 @@ pub struct PaymentPage {
     pub page_size: u32,
 }
 
+/// A page of payments in ascending ID order. Pass `next_cursor` back to
+/// read the following page; `None` means there are no more payments.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentCursorPage {
+    pub items: Vec<Payment>,
+    pub next_cursor: Option<u64>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DonationPledge {
@@ -95,15 +69,317 @@ pub struct DonationPledge {
     pub created_at: u64,
 }
 
-/// On-chain vesting schedule for donor reward tokens.
+/// Token funding behind a pledge, kept next to the `DonationPledge` record.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PledgeFunding {
+    /// Token the donor approved this contract to pull from.
+    pub token: Address,
+    /// Start of the next period to collect.
+    pub next_due: u64,
+    pub periods_executed: u32,
+    pub periods_skipped: u32,
+    pub total_given: i128,
+    /// Set when the last due period could not be collected because the
+    /// donor's allowance or balance was short.
+    pub underfunded: bool,
+}
+
+/// One due period of a pledge, collected or skipped.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PledgeExecution {
+    pub pledge_id: u64,
+    pub period: u32,
+    pub amount: i128,
+    pub executed_at: u64,
+    /// `false` when the period was skipped for lack of allowance or balance.
+    pub collected: bool,
+}
+
+/// A donation pool, named by a pledge's `payee_pool` and `region`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolId {
+    pub name: String,
+    pub region: String,
+}
+
+/// Fees charged on escrow release. Rates are in basis points of the
+/// escrowed amount; surcharges apply on top of the platform fee for Critical
+/// and Urgent requests.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeePolicy {
+    pub treasury: Address,
+    pub platform_fee_bps: u32,
+    pub fixed_fee: i128,
+    pub critical_surcharge_bps: u32,
+    pub urgent_surcharge_bps: u32,
+}
+
+/// Asset identifier of a SEP-40 price feed.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(soroban_sdk::Symbol),
+}
+
+/// A SEP-40 price, scaled by the oracle's `decimals`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// The subset of the SEP-40 price feed interface used to value escrow
+/// totals in the oracle's reference currency.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OracleConfig {
+    pub oracle: Address,
+    /// Prices older than this are ignored.
+    pub max_age_secs: u64,
+}
+
+/// Per-token totals valued in the oracle's reference currency, scaled by
+/// `decimals`. Tokens without a fresh price are left out and listed in
+/// `unpriced`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NormalizedStats {
+    pub decimals: u32,
+    pub total_locked: i128,
+    pub total_released: i128,
+    pub total_refunded: i128,
+    pub unpriced: Vec<Address>,
+}
+
+/// What has to happen before a milestone may be released. The settler
+/// releasing the milestone vouches for it.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MilestoneCondition {
+    /// The blood unit with this ID was delivered.
+    UnitDelivered(u64),
+    /// The workflow leg at this index was delivered.
+    LegDelivered(u32),
+    /// Released at the settler's discretion.
+    Manual,
+}
+
+/// One tranche of an escrow.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    pub amount: i128,
+    pub condition: MilestoneCondition,
+    pub released_at: Option<u64>,
+}
+
+/// Who must approve a disbursement from a pool.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DisbursementPolicy {
+    /// The contract admin alone.
+    Admin,
+    /// At least `threshold` of `signers`, all authorising the same call.
+    Multisig(MultisigPolicy),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigPolicy {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// On-chain account for a donation pool. Balances are kept per token.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DonationPool {
+    pub id: PoolId,
+    pub emergency: bool,
+    pub policy: DisbursementPolicy,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolEntryKind {
+    /// A pledge period collected into the pool.
+    Deposit,
+    /// Funds paid out to the hospital behind a blood request.
+    Disbursement,
+}
+
+/// One line of a pool's public ledger.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolLedgerEntry {
+    pub kind: PoolEntryKind,
+    pub token: Address,
+    pub amount: i128,
+    /// Donor for a deposit, hospital for a disbursement.
+    pub counterparty: Address,
+    pub pledge_id: Option<u64>,
+    pub request_id: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// How a dispute is settled.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisputeOutcome {
+    /// The whole amount goes back to the payer.
+    Refund,
+    /// The whole amount goes to the payee.
+    Release,
+    /// The payee receives this many basis points of the amount and the
+    /// payer the rest.
+    Split(u32),
+}
+
+/// Arbiter's decision on a dispute, kept alongside the payment.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeResolution {
+    pub payment_id: u64,
+    pub arbiter: Address,
+    pub outcome: DisputeOutcome,
+    pub payee_amount: i128,
+    pub payer_amount: i128,
+    /// Hash of the off-chain evidence the decision rests on.
+    pub evidence_hash: BytesN<32>,
+    pub resolved_at: u64,
+}
+
+/// On-chain vesting schedule for donor reward tokens. The contract holds
+/// `total_amount - claimed` of `token` for it.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct VestingSchedule {
+    pub id: u64,
     pub donor: Address,
+    pub token: Address,
     pub total_amount: i128,
     pub cliff_timestamp: u64,
     pub vest_end_timestamp: u64,
     pub claimed: i128,
+    /// Set when the admin revoked the unvested part; `total_amount` is then
+    /// what had vested at revocation.
+    pub revoked: bool,
+}
+
+/// One line of an invoice: `units` of `component` delivered against
+/// `request_id`, billed at `unit_price` each.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceLine {
+    pub request_id: u64,
+    pub units: u32,
+    pub component: BloodComponent,
+    pub unit_price: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvoiceStatus {
+    /// Waiting for the hospital to accept it.
+    Issued,
+    /// Accepted; `payment_id` holds the escrow that pays it.
+    Accepted,
+    Cancelled,
+}
+
+/// A bank's bill to a hospital for delivered requests. Accepting it locks
+/// `total` of `token` in one escrow payment from the hospital to the bank.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invoice {
+    pub id: u64,
+    pub bank: Address,
+    pub hospital: Address,
+    pub token: Address,
+    pub lines: Vec<InvoiceLine>,
+    pub total: i128,
+    pub status: InvoiceStatus,
+    pub issued_at: u64,
+    pub payment_id: Option<u64>,
+}
+
+/// Statement amounts in one token; `token` is `None` for payments recorded
+/// without escrow.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementTotals {
+    pub token: Option<Address>,
+    pub invoiced: i128,
+    pub paid: i128,
+    pub released: i128,
+    pub refunded: i128,
+    pub fees: i128,
+}
+
+/// A hospital's billing summary for invoices issued and payments made from
+/// `from` to `to`, both inclusive. Counts and totals reflect each item's
+/// current state.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BillingStatement {
+    pub hospital: Address,
+    pub from: u64,
+    pub to: u64,
+    pub invoices_issued: u32,
+    pub invoices_accepted: u32,
+    pub invoices_open: u32,
+    pub payments: u32,
+    /// Payments that returned any amount to the hospital.
+    pub refunds: u32,
+    /// Payments that were disputed, resolved or not.
+    pub disputes: u32,
+    pub open_disputes: u32,
+    pub totals: Vec<StatementTotals>,
+}
+
+/// Payment layout of schema version 1, before partial releases and fees were
+/// recorded. Only read by `migrate`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PaymentV1 {
+    id: u64,
+    request_id: u64,
+    payer: Address,
+    payee: Address,
+    amount: i128,
+    status: PaymentStatus,
+    created_at: u64,
+    updated_at: u64,
+    dispute_reason_code: Option<u32>,
+    dispute_case_id: Option<String>,
+    dispute_resolved: bool,
+    token: Option<Address>,
+}
+
+/// Vesting schedule layout of schema version 2 and earlier, stored under the
+/// donor's address before donors could hold several funded schedules. Only
+/// read by `migrate_vesting`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct VestingScheduleV1 {
+    donor: Address,
+    total_amount: i128,
+    cliff_timestamp: u64,
+    vest_end_timestamp: u64,
+    claimed: i128,
 }
 
 #[contracterror]
@@ -133,12 +409,92 @@ pub enum Error {
     PaymentNotLocked = 515,
     /// Dispute timeout has not yet elapsed.
     DisputeNotExpired = 516,
+    /// Partial settlement amount is zero or exceeds the escrowed amount.
+    InvalidSettlementAmount = 517,
+    /// `migrate` was called with a version other than the stored schema version.
+    SchemaMismatch = 518,
+    /// The payment's current status does not allow the requested change.
+    InvalidStatusTransition = 519,
+    /// The payment is not under dispute.
+    PaymentNotDisputed = 520,
+    /// Escrow-backed payments change status only through the settlement
+    /// calls, so the token balance always follows the status.
+    EscrowManagedStatus = 521,
+    /// A split outcome above 10 000 basis points.
+    InvalidSplit = 522,
+    /// No pool account exists for this pool.
+    PoolNotFound = 523,
+    /// A pool account already exists for this pool.
+    PoolExists = 524,
+    /// The pool holds less of the token than the disbursement asks for.
+    InsufficientPoolBalance = 525,
+    /// A multisig policy needs a threshold between 1 and the signer count.
+    InvalidPolicy = 526,
+    /// A fee policy with a negative fixed fee or rates above 10 000 basis
+    /// points.
+    InvalidFeePolicy = 527,
+    /// Milestones must be positive, at most `MAX_MILESTONES`, sum to the
+    /// escrow and be set once, before anything is released.
+    InvalidMilestones = 528,
+    /// The payment has no milestone at this index.
+    MilestoneNotFound = 529,
+    MilestoneAlreadyReleased = 530,
+    /// The token is not on the accepted-token allowlist.
+    TokenNotAllowed = 531,
+    /// No price oracle is configured.
+    OracleNotSet = 532,
+    /// The vesting schedule was already revoked.
+    VestingRevoked = 533,
+    InvoiceNotFound = 534,
+    /// Invoices need 1 to `MAX_INVOICE_LINES` lines with positive units and
+    /// prices, each for a different request of the invoiced hospital.
+    InvalidInvoice = 535,
+    /// The invoice was already accepted or cancelled.
+    InvoiceNotOpen = 536,
+    /// Only fulfilled requests can be invoiced.
+    RequestNotDelivered = 537,
+    /// The request is already on an open or accepted invoice.
+    RequestAlreadyInvoiced = 538,
+    /// A statement period that ends before it starts.
+    InvalidPeriod = 539,
+    /// The withdrawal exceeds the payee's claimable balance.
+    InsufficientBalance = 540,
+    /// More donors than `MIGRATION_BATCH_SIZE` passed to `migrate_vesting`.
+    MigrationBatchTooLarge = 541,
+    /// A token's scale or a normalized total does not fit in an `i128`.
+    NormalizationOverflow = 542,
+    /// The invoicing bank is not a verified blood bank in the identity
+    /// contract.
+    BankNotVerified = 543,
 }
 
+/// Basis points in a whole amount.
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Most milestones a single escrow can be split into.
+const MAX_MILESTONES: u32 = 32;
+
+/// Most lines one invoice may carry.
+const MAX_INVOICE_LINES: u32 = 32;
+
+/// Pledges visited per `execute_due_pledges` call, whatever `max` is.
+const MAX_PLEDGES_SCANNED: u64 = 50;
+
+/// Periods stored per page of a pledge's execution history.
+const PLEDGE_HISTORY_PAGE_SIZE: u32 = 50;
+
+/// Records visited per `migrate` call, so a step stays within ledger limits
+/// however many payments are stored.
+const MIGRATION_BATCH_SIZE: u64 = 50;
+
 // ── Storage keys ───────────────────────────────────────────────────────────────
 
 const PAYMENT_COUNTER: soroban_sdk::Symbol = symbol_short!("PAY_CTR");
 const PLEDGE_COUNTER: soroban_sdk::Symbol = symbol_short!("PLG_CTR");
+/// Instance storage key for the pledge `execute_due_pledges` resumes at.
+const PLEDGE_CURSOR: soroban_sdk::Symbol = symbol_short!("PLG_CUR");
+const VESTING_COUNTER: soroban_sdk::Symbol = symbol_short!("VST_CTR");
+const INVOICE_COUNTER: soroban_sdk::Symbol = symbol_short!("INV_CTR");
 const ADMIN_KEY: soroban_sdk::Symbol = symbol_short!("ADMIN");
 const PAUSED_KEY: soroban_sdk::Symbol = symbol_short!("PAUSED");
 const REWARD_TOKEN_KEY: soroban_sdk::Symbol = symbol_short!("RWD_TOK");
@@ -152,6 +508,29 @@ const REQ_CONTRACT: soroban_sdk::Symbol = symbol_short!("REQ_CTR");
 const DEFAULT_DISPUTE_TIMEOUT_SECS: u64 = 7 * 24 * 3600;
 /// Instance storage key for the dispute timeout override.
 const DISPUTE_TIMEOUT: soroban_sdk::Symbol = symbol_short!("DISP_TO");
+/// Instance storage key for the coordinator contract allowed to settle escrow.
+const COORD_KEY: soroban_sdk::Symbol = symbol_short!("COORD");
+/// Instance storage key for the deployment registry that overrides the
+/// requests contract and coordinator stored here.
+const REGISTRY_KEY: soroban_sdk::Symbol = symbol_short!("REGISTRY");
+/// Instance storage key for the fee policy applied at escrow release.
+const FEE_POLICY_KEY: soroban_sdk::Symbol = symbol_short!("FEE_POL");
+/// Instance storage key for the tokens that have per-token stats.
+const STATS_TOKENS_KEY: soroban_sdk::Symbol = symbol_short!("STAT_TOK");
+/// Instance storage key for the accepted-token allowlist; absent means any
+/// token is accepted.
+const TOKEN_ALLOWLIST_KEY: soroban_sdk::Symbol = symbol_short!("TOK_OK");
+/// Instance storage key for the price oracle configuration.
+const ORACLE_KEY: soroban_sdk::Symbol = symbol_short!("ORACLE");
+/// Instance-level storage schema version; absent means 1.
+const SCHEMA_KEY: soroban_sdk::Symbol = symbol_short!("SCHEMA");
+/// Instance storage key for the next record a resumable `migrate` step
+/// visits; absent means 1.
+const MIGRATION_CURSOR_KEY: soroban_sdk::Symbol = symbol_short!("MIG_CUR");
+
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 3;
 
 fn payment_key(id: u64) -> (u64, &'static str) {
     (id, "pay")
@@ -181,6 +560,29 @@ fn status_index_key(status: PaymentStatus) -> (u32, &'static str) {
     (code, "si")
 }
 
+/// The registry's `requests` entry when a registry is set, otherwise the
+/// requests contract given at initialization, if any.
+fn requests_contract(env: &Env) -> Option<Address> {
+    resolve_service(env, env.storage().instance().get(&REGISTRY_KEY), "requests")
+        .or_else(|| env.storage().instance().get(&REQ_CONTRACT))
+}
+
+/// The registry's `identity` entry, if a registry is set.
+fn identity_contract(env: &Env) -> Option<Address> {
+    resolve_service(env, env.storage().instance().get(&REGISTRY_KEY), "identity")
+}
+
+/// The registry's `coordinator` entry when a registry is set, otherwise the
+/// coordinator set by the admin, if any.
+fn coordinator(env: &Env) -> Option<Address> {
+    resolve_service(
+        env,
+        env.storage().instance().get(&REGISTRY_KEY),
+        "coordinator",
+    )
+    .or_else(|| env.storage().instance().get(&COORD_KEY))
+}
+
 fn get_counter(env: &Env) -> u64 {
     env.storage()
         .instance()
@@ -223,186 +625,522 @@ fn load_pledge(env: &Env, id: u64) -> Option<DonationPledge> {
     env.storage().persistent().get(&pledge_key(id))
 }
 
-fn vesting_key(donor: &Address) -> (Address, &'static str) {
-    (donor.clone(), "vest")
+fn pledge_funding_key(id: u64) -> (u64, &'static str) {
+    (id, "plf")
 }
 
-fn store_vesting(env: &Env, schedule: &VestingSchedule) {
-    env.storage()
-        .persistent()
-        .set(&vesting_key(&schedule.donor), schedule);
+/// Last page number of a pledge's execution history.
+fn pledge_history_pages_key(id: u64) -> (u64, &'static str) {
+    (id, "plxn")
 }
 
-fn load_vesting(env: &Env, donor: &Address) -> Option<VestingSchedule> {
-    env.storage().persistent().get(&vesting_key(donor))
+fn pledge_history_key(id: u64, page: u32) -> (u64, u32, &'static str) {
+    (id, page, "plx")
 }
 
-// ── Index helpers ──────────────────────────────────────────────────────────────
+fn pool_balance_key(pool: &PoolId, token: &Address) -> (PoolId, Address, &'static str) {
+    (pool.clone(), token.clone(), "pool")
+}
 
-fn index_by_payer(env: &Env, payer: &Address, id: u64) {
-    let key = payer_index_key(payer);
-    let mut ids: Vec<u64> = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .unwrap_or(Vec::new(env));
-    ids.push_back(id);
-    env.storage().persistent().set(&key, &ids);
+fn donor_total_key(donor: &Address, token: &Address) -> (Address, Address, &'static str) {
+    (donor.clone(), token.clone(), "dgv")
 }
 
-fn index_by_payee(env: &Env, payee: &Address, id: u64) {
-    let key = payee_index_key(payee);
-    let mut ids: Vec<u64> = env
-        .storage()
-        .persistent()
-        .get(&key)
-        .unwrap_or(Vec::new(env));
-    ids.push_back(id);
-    env.storage().persistent().set(&key, &ids);
+fn load_pledge_funding(env: &Env, id: u64) -> Option<PledgeFunding> {
+    env.storage().persistent().get(&pledge_funding_key(id))
 }
 
-fn index_by_status(env: &Env, status: PaymentStatus, id: u64) {
-    let key = status_index_key(status);
-    let mut ids: Vec<u64> = env
-        .storage()
+fn store_pledge_funding(env: &Env, id: u64, funding: &PledgeFunding) {
+    env.storage()
         .persistent()
-        .get(&key)
-        .unwrap_or(Vec::new(env));
-    ids.push_back(id);
-    env.storage().persistent().set(&key, &ids);
+        .set(&pledge_funding_key(id), funding);
 }
 
-fn index_by_request(env: &Env, request_id: u64, payment_id: u64) {
-    let mut map: Map<u64, u64> = env
-        .storage()
-        .instance()
-        .get(&REQ_IDX)
-        .unwrap_or(Map::new(env));
-    map.set(request_id, payment_id);
-    env.storage().instance().set(&REQ_IDX, &map);
+fn get_pledge_history_last_page(env: &Env, id: u64) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&pledge_history_pages_key(id))
+        .unwrap_or(0)
 }
 
-/// Remove `id` from the persistent Vec stored under the given status index key.
-fn remove_from_status_index(env: &Env, status: PaymentStatus, id: u64) {
-    let key = status_index_key(status);
-    let ids: Vec<u64> = env
-        .storage()
+fn load_pledge_history_page(env: &Env, id: u64, page: u32) -> Vec<PledgeExecution> {
+    env.storage()
         .persistent()
-        .get(&key)
-        .unwrap_or(Vec::new(env));
-    let mut new_ids: Vec<u64> = Vec::new(env);
-    for i in 0..ids.len() {
-        let existing = ids.get(i).unwrap();
-        if existing != id {
-            new_ids.push_back(existing);
+        .get(&pledge_history_key(id, page))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Append executions to a pledge's history, starting a new page whenever
+/// the last one holds `PLEDGE_HISTORY_PAGE_SIZE` entries.
+fn append_pledge_history(env: &Env, id: u64, executions: &Vec<PledgeExecution>) {
+    if executions.is_empty() {
+        return;
+    }
+    let mut last = get_pledge_history_last_page(env, id);
+    let mut page = load_pledge_history_page(env, id, last);
+    for execution in executions.iter() {
+        if page.len() >= PLEDGE_HISTORY_PAGE_SIZE {
+            env.storage()
+                .persistent()
+                .set(&pledge_history_key(id, last), &page);
+            last += 1;
+            page = Vec::new(env);
         }
+        page.push_back(execution);
     }
-    env.storage().persistent().set(&key, &new_ids);
+    env.storage()
+        .persistent()
+        .set(&pledge_history_key(id, last), &page);
+    env.storage()
+        .persistent()
+        .set(&pledge_history_pages_key(id), &last);
 }
 
-// ── Stats helpers ──────────────────────────────────────────────────────────────
+fn get_pool_balance(env: &Env, pool: &PoolId, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&pool_balance_key(pool, token))
+        .unwrap_or(0)
+}
 
-fn load_stats(env: &Env) -> PaymentStats {
+fn set_pool_balance(env: &Env, pool: &PoolId, token: &Address, balance: i128) {
     env.storage()
-        .instance()
-        .get(&STATS_KEY)
-        .unwrap_or(PaymentStats {
-            total_locked: 0,
-            total_released: 0,
-            total_refunded: 0,
-            count_locked: 0,
-            count_released: 0,
-            count_refunded: 0,
-        })
+        .persistent()
+        .set(&pool_balance_key(pool, token), &balance);
 }
 
-fn store_stats(env: &Env, stats: &PaymentStats) {
-    env.storage().instance().set(&STATS_KEY, stats);
+fn pool_key(pool: &PoolId) -> (PoolId, &'static str) {
+    (pool.clone(), "pacct")
 }
 
-fn update_stats_on_transition(env: &Env, amount: i128, old: PaymentStatus, new: PaymentStatus) {
-    let mut stats = load_stats(env);
-    match old {
-        PaymentStatus::Locked => {
-            stats.total_locked -= amount;
-            stats.count_locked = stats.count_locked.saturating_sub(1);
-        }
-        PaymentStatus::Released => {
-            stats.total_released -= amount;
-            stats.count_released = stats.count_released.saturating_sub(1);
-        }
-        PaymentStatus::Refunded => {
-            stats.total_refunded -= amount;
-            stats.count_refunded = stats.count_refunded.saturating_sub(1);
-        }
-        _ => {}
-    }
-    match new {
-        PaymentStatus::Locked => {
-            stats.total_locked += amount;
-            stats.count_locked += 1;
-        }
-        PaymentStatus::Released => {
-            stats.total_released += amount;
-            stats.count_released += 1;
-        }
-        PaymentStatus::Refunded => {
-            stats.total_refunded += amount;
-            stats.count_refunded += 1;
-        }
-        _ => {}
-    }
-    store_stats(env, &stats);
+fn pool_ledger_len_key(pool: &PoolId) -> (PoolId, &'static str) {
+    (pool.clone(), "plen")
 }
 
-// ── Request-contract cross-contract interface (minimal) ────────────────────────
+fn pool_ledger_key(pool: &PoolId, index: u32) -> (PoolId, u32, &'static str) {
+    (pool.clone(), index, "pled")
+}
 
-mod request_client {
-    use soroban_sdk::{contractclient, contracttype, Env};
+fn load_pool(env: &Env, pool: &PoolId) -> Option<DonationPool> {
+    env.storage().persistent().get(&pool_key(pool))
+}
 
-    #[contracttype]
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    pub enum RequestStatus {
-        Pending,
-        Approved,
-        Fulfilled,
-        Cancelled,
-    }
+fn get_pool_ledger_len(env: &Env, pool: &PoolId) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&pool_ledger_len_key(pool))
+        .unwrap_or(0)
+}
 
-    #[contracttype]
-    #[derive(Clone, Debug)]
-    pub struct BloodRequest {
-        pub id: u64,
-        pub status: RequestStatus,
-    }
+fn append_pool_ledger(env: &Env, pool: &PoolId, entry: &PoolLedgerEntry) {
+    let len = get_pool_ledger_len(env, pool);
+    env.storage()
+        .persistent()
+        .set(&pool_ledger_key(pool, len), entry);
+    env.storage()
+        .persistent()
+        .set(&pool_ledger_len_key(pool), &(len + 1));
+}
 
-    #[contractclient(name = "RequestContractClient")]
-    pub trait RequestContractInterface {
-        fn get_request(env: Env, request_id: u64) -> BloodRequest;
-        fn update_request_status(
-            env: Env,
-            caller: soroban_sdk::Address,
-            request_id: u64,
-            new_status: RequestStatus,
-        ) -> Result<(), soroban_sdk::Error>;
-    }
+fn resolution_key(payment_id: u64) -> (u64, &'static str) {
+    (payment_id, "res")
 }
 
-use request_client::{RequestContractClient, RequestStatus as ReqStatus};
+fn milestones_key(payment_id: u64) -> (u64, &'static str) {
+    (payment_id, "mls")
+}
 
-/// Returns Ok(()) if `request_id` exists and is in Pending or Approved status.
-fn validate_request_payable(
-    env: &Env,
-    requests_contract: &Address,
-    request_id: u64,
-) -> Result<(), Error> {
-    let client = RequestContractClient::new(env, requests_contract);
-    let req = client
-        .try_get_request(&request_id)
-        .map_err(|_| Error::RequestNotFound)?
-        .map_err(|_| Error::RequestNotFound)?;
-    match req.status {
-        ReqStatus::Pending | ReqStatus::Approved => Ok(()),
+fn load_milestones(env: &Env, payment_id: u64) -> Vec<Milestone> {
+    env.storage()
+        .persistent()
+        .get(&milestones_key(payment_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Escrowed funds not yet paid to the payee.
+fn remaining_escrow(payment: &Payment) -> i128 {
+    payment.amount - payment.released_amount
+}
+
+fn vesting_key(schedule_id: u64) -> (u64, &'static str) {
+    (schedule_id, "vest")
+}
+
+fn donor_vestings_key(donor: &Address) -> (Address, &'static str) {
+    (donor.clone(), "vests")
+}
+
+fn store_vesting(env: &Env, schedule: &VestingSchedule) {
+    env.storage()
+        .persistent()
+        .set(&vesting_key(schedule.id), schedule);
+}
+
+fn load_vesting(env: &Env, schedule_id: u64) -> Option<VestingSchedule> {
+    env.storage().persistent().get(&vesting_key(schedule_id))
+}
+
+fn load_donor_vestings(env: &Env, donor: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&donor_vestings_key(donor))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Amount of `schedule` vested at `now`: nothing before the cliff, then
+/// linear up to the end.
+fn vested_amount(schedule: &VestingSchedule, now: u64) -> i128 {
+    if now < schedule.cliff_timestamp {
+        0
+    } else if now >= schedule.vest_end_timestamp {
+        schedule.total_amount
+    } else {
+        let elapsed = now - schedule.cliff_timestamp;
+        let duration = schedule.vest_end_timestamp - schedule.cliff_timestamp;
+        (schedule.total_amount * elapsed as i128) / duration as i128
+    }
+}
+
+fn invoice_key(invoice_id: u64) -> (u64, &'static str) {
+    (invoice_id, "inv")
+}
+
+fn hospital_invoices_key(hospital: &Address) -> (Address, &'static str) {
+    (hospital.clone(), "hinv")
+}
+
+fn invoiced_request_key(request_id: u64) -> (u64, &'static str) {
+    (request_id, "rinv")
+}
+
+fn store_invoice(env: &Env, invoice: &Invoice) {
+    env.storage()
+        .persistent()
+        .set(&invoice_key(invoice.id), invoice);
+}
+
+fn load_invoice(env: &Env, invoice_id: u64) -> Option<Invoice> {
+    env.storage().persistent().get(&invoice_key(invoice_id))
+}
+
+fn load_hospital_invoices(env: &Env, hospital: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&hospital_invoices_key(hospital))
+        .unwrap_or(Vec::new(env))
+}
+
+/// First position in `ids`, which ascend by ID and so by creation time,
+/// whose item was created at or after `from`.
+fn first_created_from(ids: &Vec<u64>, from: u64, created_at: impl Fn(u64) -> u64) -> u32 {
+    let (mut lo, mut hi) = (0u32, ids.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if created_at(ids.get(mid).unwrap()) < from {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// The statement totals for `token`, added on first use.
+fn statement_totals(totals: &mut Vec<StatementTotals>, token: &Option<Address>) -> u32 {
+    if let Some(i) = totals.iter().position(|t| t.token == *token) {
+        return i as u32;
+    }
+    totals.push_back(StatementTotals {
+        token: token.clone(),
+        invoiced: 0,
+        paid: 0,
+        released: 0,
+        refunded: 0,
+        fees: 0,
+    });
+    totals.len() - 1
+}
+
+/// What a payment has paid out to the payee and back to the payer so far.
+/// Payments without escrow move their whole amount on Released or Refunded.
+fn settled_amounts(payment: &Payment) -> (i128, i128) {
+    match (payment.status, &payment.token) {
+        (PaymentStatus::Released, None) => (payment.amount, 0),
+        (PaymentStatus::Refunded, None) => (0, payment.amount),
+        (PaymentStatus::Released | PaymentStatus::Refunded, Some(_)) => (
+            payment.released_amount,
+            payment.amount - payment.released_amount,
+        ),
+        _ => (payment.released_amount, 0),
+    }
+}
+
+fn claimable_key(payee: &Address, token: &Address) -> (Address, Address, &'static str) {
+    (payee.clone(), token.clone(), "claim")
+}
+
+/// Set while `payee` has turned auto-push off.
+fn pull_only_key(payee: &Address) -> (Address, &'static str) {
+    (payee.clone(), "pull")
+}
+
+fn get_claimable(env: &Env, payee: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&claimable_key(payee, token))
+        .unwrap_or(0)
+}
+
+fn set_claimable(env: &Env, payee: &Address, token: &Address, balance: i128) {
+    if balance == 0 {
+        env.storage()
+            .persistent()
+            .remove(&claimable_key(payee, token));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&claimable_key(payee, token), &balance);
+    }
+}
+
+// ── Index helpers ──────────────────────────────────────────────────────────────
+
+fn index_by_payer(env: &Env, payer: &Address, id: u64) {
+    let key = payer_index_key(payer);
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    ids.push_back(id);
+    env.storage().persistent().set(&key, &ids);
+}
+
+fn index_by_payee(env: &Env, payee: &Address, id: u64) {
+    let key = payee_index_key(payee);
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    ids.push_back(id);
+    env.storage().persistent().set(&key, &ids);
+}
+
+/// Payments change status in any order, so the ID is inserted in place to
+/// keep the index ascending like the payer and payee indexes.
+fn index_by_status(env: &Env, status: PaymentStatus, id: u64) {
+    let key = status_index_key(status);
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    if let Err(position) = ids.binary_search(id) {
+        ids.insert(position, id);
+    }
+    env.storage().persistent().set(&key, &ids);
+}
+
+fn index_by_request(env: &Env, request_id: u64, payment_id: u64) {
+    let mut map: Map<u64, u64> = env
+        .storage()
+        .instance()
+        .get(&REQ_IDX)
+        .unwrap_or(Map::new(env));
+    map.set(request_id, payment_id);
+    env.storage().instance().set(&REQ_IDX, &map);
+}
+
+/// Remove `id` from the persistent Vec stored under the given status index key.
+fn remove_from_status_index(env: &Env, status: PaymentStatus, id: u64) {
+    let key = status_index_key(status);
+    let mut ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    if let Some(position) = ids.first_index_of(id) {
+        ids.remove(position);
+        env.storage().persistent().set(&key, &ids);
+    }
+}
+
+// ── Stats helpers ──────────────────────────────────────────────────────────────
+
+fn empty_stats() -> PaymentStats {
+    PaymentStats {
+        total_locked: 0,
+        total_released: 0,
+        total_refunded: 0,
+        count_locked: 0,
+        count_released: 0,
+        count_refunded: 0,
+    }
+}
+
+fn load_stats(env: &Env) -> PaymentStats {
+    env.storage()
+        .instance()
+        .get(&STATS_KEY)
+        .unwrap_or_else(empty_stats)
+}
+
+fn store_stats(env: &Env, stats: &PaymentStats) {
+    env.storage().instance().set(&STATS_KEY, stats);
+}
+
+fn token_stats_key(token: &Address) -> (Address, &'static str) {
+    (token.clone(), "tstats")
+}
+
+fn load_token_stats(env: &Env, token: &Address) -> Option<PaymentStats> {
+    env.storage().persistent().get(&token_stats_key(token))
+}
+
+/// Tokens that have per-token stats, in the order they were first seen.
+fn load_stats_tokens(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&STATS_TOKENS_KEY)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Apply `update` to the contract-wide stats and, for escrow payments, to the
+/// stats of their token.
+fn update_stats(env: &Env, token: &Option<Address>, update: impl Fn(&mut PaymentStats)) {
+    let mut stats = load_stats(env);
+    update(&mut stats);
+    store_stats(env, &stats);
+
+    if let Some(token) = token {
+        let mut token_stats = load_token_stats(env, token).unwrap_or_else(|| {
+            let mut tokens = load_stats_tokens(env);
+            tokens.push_back(token.clone());
+            env.storage().instance().set(&STATS_TOKENS_KEY, &tokens);
+            empty_stats()
+        });
+        update(&mut token_stats);
+        env.storage()
+            .persistent()
+            .set(&token_stats_key(token), &token_stats);
+    }
+}
+
+fn update_stats_on_transition(
+    env: &Env,
+    token: &Option<Address>,
+    amount: i128,
+    old: PaymentStatus,
+    new: PaymentStatus,
+) {
+    update_stats(env, token, |stats| {
+        match old {
+            PaymentStatus::Locked => {
+                stats.total_locked -= amount;
+                stats.count_locked = stats.count_locked.saturating_sub(1);
+            }
+            PaymentStatus::Released => {
+                stats.total_released -= amount;
+                stats.count_released = stats.count_released.saturating_sub(1);
+            }
+            PaymentStatus::Refunded => {
+                stats.total_refunded -= amount;
+                stats.count_refunded = stats.count_refunded.saturating_sub(1);
+            }
+            _ => {}
+        }
+        match new {
+            PaymentStatus::Locked => {
+                stats.total_locked += amount;
+                stats.count_locked += 1;
+            }
+            PaymentStatus::Released => {
+                stats.total_released += amount;
+                stats.count_released += 1;
+            }
+            PaymentStatus::Refunded => {
+                stats.total_refunded += amount;
+                stats.count_refunded += 1;
+            }
+            _ => {}
+        }
+    });
+}
+
+/// A settled escrow leaves `old`, Locked or Disputed, and counts once under
+/// its new status, Released or Refunded; the payee's share and the refunded
+/// remainder go to their own totals.
+fn update_stats_on_settlement(env: &Env, payment: &Payment, old: PaymentStatus) {
+    let amount = payment.amount;
+    let released = payment.released_amount;
+    let new = payment.status;
+    update_stats(env, &payment.token, |stats| {
+        if old == PaymentStatus::Locked {
+            stats.total_locked -= amount;
+            stats.count_locked = stats.count_locked.saturating_sub(1);
+        }
+        if new == PaymentStatus::Released {
+            stats.count_released += 1;
+        } else {
+            stats.count_refunded += 1;
+        }
+        stats.total_released += released;
+        stats.total_refunded += amount - released;
+    });
+}
+
+// ── Request-contract cross-contract interface ──────────────────────────────────
+
+use lifebank_types::clients::{IdentityContractClient, RequestContractClient};
+use lifebank_types::registry::resolve_service;
+use lifebank_types::{BloodRequest, OrgType, RequestStatus as ReqStatus, Urgency};
+
+/// Returns Ok(()) if `request_id` exists and is in Pending or Approved status.
+fn validate_request_payable(
+    env: &Env,
+    requests_contract: &Address,
+    request_id: u64,
+) -> Result<(), Error> {
+    load_payable_request(env, requests_contract, request_id).map(|_| ())
+}
+
+/// Split `amount` into the fees owed under `policy`. Fees never exceed the
+/// amount itself.
+fn compute_fees(policy: &FeePolicy, amount: i128, urgency: Option<Urgency>) -> FeeBreakdown {
+    let surcharge_bps = match urgency {
+        Some(Urgency::Critical) => policy.critical_surcharge_bps,
+        Some(Urgency::Urgent) => policy.urgent_surcharge_bps,
+        _ => 0,
+    };
+    let platform_fee = amount * policy.platform_fee_bps as i128 / BPS_DENOMINATOR as i128;
+    let urgency_surcharge = amount * surcharge_bps as i128 / BPS_DENOMINATOR as i128;
+    let fixed_fee = policy
+        .fixed_fee
+        .min(amount - platform_fee - urgency_surcharge)
+        .max(0);
+    FeeBreakdown {
+        platform_fee,
+        fixed_fee,
+        urgency_surcharge,
+        total: platform_fee + fixed_fee + urgency_surcharge,
+        treasury: Some(policy.treasury.clone()),
+    }
+}
+
+/// Loads `request_id` if it exists and is in Pending or Approved status.
+fn load_payable_request(
+    env: &Env,
+    requests_contract: &Address,
+    request_id: u64,
+) -> Result<BloodRequest, Error> {
+    let client = RequestContractClient::new(env, requests_contract);
+    let req = client
+        .try_get_request(&request_id)
+        .map_err(|_| Error::RequestNotFound)?
+        .map_err(|_| Error::RequestNotFound)?;
+    match req.status {
+        ReqStatus::Pending | ReqStatus::Approved => Ok(req),
         _ => Err(Error::RequestNotPayable),
     }
 }
@@ -441,10 +1179,14 @@ impl PaymentContract {
         if let Some(rc) = requests_contract {
             env.storage().instance().set(&REQ_CONTRACT, &rc);
         }
+        env.storage().instance().set(&SCHEMA_KEY, &SCHEMA_VERSION);
         Ok(())
     }
 
-    pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
         admin.require_auth();
         let stored: Address = env
             .storage()
@@ -454,11 +1196,37 @@ impl PaymentContract {
         if admin != stored {
             return Err(Error::Unauthorized);
         }
-        env.storage().instance().set(&PAUSED_KEY, &true);
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("upgraded")),
+            new_wasm_hash,
+        );
         Ok(())
     }
 
-    pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage().instance().get(&SCHEMA_KEY).unwrap_or(1)
+    }
+
+    /// Run one bounded batch of the storage migration out of `from_version`.
+    /// Admin only.
+    ///
+    /// Each call visits at most `MIGRATION_BATCH_SIZE` records and saves its
+    /// position, so a large migration is resumed by calling again with the
+    /// same `from_version`. Records already in the new layout are left
+    /// alone. Returns `true` once the stored schema matches this build;
+    /// later calls are no-ops.
+    ///
+    /// Steps:
+    /// - 1 -> 2: give payments stored before partial releases and fees were
+    ///   recorded a zero released amount and an empty fee breakdown
+    /// - 2 -> 3: record that vesting schedules are keyed by ID. Donors cannot
+    ///   be listed on chain, so their schedules are re-keyed beforehand with
+    ///   `migrate_vesting`
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, Error> {
         admin.require_auth();
         let stored: Address = env
             .storage()
@@ -468,30 +1236,306 @@ impl PaymentContract {
         if admin != stored {
             return Err(Error::Unauthorized);
         }
-        env.storage().instance().set(&PAUSED_KEY, &false);
-        Ok(())
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(Error::SchemaMismatch);
+        }
+
+        let finished = match current {
+            1 => Self::migrate_payment_fields(&env),
+            2 => true,
+            _ => return Err(Error::SchemaMismatch),
+        };
+        if !finished {
+            return Ok(false);
+        }
+
+        env.storage().instance().remove(&MIGRATION_CURSOR_KEY);
+        env.storage().instance().set(&SCHEMA_KEY, &(current + 1));
+        env.events().publish(
+            (
+                symbol_short!("payment"),
+                symbol_short!("migrated"),
+                symbol_short!("v1"),
+            ),
+            (current, current + 1),
+        );
+        Ok(current + 1 >= SCHEMA_VERSION)
     }
 
-    pub fn is_paused(env: Env) -> bool {
-        env.storage().instance().get(&PAUSED_KEY).unwrap_or(false)
+    /// Migration 1 -> 2: add a zero released amount and an empty fee
+    /// breakdown to payments written before either was recorded. Returns
+    /// `true` when every payment has been visited.
+    fn migrate_payment_fields(env: &Env) -> bool {
+        let last_id = get_counter(env);
+        let start: u64 = env
+            .storage()
+            .instance()
+            .get(&MIGRATION_CURSOR_KEY)
+            .unwrap_or(1);
+        let end = last_id.min(start + MIGRATION_BATCH_SIZE - 1);
+        let fee_field = symbol_short!("fee");
+
+        for id in start..=end {
+            let Some(raw) = env
+                .storage()
+                .persistent()
+                .get::<_, Map<Symbol, Val>>(&payment_key(id))
+            else {
+                continue;
+            };
+            if raw.contains_key(fee_field.clone()) {
+                continue;
+            }
+            let old: PaymentV1 = env.storage().persistent().get(&payment_key(id)).unwrap();
+            store_payment(
+                env,
+                &Payment {
+                    id: old.id,
+                    request_id: old.request_id,
+                    payer: old.payer,
+                    payee: old.payee,
+                    amount: old.amount,
+                    status: old.status,
+                    created_at: old.created_at,
+                    updated_at: old.updated_at,
+                    dispute_reason_code: old.dispute_reason_code,
+                    dispute_case_id: old.dispute_case_id,
+                    dispute_resolved: old.dispute_resolved,
+                    token: old.token,
+                    released_amount: 0,
+                    fee: FeeBreakdown::none(),
+                },
+            );
+        }
+
+        if end >= last_id {
+            return true;
+        }
+        env.storage()
+            .instance()
+            .set(&MIGRATION_CURSOR_KEY, &(end + 1));
+        false
     }
 
-    fn require_not_paused(env: &Env) -> Result<(), Error> {
-        if env.storage().instance().get(&PAUSED_KEY).unwrap_or(false) {
-            return Err(Error::ContractPaused);
+    /// Re-key the schedules `donors` held before schedules had IDs, ahead of
+    /// `migrate` from version 2. Admin only; takes at most
+    /// `MIGRATION_BATCH_SIZE` donors per call, and donors without a legacy
+    /// schedule are skipped, so calls can be repeated safely.
+    ///
+    /// Each schedule gets the next ID, `token` as its reward token and joins
+    /// the donor's list. Legacy schedules were paid from whatever the
+    /// contract held, so the unclaimed balance must already be in the
+    /// contract. Returns the number of schedules re-keyed.
+    pub fn migrate_vesting(
+        env: Env,
+        admin: Address,
+        token: Address,
+        donors: Vec<Address>,
+    ) -> Result<u32, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if Self::schema_version(env.clone()) != 2 {
+            return Err(Error::SchemaMismatch);
         }
-        Ok(())
+        if donors.len() as u64 > MIGRATION_BATCH_SIZE {
+            return Err(Error::MigrationBatchTooLarge);
+        }
+
+        let mut migrated = 0u32;
+        for donor in donors.iter() {
+            let legacy_key = (donor.clone(), "vest");
+            let Some(old) = env
+                .storage()
+                .persistent()
+                .get::<_, VestingScheduleV1>(&legacy_key)
+            else {
+                continue;
+            };
+
+            let id: u64 = env
+                .storage()
+                .instance()
+                .get(&VESTING_COUNTER)
+                .unwrap_or(0u64)
+                + 1;
+            env.storage().instance().set(&VESTING_COUNTER, &id);
+            store_vesting(
+                &env,
+                &VestingSchedule {
+                    id,
+                    donor: old.donor,
+                    token: token.clone(),
+                    total_amount: old.total_amount,
+                    cliff_timestamp: old.cliff_timestamp,
+                    vest_end_timestamp: old.vest_end_timestamp,
+                    claimed: old.claimed,
+                    revoked: false,
+                },
+            );
+            let mut ids = load_donor_vestings(&env, &donor);
+            ids.push_back(id);
+            env.storage()
+                .persistent()
+                .set(&donor_vestings_key(&donor), &ids);
+            env.storage().persistent().remove(&legacy_key);
+
+            env.events().publish(
+                (
+                    symbol_short!("vest"),
+                    symbol_short!("migrated"),
+                    symbol_short!("v1"),
+                ),
+                (id, donor),
+            );
+            migrated += 1;
+        }
+        Ok(migrated)
     }
 
-    fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
+    pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
         let stored: Address = env
             .storage()
             .instance()
             .get(&ADMIN_KEY)
             .ok_or(Error::Unauthorized)?;
-        if *caller != stored {
+        if admin != stored {
             return Err(Error::Unauthorized);
         }
+        env.storage().instance().set(&PAUSED_KEY, &true);
+        Ok(())
+    }
+
+    pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::Unauthorized)?;
+        if admin != stored {
+            return Err(Error::Unauthorized);
+        }
+        env.storage().instance().set(&PAUSED_KEY, &false);
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&PAUSED_KEY).unwrap_or(false)
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if env.storage().instance().get(&PAUSED_KEY).unwrap_or(false) {
+            return Err(Error::ContractPaused);
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(Error::Unauthorized)?;
+        if *caller != stored {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Escrow may be settled by the admin or the registered coordinator.
+    fn require_settler(env: &Env, caller: &Address) -> Result<(), Error> {
+        if coordinator(env).as_ref() == Some(caller) {
+            return Ok(());
+        }
+        Self::require_admin(env, caller)
+    }
+
+    fn validate_policy(policy: &DisbursementPolicy) -> Result<(), Error> {
+        if let DisbursementPolicy::Multisig(multisig) = policy {
+            if multisig.threshold == 0 || multisig.threshold > multisig.signers.len() {
+                return Err(Error::InvalidPolicy);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `approvers`, already distinct and authorised, satisfy
+    /// `policy`.
+    fn require_policy(
+        env: &Env,
+        policy: &DisbursementPolicy,
+        approvers: &Vec<Address>,
+    ) -> Result<(), Error> {
+        match policy {
+            DisbursementPolicy::Admin => {
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&ADMIN_KEY)
+                    .ok_or(Error::Unauthorized)?;
+                if approvers.contains(&admin) {
+                    Ok(())
+                } else {
+                    Err(Error::Unauthorized)
+                }
+            }
+            DisbursementPolicy::Multisig(multisig) => {
+                let signed = approvers
+                    .iter()
+                    .filter(|approver| multisig.signers.contains(approver))
+                    .count() as u32;
+                if signed >= multisig.threshold {
+                    Ok(())
+                } else {
+                    Err(Error::Unauthorized)
+                }
+            }
+        }
+    }
+
+    /// Disputes may be opened by a settler or by either party to the payment.
+    fn require_party_or_settler(
+        env: &Env,
+        caller: &Address,
+        payment: &Payment,
+    ) -> Result<(), Error> {
+        if *caller == payment.payer || *caller == payment.payee {
+            return Ok(());
+        }
+        Self::require_settler(env, caller)
+    }
+
+    /// Register the coordinator contract as an escrow settler. Admin only.
+    pub fn set_coordinator(env: Env, admin: Address, coordinator: Address) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&COORD_KEY, &coordinator);
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("coord")),
+            coordinator,
+        );
+        Ok(())
+    }
+
+    /// The coordinator allowed to settle escrow, resolved through the
+    /// registry when one is set.
+    pub fn get_coordinator(env: Env) -> Option<Address> {
+        coordinator(&env)
+    }
+
+    /// Resolve the requests contract and the coordinator through a
+    /// deployment registry, falling back to the stored addresses. The
+    /// registry's `identity` entry, when present, vets invoicing banks.
+    /// Admin only.
+    pub fn set_registry(env: Env, admin: Address, registry: Address) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&REGISTRY_KEY, &registry);
         Ok(())
     }
 
@@ -520,9 +1564,16 @@ impl PaymentContract {
         if existing_map.contains_key(request_id) {
             return Err(Error::DuplicatePayment);
         }
+        if env
+            .storage()
+            .persistent()
+            .has(&invoiced_request_key(request_id))
+        {
+            return Err(Error::RequestAlreadyInvoiced);
+        }
 
         // Validate request state if the requests contract is configured.
-        if let Some(rc) = env.storage().instance().get::<_, Address>(&REQ_CONTRACT) {
+        if let Some(rc) = requests_contract(&env) {
             validate_request_payable(&env, &rc, request_id)?;
         }
 
@@ -543,6 +1594,8 @@ impl PaymentContract {
             dispute_case_id: None,
             dispute_resolved: false,
             token: None,
+            released_amount: 0,
+            fee: FeeBreakdown::none(),
         };
 
         store_payment(&env, &payment);
@@ -595,6 +1648,7 @@ impl PaymentContract {
         if hospital == payee {
             return Err(Error::SamePayerPayee);
         }
+        Self::require_token_allowed(&env, &token)?;
         hospital.require_auth();
 
         // Reject if a payment for this request already exists.
@@ -606,78 +1660,96 @@ impl PaymentContract {
         if existing_map.contains_key(request_id) {
             return Err(Error::DuplicatePayment);
         }
+        if env
+            .storage()
+            .persistent()
+            .has(&invoiced_request_key(request_id))
+        {
+            return Err(Error::RequestAlreadyInvoiced);
+        }
 
         // Validate request state if the requests contract is configured.
-        if let Some(rc) = env.storage().instance().get::<_, Address>(&REQ_CONTRACT) {
+        if let Some(rc) = requests_contract(&env) {
             validate_request_payable(&env, &rc, request_id)?;
         }
 
-        let token_client = token::Client::new(&env, &token);
-        let available = token_client.balance(&hospital);
-        if available < amount {
-            return Err(Error::InsufficientEscrowFunds);
-        }
-        token_client.transfer(&hospital, &env.current_contract_address(), &amount);
+        let id = Self::lock_escrow(&env, request_id, &hospital, &payee, amount, &token)?;
+        index_by_request(&env, request_id, id);
+        Ok(id)
+    }
 
-        let id = get_counter(&env) + 1;
-        set_counter(&env, id);
+    /// Release escrowed funds to the payee. Admin or coordinator only.
+    /// Pays what is still locked, less fees, to the payee and marks the
+    /// payment as Released. A payee who cannot receive the token right now
+    /// has it credited for `withdraw` instead.
+    pub fn release_escrow(env: Env, caller: Address, payment_id: u64) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::require_settler(&env, &caller)?;
 
-        let now = env.ledger().timestamp();
-        let payment = Payment {
-            id,
-            request_id,
-            payer: hospital.clone(),
-            payee: payee.clone(),
-            amount,
-            status: PaymentStatus::Locked,
-            created_at: now,
-            updated_at: now,
-            dispute_reason_code: None,
-            dispute_case_id: None,
-            dispute_resolved: false,
-            token: Some(token.clone()),
-        };
+        let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+
+        if payment.status != PaymentStatus::Locked {
+            return Err(Error::PaymentNotLocked);
+        }
+
+        let token_addr = payment.token.clone().ok_or(Error::NotEscrowPayment)?;
+        let amount = remaining_escrow(&payment);
+        let net = Self::pay_payee(&env, &token_addr, &mut payment, amount);
 
+        let old_status = payment.status;
+        payment.status = PaymentStatus::Released;
+        payment.updated_at = env.ledger().timestamp();
         store_payment(&env, &payment);
-        index_by_payer(&env, &hospital, id);
-        index_by_payee(&env, &payee, id);
-        index_by_status(&env, PaymentStatus::Locked, id);
-        index_by_request(&env, request_id, id);
-        update_stats_on_transition(&env, amount, PaymentStatus::Pending, PaymentStatus::Locked);
+
+        remove_from_status_index(&env, old_status, payment_id);
+        index_by_status(&env, PaymentStatus::Released, payment_id);
+        update_stats_on_settlement(&env, &payment, old_status);
 
         env.events().publish(
             (
                 symbol_short!("payment"),
-                symbol_short!("escrowed"),
-                symbol_short!("v1"),
+                symbol_short!("released"),
+                symbol_short!("v2"),
             ),
-            id,
+            (payment_id, payment.payee.clone(), net, payment.fee.clone()),
         );
-
-        Ok(id)
+        Ok(())
     }
 
-    /// Release escrowed funds to the payee. Admin only.
-    /// Transfers the locked amount from the contract to the payee and marks
-    /// the payment as Released.
-    pub fn release_escrow(env: Env, caller: Address, payment_id: u64) -> Result<(), Error> {
+    /// Settle an escrow when only part of the order was delivered.
+    /// Admin or coordinator only.
+    ///
+    /// `amount` goes to the payee less fees and the rest of what is still
+    /// locked back to the payer. The payment ends Released with
+    /// `released_amount` recording the payee's share, including released
+    /// milestones.
+    pub fn release_partial(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        amount: i128,
+    ) -> Result<(), Error> {
         caller.require_auth();
         Self::require_not_paused(&env)?;
-        Self::require_admin(&env, &caller)?;
+        Self::require_settler(&env, &caller)?;
 
         let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
 
         if payment.status != PaymentStatus::Locked {
             return Err(Error::PaymentNotLocked);
         }
+        if amount <= 0 || amount > remaining_escrow(&payment) {
+            return Err(Error::InvalidSettlementAmount);
+        }
 
         let token_addr = payment.token.clone().ok_or(Error::NotEscrowPayment)?;
         let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &payment.payee,
-            &payment.amount,
-        );
+        let remainder = remaining_escrow(&payment) - amount;
+        Self::pay_payee(&env, &token_addr, &mut payment, amount);
+        if remainder > 0 {
+            token_client.transfer(&env.current_contract_address(), &payment.payer, &remainder);
+        }
 
         let old_status = payment.status;
         payment.status = PaymentStatus::Released;
@@ -686,22 +1758,23 @@ impl PaymentContract {
 
         remove_from_status_index(&env, old_status, payment_id);
         index_by_status(&env, PaymentStatus::Released, payment_id);
-        update_stats_on_transition(&env, payment.amount, old_status, PaymentStatus::Released);
+        update_stats_on_settlement(&env, &payment, old_status);
 
         env.events().publish(
-            (symbol_short!("payment"), symbol_short!("released")),
-            (payment_id, payment.payee.clone(), payment.amount),
+            (symbol_short!("payment"), symbol_short!("partial")),
+            (payment_id, amount, remainder),
         );
         Ok(())
     }
 
-    /// Refund escrowed funds to the payer. Admin only.
-    /// Transfers the locked amount from the contract back to the payer and
-    /// marks the payment as Refunded.
+    /// Refund escrowed funds to the payer. Admin or coordinator only.
+    /// Transfers what is still locked from the contract back to the payer and
+    /// marks the payment as Refunded; released milestones stay with the
+    /// payee.
     pub fn refund_escrow(env: Env, caller: Address, payment_id: u64) -> Result<(), Error> {
         caller.require_auth();
         Self::require_not_paused(&env)?;
-        Self::require_admin(&env, &caller)?;
+        Self::require_settler(&env, &caller)?;
 
         let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
 
@@ -711,11 +1784,8 @@ impl PaymentContract {
 
         let token_addr = payment.token.clone().ok_or(Error::NotEscrowPayment)?;
         let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &payment.payer,
-            &payment.amount,
-        );
+        let refund = remaining_escrow(&payment);
+        token_client.transfer(&env.current_contract_address(), &payment.payer, &refund);
 
         let old_status = payment.status;
         payment.status = PaymentStatus::Refunded;
@@ -724,36 +1794,60 @@ impl PaymentContract {
 
         remove_from_status_index(&env, old_status, payment_id);
         index_by_status(&env, PaymentStatus::Refunded, payment_id);
-        update_stats_on_transition(&env, payment.amount, old_status, PaymentStatus::Refunded);
+        update_stats_on_settlement(&env, &payment, old_status);
 
         env.events().publish(
             (symbol_short!("payment"), symbol_short!("refunded")),
-            (payment_id, payment.payer.clone(), payment.amount),
+            (payment_id, payment.payer.clone(), refund),
         );
         Ok(())
     }
 
-    pub fn update_status(env: Env, payment_id: u64, status: PaymentStatus) -> Result<(), Error> {
+    /// Move a payment without escrowed funds through the transition table.
+    /// Admin or coordinator only. Escrow-backed payments are settled through
+    /// `release_escrow`, `release_partial` and `refund_escrow` instead.
+    pub fn update_status(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        status: PaymentStatus,
+    ) -> Result<(), Error> {
+        caller.require_auth();
         Self::require_not_paused(&env)?;
+        Self::require_settler(&env, &caller)?;
         let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+        if payment.token.is_some() {
+            return Err(Error::EscrowManagedStatus);
+        }
+        if !payment.can_transition_to(status) {
+            return Err(Error::InvalidStatusTransition);
+        }
         let old_status = payment.status;
         payment.status = status;
         payment.updated_at = env.ledger().timestamp();
         store_payment(&env, &payment);
         remove_from_status_index(&env, old_status, payment_id);
         index_by_status(&env, status, payment_id);
-        update_stats_on_transition(&env, payment.amount, old_status, status);
+        update_stats_on_transition(&env, &payment.token, payment.amount, old_status, status);
         Ok(())
     }
 
+    /// Open a dispute on a Locked payment. The admin, the coordinator, the
+    /// payer or the payee may raise it.
     pub fn record_dispute(
         env: Env,
+        caller: Address,
         payment_id: u64,
         reason: DisputeReason,
         case_id: String,
     ) -> Result<(), Error> {
+        caller.require_auth();
         Self::require_not_paused(&env)?;
         let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+        Self::require_party_or_settler(&env, &caller, &payment)?;
+        if !payment.can_transition_to(PaymentStatus::Disputed) {
+            return Err(Error::InvalidStatusTransition);
+        }
         let old_status = payment.status;
         payment.status = PaymentStatus::Disputed;
         payment.dispute_reason_code = Some(dispute_reason_to_code(reason));
@@ -763,7 +1857,13 @@ impl PaymentContract {
         store_payment(&env, &payment);
         remove_from_status_index(&env, old_status, payment_id);
         index_by_status(&env, PaymentStatus::Disputed, payment_id);
-        update_stats_on_transition(&env, payment.amount, old_status, PaymentStatus::Disputed);
+        update_stats_on_transition(
+            &env,
+            &payment.token,
+            payment.amount,
+            old_status,
+            PaymentStatus::Disputed,
+        );
         env.events().publish(
             (
                 symbol_short!("payment"),
@@ -775,25 +1875,228 @@ impl PaymentContract {
         Ok(())
     }
 
-    pub fn resolve_dispute(env: Env, payment_id: u64) -> Result<(), Error> {
+    /// Settle a Disputed payment. Admin or coordinator only; the caller is
+    /// recorded as the arbiter together with `evidence_hash`.
+    ///
+    /// Tokens still in escrow are paid out according to `outcome`, fees being
+    /// taken from the payee's share, and the payment ends Released when the
+    /// payee has received anything, including earlier milestones, Refunded
+    /// otherwise.
+    pub fn resolve_dispute(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        outcome: DisputeOutcome,
+        evidence_hash: BytesN<32>,
+    ) -> Result<DisputeResolution, Error> {
+        caller.require_auth();
         Self::require_not_paused(&env)?;
+        Self::require_settler(&env, &caller)?;
         let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
-        if payment.dispute_case_id.is_some() {
-            payment.dispute_resolved = true;
+        if payment.status != PaymentStatus::Disputed {
+            return Err(Error::PaymentNotDisputed);
         }
-        payment.updated_at = env.ledger().timestamp();
+
+        let disputed = remaining_escrow(&payment);
+        let payee_amount = match outcome {
+            DisputeOutcome::Refund => 0,
+            DisputeOutcome::Release => disputed,
+            DisputeOutcome::Split(bps) if bps <= BPS_DENOMINATOR => {
+                disputed * bps as i128 / BPS_DENOMINATOR as i128
+            }
+            DisputeOutcome::Split(_) => return Err(Error::InvalidSplit),
+        };
+        let payer_amount = disputed - payee_amount;
+
+        if let Some(token_addr) = payment.token.clone() {
+            let token_client = token::Client::new(&env, &token_addr);
+            Self::pay_payee(&env, &token_addr, &mut payment, payee_amount);
+            if payer_amount > 0 {
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &payment.payer,
+                    &payer_amount,
+                );
+            }
+        } else {
+            payment.released_amount += payee_amount;
+        }
+
+        let old_status = payment.status;
+        let new_status = if payment.released_amount > 0 {
+            PaymentStatus::Released
+        } else {
+            PaymentStatus::Refunded
+        };
+        let now = env.ledger().timestamp();
+        payment.status = new_status;
+        payment.dispute_resolved = true;
+        payment.updated_at = now;
         store_payment(&env, &payment);
+
+        remove_from_status_index(&env, old_status, payment_id);
+        index_by_status(&env, new_status, payment_id);
+        update_stats_on_settlement(&env, &payment, old_status);
+
+        let resolution = DisputeResolution {
+            payment_id,
+            arbiter: caller.clone(),
+            outcome,
+            payee_amount,
+            payer_amount,
+            evidence_hash,
+            resolved_at: now,
+        };
+        env.storage()
+            .persistent()
+            .set(&resolution_key(payment_id), &resolution);
+
         env.events().publish(
             (
                 symbol_short!("payment"),
                 symbol_short!("resolved"),
+                symbol_short!("v2"),
+            ),
+            (payment_id, caller, payee_amount, payer_amount),
+        );
+        Ok(resolution)
+    }
+
+    pub fn get_dispute_resolution(env: Env, payment_id: u64) -> Option<DisputeResolution> {
+        env.storage().persistent().get(&resolution_key(payment_id))
+    }
+
+    // ── Milestones ─────────────────────────────────────────────────────────────
+
+    /// Split a Locked escrow into milestones, each an amount and the
+    /// condition for releasing it. Admin or coordinator only.
+    ///
+    /// The amounts must add up to the escrow. Milestones are set once,
+    /// before any funds leave the escrow.
+    pub fn set_milestones(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        milestones: Vec<(i128, MilestoneCondition)>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::require_settler(&env, &caller)?;
+        let payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+        if payment.status != PaymentStatus::Locked {
+            return Err(Error::PaymentNotLocked);
+        }
+        if payment.token.is_none() {
+            return Err(Error::NotEscrowPayment);
+        }
+        if payment.released_amount > 0
+            || !load_milestones(&env, payment_id).is_empty()
+            || milestones.is_empty()
+            || milestones.len() > MAX_MILESTONES
+        {
+            return Err(Error::InvalidMilestones);
+        }
+
+        let mut stored: Vec<Milestone> = Vec::new(&env);
+        let mut total: i128 = 0;
+        for (amount, condition) in milestones.iter() {
+            if amount <= 0 {
+                return Err(Error::InvalidMilestones);
+            }
+            total += amount;
+            stored.push_back(Milestone {
+                amount,
+                condition,
+                released_at: None,
+            });
+        }
+        if total != payment.amount {
+            return Err(Error::InvalidMilestones);
+        }
+        env.storage()
+            .persistent()
+            .set(&milestones_key(payment_id), &stored);
+        env.events().publish(
+            (
+                symbol_short!("payment"),
+                symbol_short!("mlset"),
                 symbol_short!("v1"),
             ),
-            payment_id,
+            (payment_id, stored.len()),
         );
         Ok(())
     }
 
+    /// Pay out milestone `index` of a Locked escrow once its condition is
+    /// met. Admin or coordinator only. Fees apply to each milestone as it is
+    /// released; the fixed fee is charged on the first release only.
+    ///
+    /// The payment stays Locked until the last milestone is released, then
+    /// ends Released. Returns the amount still held in escrow.
+    pub fn release_milestone(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        index: u32,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::require_settler(&env, &caller)?;
+        let mut payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+        if payment.status != PaymentStatus::Locked {
+            return Err(Error::PaymentNotLocked);
+        }
+        let token_addr = payment.token.clone().ok_or(Error::NotEscrowPayment)?;
+
+        let mut milestones = load_milestones(&env, payment_id);
+        let mut milestone = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+        if milestone.released_at.is_some() {
+            return Err(Error::MilestoneAlreadyReleased);
+        }
+
+        let now = env.ledger().timestamp();
+        let net = Self::pay_payee(&env, &token_addr, &mut payment, milestone.amount);
+        milestone.released_at = Some(now);
+        milestones.set(index, milestone);
+        env.storage()
+            .persistent()
+            .set(&milestones_key(payment_id), &milestones);
+
+        let remaining = remaining_escrow(&payment);
+        let old_status = payment.status;
+        if remaining == 0 {
+            payment.status = PaymentStatus::Released;
+            remove_from_status_index(&env, old_status, payment_id);
+            index_by_status(&env, PaymentStatus::Released, payment_id);
+            update_stats_on_settlement(&env, &payment, old_status);
+        }
+        payment.updated_at = now;
+        store_payment(&env, &payment);
+
+        env.events().publish(
+            (
+                symbol_short!("payment"),
+                symbol_short!("milestone"),
+                symbol_short!("v1"),
+            ),
+            (payment_id, index, net, remaining),
+        );
+        Ok(remaining)
+    }
+
+    pub fn get_milestones(env: Env, payment_id: u64) -> Vec<Milestone> {
+        load_milestones(&env, payment_id)
+    }
+
+    /// Escrowed funds not yet paid to the payee; zero once settled.
+    pub fn get_remaining_escrow(env: Env, payment_id: u64) -> Result<i128, Error> {
+        let payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+        Ok(match payment.status {
+            PaymentStatus::Locked | PaymentStatus::Disputed => remaining_escrow(&payment),
+            _ => 0,
+        })
+    }
+
     // ── Query functions ────────────────────────────────────────────────────────
 
     pub fn get_payment(env: Env, payment_id: u64) -> Result<Payment, Error> {
@@ -855,39 +2158,135 @@ impl PaymentContract {
         Self::load_page(&env, ids, page, page_size)
     }
 
+    /// Cursor-paged `get_payments_by_payer`: up to `limit` payments of
+    /// `payer` with IDs above `cursor`.
+    pub fn get_payments_by_payer_after(
+        env: Env,
+        payer: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> PaymentCursorPage {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&payer_index_key(&payer))
+            .unwrap_or(Vec::new(&env));
+        Self::load_cursor_page(&env, &ids, cursor, limit)
+    }
+
+    /// Cursor-paged `get_payments_by_payee`.
+    pub fn get_payments_by_payee_after(
+        env: Env,
+        payee: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> PaymentCursorPage {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&payee_index_key(&payee))
+            .unwrap_or(Vec::new(&env));
+        Self::load_cursor_page(&env, &ids, cursor, limit)
+    }
+
+    /// Cursor-paged `get_payments_by_status`. A payment that changes status
+    /// between two calls may be missed or seen twice.
+    pub fn get_payments_by_status_after(
+        env: Env,
+        status: PaymentStatus,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> PaymentCursorPage {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&status_index_key(status))
+            .unwrap_or(Vec::new(&env));
+        Self::load_cursor_page(&env, &ids, cursor, limit)
+    }
+
+    /// Contract-wide totals. The amounts add up raw units of every token;
+    /// compare them per token or through `get_normalized_statistics`.
     pub fn get_payment_statistics(env: Env) -> PaymentStats {
         load_stats(&env)
     }
 
-    pub fn get_payment_timeline(env: Env, page: u32, page_size: u32) -> PaymentPage {
-        let page_size = if page_size == 0 { 20 } else { page_size };
-        let total = get_counter(&env);
+    /// Stats of escrow payments in `token` alone.
+    pub fn get_token_statistics(env: Env, token: Address) -> PaymentStats {
+        load_token_stats(&env, &token).unwrap_or_else(empty_stats)
+    }
 
-        let mut all: Vec<Payment> = Vec::new(&env);
-        for id in 1..=total {
-            if let Some(p) = load_payment(&env, id) {
-                all.push_back(p);
-            }
-        }
+    /// Tokens with per-token stats, in the order they were first escrowed.
+    pub fn get_stats_tokens(env: Env) -> Vec<Address> {
+        load_stats_tokens(&env)
+    }
 
-        let len = all.len();
-        for i in 0..len {
-            for j in 0..len.saturating_sub(i + 1) {
-                let current = all.get(j).unwrap();
-                let next = all.get(j + 1).unwrap();
-                if current.created_at > next.created_at {
-                    all.set(j, next);
-                    all.set(j + 1, current);
-                }
-            }
+    /// Per-token stats valued through the configured price oracle and added
+    /// up in its reference currency. Fails with `NormalizationOverflow`
+    /// rather than wrapping when a token's decimals or a valued total are
+    /// too large.
+    pub fn get_normalized_statistics(env: Env) -> Result<NormalizedStats, Error> {
+        let config: OracleConfig = env
+            .storage()
+            .instance()
+            .get(&ORACLE_KEY)
+            .ok_or(Error::OracleNotSet)?;
+        let oracle = PriceOracleClient::new(&env, &config.oracle);
+        let now = env.ledger().timestamp();
+        let mut normalized = NormalizedStats {
+            decimals: oracle.decimals(),
+            total_locked: 0,
+            total_released: 0,
+            total_refunded: 0,
+            unpriced: Vec::new(&env),
+        };
+        for token in load_stats_tokens(&env).iter() {
+            let price = oracle
+                .try_lastprice(&OracleAsset::Stellar(token.clone()))
+                .ok()
+                .and_then(|price| price.ok())
+                .flatten()
+                .filter(|price| now.saturating_sub(price.timestamp) <= config.max_age_secs);
+            let token_decimals = token::Client::new(&env, &token)
+                .try_decimals()
+                .ok()
+                .and_then(|decimals| decimals.ok());
+            let (Some(price), Some(token_decimals)) = (price, token_decimals) else {
+                normalized.unpriced.push_back(token);
+                continue;
+            };
+            // Amounts are in the token's smallest unit; dividing by its
+            // scale leaves the value at the oracle's scale.
+            let scale = 10i128
+                .checked_pow(token_decimals)
+                .ok_or(Error::NormalizationOverflow)?;
+            let value = |amount: i128, total: i128| {
+                amount
+                    .checked_mul(price.price)
+                    .map(|amount| amount / scale)
+                    .and_then(|amount| total.checked_add(amount))
+                    .ok_or(Error::NormalizationOverflow)
+            };
+            let stats = load_token_stats(&env, &token).unwrap_or_else(empty_stats);
+            normalized.total_locked = value(stats.total_locked, normalized.total_locked)?;
+            normalized.total_released = value(stats.total_released, normalized.total_released)?;
+            normalized.total_refunded = value(stats.total_refunded, normalized.total_refunded)?;
         }
+        Ok(normalized)
+    }
+
+    /// Payments in creation order. IDs are assigned in creation order, so
+    /// a page is read straight from its ID range.
+    pub fn get_payment_timeline(env: Env, page: u32, page_size: u32) -> PaymentPage {
+        let page_size = if page_size == 0 { 20 } else { page_size };
+        let total = get_counter(&env);
 
         let start = (page as u64) * (page_size as u64);
         let end = (start + page_size as u64).min(total);
         let mut items: Vec<Payment> = Vec::new(&env);
-        if start < total {
-            for i in start..end {
-                items.push_back(all.get(i as u32).unwrap());
+        for id in (start + 1)..=end {
+            if let Some(p) = load_payment(&env, id) {
+                items.push_back(p);
             }
         }
 
@@ -899,13 +2298,42 @@ impl PaymentContract {
         }
     }
 
+    /// Cursor-paged `get_payment_timeline`: up to `limit` payments created
+    /// after payment `cursor`.
+    pub fn get_payment_timeline_after(
+        env: Env,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> PaymentCursorPage {
+        let limit = if limit == 0 { 20 } else { limit };
+        let total = get_counter(&env);
+        let start = cursor.unwrap_or(0);
+        let end = start.saturating_add(limit as u64).min(total);
+        let mut items: Vec<Payment> = Vec::new(&env);
+        for id in (start + 1)..=end {
+            if let Some(p) = load_payment(&env, id) {
+                items.push_back(p);
+            }
+        }
+        PaymentCursorPage {
+            items,
+            next_cursor: if end < total { Some(end) } else { None },
+        }
+    }
+
     pub fn get_payment_count(env: Env) -> u64 {
         get_counter(&env)
     }
 
+    /// Pledge `amount_per_period` of `token` every `interval_secs` to the
+    /// pool named by `payee_pool` and `region`. The donor approves this
+    /// contract to spend `token`; `execute_due_pledges` collects each period
+    /// with `transfer_from`, the first one straight away.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_pledge(
         env: Env,
         donor: Address,
+        token: Address,
         amount_per_period: i128,
         interval_secs: u64,
         payee_pool: String,
@@ -921,151 +2349,1061 @@ impl PaymentContract {
         if interval_secs == 0 {
             return Err(Error::InvalidAmount);
         }
+        Self::require_token_allowed(&env, &token)?;
+
+        let id = get_pledge_counter(&env) + 1;
+        set_pledge_counter(&env, id);
+
+        let pledge = DonationPledge {
+            id,
+            donor: donor.clone(),
+            amount_per_period,
+            interval_secs,
+            payee_pool,
+            cause,
+            region,
+            emergency_pool,
+            active: true,
+            created_at: env.ledger().timestamp(),
+        };
+        store_pledge(&env, &pledge);
+        store_pledge_funding(
+            &env,
+            id,
+            &PledgeFunding {
+                token,
+                next_due: pledge.created_at,
+                periods_executed: 0,
+                periods_skipped: 0,
+                total_given: 0,
+                underfunded: false,
+            },
+        );
+
+        env.events().publish(
+            (
+                symbol_short!("pledge"),
+                symbol_short!("create"),
+                symbol_short!("v1"),
+            ),
+            id,
+        );
+
+        Ok(id)
+    }
+
+    pub fn get_pledge(env: Env, pledge_id: u64) -> Result<DonationPledge, Error> {
+        load_pledge(&env, pledge_id).ok_or(Error::PaymentNotFound)
+    }
+
+    pub fn set_pledge_active(
+        env: Env,
+        pledge_id: u64,
+        donor: Address,
+        active: bool,
+    ) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+        donor.require_auth();
+        let mut p = load_pledge(&env, pledge_id).ok_or(Error::PaymentNotFound)?;
+        if p.donor != donor {
+            return Err(Error::NotPledgeDonor);
+        }
+        p.active = active;
+        store_pledge(&env, &p);
+
+        // Periods that fell due while the pledge was paused are not owed.
+        if active {
+            if let Some(mut funding) = load_pledge_funding(&env, pledge_id) {
+                let now = env.ledger().timestamp();
+                if funding.next_due < now {
+                    funding.next_due = now;
+                    store_pledge_funding(&env, pledge_id, &funding);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect due pledge periods, at most `max` of them. Callable by anyone.
+    ///
+    /// Each due period pulls `amount_per_period` from the donor through the
+    /// token allowance into the pledge's pool. A period the donor's allowance
+    /// or balance cannot cover is skipped and the pledge flagged as
+    /// underfunded. Returns the periods handled in this call.
+    ///
+    /// A call visits at most `MAX_PLEDGES_SCANNED` pledges, starting where
+    /// the previous call stopped and wrapping around after the newest, so
+    /// repeated calls reach every pledge however many exist. Active pledges
+    /// created before pledges carried a token cannot be collected; visiting
+    /// one emits `(pledge, nofund, v1)` instead.
+    pub fn execute_due_pledges(env: Env, max: u32) -> Result<Vec<PledgeExecution>, Error> {
+        Self::require_not_paused(&env)?;
+        let now = env.ledger().timestamp();
+        let this = env.current_contract_address();
+        let mut handled: Vec<PledgeExecution> = Vec::new(&env);
+
+        let last_id = get_pledge_counter(&env);
+        let mut cursor: u64 = env.storage().instance().get(&PLEDGE_CURSOR).unwrap_or(1);
+        let mut visited = 0u64;
+        while visited < last_id.min(MAX_PLEDGES_SCANNED) && handled.len() < max {
+            if cursor > last_id {
+                cursor = 1;
+            }
+            let pledge_id = cursor;
+            cursor += 1;
+            visited += 1;
+
+            let Some(pledge) = load_pledge(&env, pledge_id) else {
+                continue;
+            };
+            if !pledge.active {
+                continue;
+            }
+            let Some(mut funding) = load_pledge_funding(&env, pledge_id) else {
+                env.events().publish(
+                    (
+                        symbol_short!("pledge"),
+                        symbol_short!("nofund"),
+                        symbol_short!("v1"),
+                    ),
+                    pledge_id,
+                );
+                continue;
+            };
+            if funding.next_due > now {
+                continue;
+            }
+
+            let token_client = token::Client::new(&env, &funding.token);
+            let pool = PoolId {
+                name: pledge.payee_pool.clone(),
+                region: pledge.region.clone(),
+            };
+            let mut history: Vec<PledgeExecution> = Vec::new(&env);
+
+            while funding.next_due <= now && handled.len() < max {
+                let amount = pledge.amount_per_period;
+                let collected = token_client.allowance(&pledge.donor, &this) >= amount
+                    && token_client.balance(&pledge.donor) >= amount;
+                let period = funding.periods_executed + funding.periods_skipped + 1;
+
+                if collected {
+                    token_client.transfer_from(&this, &pledge.donor, &this, &amount);
+                    let balance = get_pool_balance(&env, &pool, &funding.token);
+                    set_pool_balance(&env, &pool, &funding.token, balance + amount);
+                    append_pool_ledger(
+                        &env,
+                        &pool,
+                        &PoolLedgerEntry {
+                            kind: PoolEntryKind::Deposit,
+                            token: funding.token.clone(),
+                            amount,
+                            counterparty: pledge.donor.clone(),
+                            pledge_id: Some(pledge_id),
+                            request_id: None,
+                            timestamp: now,
+                        },
+                    );
+
+                    let total_key = donor_total_key(&pledge.donor, &funding.token);
+                    let given: i128 = env.storage().persistent().get(&total_key).unwrap_or(0);
+                    env.storage()
+                        .persistent()
+                        .set(&total_key, &(given + amount));
+
+                    funding.periods_executed += 1;
+                    funding.total_given += amount;
+                    funding.underfunded = false;
+                    env.events().publish(
+                        (
+                            symbol_short!("pledge"),
+                            symbol_short!("exec"),
+                            symbol_short!("v1"),
+                        ),
+                        (pledge_id, period, amount),
+                    );
+                } else {
+                    funding.periods_skipped += 1;
+                    funding.underfunded = true;
+                    env.events().publish(
+                        (
+                            symbol_short!("pledge"),
+                            symbol_short!("skipped"),
+                            symbol_short!("v1"),
+                        ),
+                        (pledge_id, period),
+                    );
+                }
+
+                let execution = PledgeExecution {
+                    pledge_id,
+                    period,
+                    amount,
+                    executed_at: now,
+                    collected,
+                };
+                history.push_back(execution.clone());
+                handled.push_back(execution);
+                funding.next_due = funding.next_due.saturating_add(pledge.interval_secs);
+            }
+            // Stopped by `max` with periods still due: resume here.
+            if funding.next_due <= now {
+                cursor = pledge_id;
+            }
+
+            append_pledge_history(&env, pledge_id, &history);
+            store_pledge_funding(&env, pledge_id, &funding);
+        }
+        env.storage().instance().set(&PLEDGE_CURSOR, &cursor);
+
+        Ok(handled)
+    }
+
+    pub fn get_pledge_funding(env: Env, pledge_id: u64) -> Result<PledgeFunding, Error> {
+        load_pledge_funding(&env, pledge_id).ok_or(Error::PaymentNotFound)
+    }
+
+    /// Every period of the pledge collected or skipped so far, oldest first,
+    /// read page by page. Callers that only need recent periods should use
+    /// `get_pledge_history_page`.
+    pub fn get_pledge_history(env: Env, pledge_id: u64) -> Vec<PledgeExecution> {
+        let mut all = Vec::new(&env);
+        for page in 0..=get_pledge_history_last_page(&env, pledge_id) {
+            all.append(&load_pledge_history_page(&env, pledge_id, page));
+        }
+        all
+    }
+
+    /// One page of the pledge's history, at most `PLEDGE_HISTORY_PAGE_SIZE`
+    /// periods. O(1) storage reads.
+    pub fn get_pledge_history_page(env: Env, pledge_id: u64, page: u32) -> Vec<PledgeExecution> {
+        load_pledge_history_page(&env, pledge_id, page)
+    }
+
+    /// Return the last page number of the pledge's history (0-based).
+    pub fn get_pledge_history_page_count(env: Env, pledge_id: u64) -> u32 {
+        get_pledge_history_last_page(&env, pledge_id)
+    }
+
+    /// Total `token` a donor has given through pledges.
+    pub fn get_donor_total(env: Env, donor: Address, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&donor_total_key(&donor, &token))
+            .unwrap_or(0)
+    }
+
+    pub fn get_pool_balance(env: Env, pool: PoolId, token: Address) -> i128 {
+        get_pool_balance(&env, &pool, &token)
+    }
+
+    // ── Donation pools ─────────────────────────────────────────────────────────
+
+    /// Open the account for a pool so it can disburse. Admin only. Pledge
+    /// deposits made before the account exists stay in the pool's balance.
+    pub fn create_pool(
+        env: Env,
+        admin: Address,
+        pool: PoolId,
+        emergency: bool,
+        policy: DisbursementPolicy,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if load_pool(&env, &pool).is_some() {
+            return Err(Error::PoolExists);
+        }
+        Self::validate_policy(&policy)?;
+        let account = DonationPool {
+            id: pool.clone(),
+            emergency,
+            policy,
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&pool_key(&pool), &account);
+        env.events().publish(
+            (
+                symbol_short!("pool"),
+                symbol_short!("create"),
+                symbol_short!("v1"),
+            ),
+            (pool.name, pool.region, emergency),
+        );
+        Ok(())
+    }
+
+    /// Replace the disbursement policy of a pool. Admin only.
+    pub fn set_pool_policy(
+        env: Env,
+        admin: Address,
+        pool: PoolId,
+        policy: DisbursementPolicy,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        let mut account = load_pool(&env, &pool).ok_or(Error::PoolNotFound)?;
+        Self::validate_policy(&policy)?;
+        account.policy = policy;
+        env.storage().persistent().set(&pool_key(&pool), &account);
+        Ok(())
+    }
+
+    pub fn get_pool(env: Env, pool: PoolId) -> Result<DonationPool, Error> {
+        load_pool(&env, &pool).ok_or(Error::PoolNotFound)
+    }
+
+    /// Pay `amount` of `token` from a pool to the hospital behind
+    /// `request_id`, which must be Pending or Approved.
+    ///
+    /// `approvers` must satisfy the pool's policy: the admin for an admin
+    /// pool, or at least the threshold of distinct signers for a multisig
+    /// pool. Every approver authorises the call. Returns the ledger index of
+    /// the disbursement.
+    pub fn disburse_from_pool(
+        env: Env,
+        approvers: Vec<Address>,
+        pool: PoolId,
+        token: Address,
+        request_id: u64,
+        amount: i128,
+    ) -> Result<u32, Error> {
+        Self::require_not_paused(&env)?;
+        // An address may only authorise once per call, so repeats are dropped
+        // before authorising and counting.
+        let mut distinct: Vec<Address> = Vec::new(&env);
+        for approver in approvers.iter() {
+            if !distinct.contains(&approver) {
+                approver.require_auth();
+                distinct.push_back(approver);
+            }
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let account = load_pool(&env, &pool).ok_or(Error::PoolNotFound)?;
+        Self::require_policy(&env, &account.policy, &distinct)?;
+
+        let rc = requests_contract(&env).ok_or(Error::RequestNotFound)?;
+        let request = load_payable_request(&env, &rc, request_id)?;
+
+        let balance = get_pool_balance(&env, &pool, &token);
+        if balance < amount {
+            return Err(Error::InsufficientPoolBalance);
+        }
+        set_pool_balance(&env, &pool, &token, balance - amount);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &request.hospital_id,
+            &amount,
+        );
+
+        let index = get_pool_ledger_len(&env, &pool);
+        append_pool_ledger(
+            &env,
+            &pool,
+            &PoolLedgerEntry {
+                kind: PoolEntryKind::Disbursement,
+                token,
+                amount,
+                counterparty: request.hospital_id.clone(),
+                pledge_id: None,
+                request_id: Some(request_id),
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        env.events().publish(
+            (
+                symbol_short!("pool"),
+                symbol_short!("disburse"),
+                symbol_short!("v1"),
+            ),
+            (
+                pool.name,
+                pool.region,
+                request_id,
+                request.hospital_id,
+                amount,
+            ),
+        );
+        Ok(index)
+    }
+
+    /// One page of a pool's ledger, oldest entries first. Public so donors
+    /// can follow their money from deposit to disbursement.
+    pub fn get_pool_ledger(
+        env: Env,
+        pool: PoolId,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Vec<PoolLedgerEntry>, Error> {
+        if page_size == 0 {
+            return Err(Error::InvalidPage);
+        }
+        let len = get_pool_ledger_len(&env, &pool);
+        let mut entries = Vec::new(&env);
+        let start = page.saturating_mul(page_size);
+        let end = start.saturating_add(page_size).min(len);
+        for index in start..end {
+            if let Some(entry) = env
+                .storage()
+                .persistent()
+                .get(&pool_ledger_key(&pool, index))
+            {
+                entries.push_back(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn get_pool_ledger_len(env: Env, pool: PoolId) -> u32 {
+        get_pool_ledger_len(&env, &pool)
+    }
+
+    // ── Vesting ────────────────────────────────────────────────────────────────
+
+    /// Lock `total_amount` of `token` as a reward for `donor`, vesting
+    /// linearly from the cliff to the end of `duration_secs`. Admin only; the
+    /// tokens are transferred from the admin now, so every claim is covered.
+    /// A donor may hold several schedules. Returns the schedule ID.
+    pub fn create_vesting(
+        env: Env,
+        admin: Address,
+        donor: Address,
+        token: Address,
+        total_amount: i128,
+        cliff_secs: u64,
+        duration_secs: u64,
+    ) -> Result<u64, Error> {
+        admin.require_auth();
+        Self::require_not_paused(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if total_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if duration_secs == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &admin,
+            env.current_contract_address(),
+            &total_amount,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&VESTING_COUNTER)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage().instance().set(&VESTING_COUNTER, &id);
+
+        let now = env.ledger().timestamp();
+        let schedule = VestingSchedule {
+            id,
+            donor: donor.clone(),
+            token,
+            total_amount,
+            cliff_timestamp: now + cliff_secs,
+            vest_end_timestamp: now + duration_secs,
+            claimed: 0,
+            revoked: false,
+        };
+        store_vesting(&env, &schedule);
+
+        let mut ids = load_donor_vestings(&env, &donor);
+        ids.push_back(id);
+        env.storage()
+            .persistent()
+            .set(&donor_vestings_key(&donor), &ids);
+
+        env.events().publish(
+            (
+                symbol_short!("vest"),
+                symbol_short!("created"),
+                symbol_short!("v2"),
+            ),
+            (
+                id,
+                donor,
+                total_amount,
+                now + cliff_secs,
+                now + duration_secs,
+            ),
+        );
+
+        Ok(id)
+    }
+
+    /// Transfer everything vested and not yet claimed on `schedule_id` to
+    /// its donor. Returns the amount claimed.
+    pub fn claim_vested(env: Env, donor: Address, schedule_id: u64) -> Result<i128, Error> {
+        donor.require_auth();
+        Self::require_not_paused(&env)?;
+
+        let mut schedule = load_vesting(&env, schedule_id)
+            .filter(|schedule| schedule.donor == donor)
+            .ok_or(Error::VestingNotFound)?;
+
+        let now = env.ledger().timestamp();
+
+        if now < schedule.cliff_timestamp {
+            return Err(Error::CliffNotReached);
+        }
+
+        let claimable = vested_amount(&schedule, now) - schedule.claimed;
+        if claimable <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        let new_claimed = schedule.claimed + claimable;
+        schedule.claimed = new_claimed;
+        store_vesting(&env, &schedule);
+
+        let token_client = token::Client::new(&env, &schedule.token);
+        token_client.transfer(&env.current_contract_address(), &donor, &claimable);
+
+        env.events().publish(
+            (
+                symbol_short!("vest"),
+                symbol_short!("claimed"),
+                symbol_short!("v2"),
+            ),
+            (schedule_id, donor, claimable, new_claimed),
+        );
+
+        Ok(claimable)
+    }
+
+    /// Stop `schedule_id` from vesting further and return the unvested
+    /// tokens to the admin. Admin only. What has vested so far stays
+    /// claimable by the donor. Returns the amount returned.
+    pub fn revoke_vesting(env: Env, admin: Address, schedule_id: u64) -> Result<i128, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        let mut schedule = load_vesting(&env, schedule_id).ok_or(Error::VestingNotFound)?;
+        if schedule.revoked {
+            return Err(Error::VestingRevoked);
+        }
+
+        let now = env.ledger().timestamp();
+        let vested = vested_amount(&schedule, now);
+        let unvested = schedule.total_amount - vested;
+        schedule.total_amount = vested;
+        schedule.vest_end_timestamp = schedule.vest_end_timestamp.min(now);
+        schedule.revoked = true;
+        store_vesting(&env, &schedule);
+
+        if unvested > 0 {
+            token::Client::new(&env, &schedule.token).transfer(
+                &env.current_contract_address(),
+                &admin,
+                &unvested,
+            );
+        }
+
+        env.events().publish(
+            (
+                symbol_short!("vest"),
+                symbol_short!("revoked"),
+                symbol_short!("v1"),
+            ),
+            (schedule_id, schedule.donor, unvested),
+        );
+
+        Ok(unvested)
+    }
+
+    pub fn get_vesting(env: Env, schedule_id: u64) -> Result<VestingSchedule, Error> {
+        load_vesting(&env, schedule_id).ok_or(Error::VestingNotFound)
+    }
+
+    /// Every schedule of `donor`, oldest first.
+    pub fn get_donor_vestings(env: Env, donor: Address) -> Vec<VestingSchedule> {
+        let mut schedules = Vec::new(&env);
+        for id in load_donor_vestings(&env, &donor).iter() {
+            if let Some(schedule) = load_vesting(&env, id) {
+                schedules.push_back(schedule);
+            }
+        }
+        schedules
+    }
+
+    // ── Payee balances ─────────────────────────────────────────────────────────
+
+    /// Send `amount` of `payee`'s claimable `token` balance to `to`.
+    /// Returns what is left claimable.
+    pub fn withdraw(
+        env: Env,
+        payee: Address,
+        token: Address,
+        amount: i128,
+        to: Address,
+    ) -> Result<i128, Error> {
+        payee.require_auth();
+        Self::require_not_paused(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let balance = get_claimable(&env, &payee, &token);
+        if amount > balance {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let remaining = balance - amount;
+        set_claimable(&env, &payee, &token, remaining);
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events().publish(
+            (
+                symbol_short!("payee"),
+                symbol_short!("withdraw"),
+                symbol_short!("v1"),
+            ),
+            (payee, token, amount, to),
+        );
+        Ok(remaining)
+    }
+
+    /// What `payee` can withdraw in `token`.
+    pub fn get_claimable_balance(env: Env, payee: Address, token: Address) -> i128 {
+        get_claimable(&env, &payee, &token)
+    }
+
+    /// Choose whether releases are pushed to `payee` when the transfer
+    /// succeeds (the default) or always credited for `withdraw`.
+    pub fn set_auto_push(env: Env, payee: Address, enabled: bool) {
+        payee.require_auth();
+        if enabled {
+            env.storage().persistent().remove(&pull_only_key(&payee));
+        } else {
+            env.storage()
+                .persistent()
+                .set(&pull_only_key(&payee), &true);
+        }
+    }
+
+    pub fn get_auto_push(env: Env, payee: Address) -> bool {
+        !env.storage().persistent().has(&pull_only_key(&payee))
+    }
+
+    // ── Invoices ───────────────────────────────────────────────────────────────
+
+    /// Bill `hospital` for delivered requests. `bank` must be a verified
+    /// blood bank when the registry lists an identity contract. Each line's
+    /// request must be fulfilled, belong to `hospital` (checked when the
+    /// requests contract is configured) and not be paid or invoiced already.
+    /// Returns the invoice ID.
+    pub fn issue_invoice(
+        env: Env,
+        bank: Address,
+        hospital: Address,
+        token: Address,
+        lines: Vec<InvoiceLine>,
+    ) -> Result<u64, Error> {
+        bank.require_auth();
+        Self::require_not_paused(&env)?;
+        if bank == hospital {
+            return Err(Error::SamePayerPayee);
+        }
+        Self::require_token_allowed(&env, &token)?;
+        if let Some(identity) = identity_contract(&env) {
+            let verified = IdentityContractClient::new(&env, &identity)
+                .try_get_organization(&bank)
+                .ok()
+                .and_then(|org| org.ok())
+                .flatten()
+                .is_some_and(|org| org.verified && org.org_type == OrgType::BloodBank);
+            if !verified {
+                return Err(Error::BankNotVerified);
+            }
+        }
+        if lines.is_empty() || lines.len() > MAX_INVOICE_LINES {
+            return Err(Error::InvalidInvoice);
+        }
 
-        let id = get_pledge_counter(&env) + 1;
-        set_pledge_counter(&env, id);
+        let paid: Map<u64, u64> = env
+            .storage()
+            .instance()
+            .get(&REQ_IDX)
+            .unwrap_or(Map::new(&env));
+        let requests = requests_contract(&env);
+        let mut total: i128 = 0;
+        for (i, line) in lines.iter().enumerate() {
+            if line.units == 0 || line.unit_price <= 0 {
+                return Err(Error::InvalidInvoice);
+            }
+            let repeated = lines
+                .iter()
+                .take(i)
+                .any(|earlier| earlier.request_id == line.request_id);
+            if repeated {
+                return Err(Error::InvalidInvoice);
+            }
+            if paid.contains_key(line.request_id) {
+                return Err(Error::DuplicatePayment);
+            }
+            if env
+                .storage()
+                .persistent()
+                .has(&invoiced_request_key(line.request_id))
+            {
+                return Err(Error::RequestAlreadyInvoiced);
+            }
+            if let Some(rc) = requests.as_ref() {
+                let req = RequestContractClient::new(&env, rc)
+                    .try_get_request(&line.request_id)
+                    .map_err(|_| Error::RequestNotFound)?
+                    .map_err(|_| Error::RequestNotFound)?;
+                if req.hospital_id != hospital {
+                    return Err(Error::InvalidInvoice);
+                }
+                if req.status != ReqStatus::Fulfilled {
+                    return Err(Error::RequestNotDelivered);
+                }
+            }
+            total = (line.units as i128)
+                .checked_mul(line.unit_price)
+                .and_then(|amount| total.checked_add(amount))
+                .ok_or(Error::InvalidInvoice)?;
+        }
 
-        let pledge = DonationPledge {
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&INVOICE_COUNTER)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage().instance().set(&INVOICE_COUNTER, &id);
+
+        for line in lines.iter() {
+            env.storage()
+                .persistent()
+                .set(&invoiced_request_key(line.request_id), &id);
+        }
+        let invoice = Invoice {
             id,
-            donor: donor.clone(),
-            amount_per_period,
-            interval_secs,
-            payee_pool,
-            cause,
-            region,
-            emergency_pool,
-            active: true,
-            created_at: env.ledger().timestamp(),
+            bank: bank.clone(),
+            hospital: hospital.clone(),
+            token,
+            lines,
+            total,
+            status: InvoiceStatus::Issued,
+            issued_at: env.ledger().timestamp(),
+            payment_id: None,
         };
-        store_pledge(&env, &pledge);
+        store_invoice(&env, &invoice);
+
+        let mut ids = load_hospital_invoices(&env, &hospital);
+        ids.push_back(id);
+        env.storage()
+            .persistent()
+            .set(&hospital_invoices_key(&hospital), &ids);
 
         env.events().publish(
             (
-                symbol_short!("pledge"),
-                symbol_short!("create"),
+                symbol_short!("invoice"),
+                symbol_short!("issued"),
                 symbol_short!("v1"),
             ),
-            id,
+            (id, bank, hospital, total),
         );
-
         Ok(id)
     }
 
-    pub fn get_pledge(env: Env, pledge_id: u64) -> Result<DonationPledge, Error> {
-        load_pledge(&env, pledge_id).ok_or(Error::PaymentNotFound)
+    /// Accept an issued invoice, locking its total from `hospital` in one
+    /// escrow payment to the bank. The payment is settled like any other
+    /// escrow and is found by each invoiced request; its `request_id` is the
+    /// first line's. Returns the payment ID.
+    ///
+    /// Fails with `DuplicatePayment` if any invoiced request was paid since
+    /// the invoice was issued.
+    pub fn accept_invoice(env: Env, hospital: Address, invoice_id: u64) -> Result<u64, Error> {
+        hospital.require_auth();
+        Self::require_not_paused(&env)?;
+
+        let mut invoice = load_invoice(&env, invoice_id)
+            .filter(|invoice| invoice.hospital == hospital)
+            .ok_or(Error::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Issued {
+            return Err(Error::InvoiceNotOpen);
+        }
+        Self::require_token_allowed(&env, &invoice.token)?;
+        let paid: Map<u64, u64> = env
+            .storage()
+            .instance()
+            .get(&REQ_IDX)
+            .unwrap_or(Map::new(&env));
+        if invoice
+            .lines
+            .iter()
+            .any(|line| paid.contains_key(line.request_id))
+        {
+            return Err(Error::DuplicatePayment);
+        }
+
+        let payment_id = Self::lock_escrow(
+            &env,
+            invoice.lines.get(0).unwrap().request_id,
+            &hospital,
+            &invoice.bank,
+            invoice.total,
+            &invoice.token,
+        )?;
+        for line in invoice.lines.iter() {
+            index_by_request(&env, line.request_id, payment_id);
+        }
+
+        invoice.status = InvoiceStatus::Accepted;
+        invoice.payment_id = Some(payment_id);
+        store_invoice(&env, &invoice);
+
+        env.events().publish(
+            (
+                symbol_short!("invoice"),
+                symbol_short!("accepted"),
+                symbol_short!("v1"),
+            ),
+            (invoice_id, payment_id),
+        );
+        Ok(payment_id)
     }
 
-    pub fn set_pledge_active(
-        env: Env,
-        pledge_id: u64,
-        donor: Address,
-        active: bool,
-    ) -> Result<(), Error> {
+    /// Withdraw an invoice that has not been accepted. The bank or the
+    /// hospital may cancel; its requests can then be invoiced again.
+    pub fn cancel_invoice(env: Env, caller: Address, invoice_id: u64) -> Result<(), Error> {
+        caller.require_auth();
         Self::require_not_paused(&env)?;
-        donor.require_auth();
-        let mut p = load_pledge(&env, pledge_id).ok_or(Error::PaymentNotFound)?;
-        if p.donor != donor {
-            return Err(Error::NotPledgeDonor);
+
+        let mut invoice = load_invoice(&env, invoice_id).ok_or(Error::InvoiceNotFound)?;
+        if caller != invoice.bank && caller != invoice.hospital {
+            return Err(Error::Unauthorized);
         }
-        p.active = active;
-        store_pledge(&env, &p);
+        if invoice.status != InvoiceStatus::Issued {
+            return Err(Error::InvoiceNotOpen);
+        }
+
+        for line in invoice.lines.iter() {
+            env.storage()
+                .persistent()
+                .remove(&invoiced_request_key(line.request_id));
+        }
+        invoice.status = InvoiceStatus::Cancelled;
+        store_invoice(&env, &invoice);
+
+        env.events().publish(
+            (
+                symbol_short!("invoice"),
+                symbol_short!("cancel"),
+                symbol_short!("v1"),
+            ),
+            (invoice_id, caller),
+        );
         Ok(())
     }
 
-    // ── Vesting ────────────────────────────────────────────────────────────────
+    pub fn get_invoice(env: Env, invoice_id: u64) -> Result<Invoice, Error> {
+        load_invoice(&env, invoice_id).ok_or(Error::InvoiceNotFound)
+    }
 
-    pub fn create_vesting(
+    /// Summarise the invoices issued to `hospital` and the payments it made
+    /// from `from` to `to`, inclusive, with amounts kept apart per token.
+    /// Only items in the period are loaded, found by binary search on the
+    /// hospital's indexes.
+    pub fn get_statement(
         env: Env,
-        admin: Address,
-        donor: Address,
-        total_amount: i128,
-        cliff_secs: u64,
-        duration_secs: u64,
-    ) -> Result<(), Error> {
-        admin.require_auth();
-        Self::require_not_paused(&env)?;
-
-        let stored_admin: Address = env
-            .storage()
-            .instance()
-            .get(&ADMIN_KEY)
-            .ok_or(Error::Unauthorized)?;
-        if admin != stored_admin {
-            return Err(Error::Unauthorized);
+        hospital: Address,
+        from: u64,
+        to: u64,
+    ) -> Result<BillingStatement, Error> {
+        if from > to {
+            return Err(Error::InvalidPeriod);
         }
+        let mut statement = BillingStatement {
+            hospital: hospital.clone(),
+            from,
+            to,
+            invoices_issued: 0,
+            invoices_accepted: 0,
+            invoices_open: 0,
+            payments: 0,
+            refunds: 0,
+            disputes: 0,
+            open_disputes: 0,
+            totals: Vec::new(&env),
+        };
 
-        if total_amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        if duration_secs == 0 {
-            return Err(Error::InvalidAmount);
+        let invoice_ids = load_hospital_invoices(&env, &hospital);
+        let start = first_created_from(&invoice_ids, from, |id| {
+            load_invoice(&env, id).map_or(0, |invoice| invoice.issued_at)
+        });
+        for i in start..invoice_ids.len() {
+            let Some(invoice) = load_invoice(&env, invoice_ids.get(i).unwrap()) else {
+                continue;
+            };
+            if invoice.issued_at > to {
+                break;
+            }
+            statement.invoices_issued += 1;
+            match invoice.status {
+                InvoiceStatus::Issued => statement.invoices_open += 1,
+                InvoiceStatus::Accepted => statement.invoices_accepted += 1,
+                InvoiceStatus::Cancelled => continue,
+            }
+            let t = statement_totals(&mut statement.totals, &Some(invoice.token.clone()));
+            let mut totals = statement.totals.get(t).unwrap();
+            totals.invoiced += invoice.total;
+            statement.totals.set(t, totals);
         }
 
-        let now = env.ledger().timestamp();
-        let schedule = VestingSchedule {
-            donor: donor.clone(),
-            total_amount,
-            cliff_timestamp: now + cliff_secs,
-            vest_end_timestamp: now + duration_secs,
-            claimed: 0,
-        };
+        let payment_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&payer_index_key(&hospital))
+            .unwrap_or(Vec::new(&env));
+        let start = first_created_from(&payment_ids, from, |id| {
+            load_payment(&env, id).map_or(0, |payment| payment.created_at)
+        });
+        for i in start..payment_ids.len() {
+            let Some(payment) = load_payment(&env, payment_ids.get(i).unwrap()) else {
+                continue;
+            };
+            if payment.created_at > to {
+                break;
+            }
+            let (released, refunded) = settled_amounts(&payment);
+            statement.payments += 1;
+            if refunded > 0 {
+                statement.refunds += 1;
+            }
+            if payment.dispute_reason_code.is_some() {
+                statement.disputes += 1;
+            }
+            if payment.status == PaymentStatus::Disputed {
+                statement.open_disputes += 1;
+            }
+            let t = statement_totals(&mut statement.totals, &payment.token);
+            let mut totals = statement.totals.get(t).unwrap();
+            totals.paid += payment.amount;
+            totals.released += released;
+            totals.refunded += refunded;
+            totals.fees += payment.fee.total;
+            statement.totals.set(t, totals);
+        }
 
-        store_vesting(&env, &schedule);
+        Ok(statement)
+    }
 
-        env.events().publish(
-            (symbol_short!("vest"), symbol_short!("created")),
-            (donor, total_amount, now + cliff_secs, now + duration_secs),
-        );
+    // ── Fees ───────────────────────────────────────────────────────────────────
 
+    /// Set the fee policy applied whenever escrow is paid to a payee. Admin
+    /// only. Escrows released before the policy is set are not charged.
+    pub fn set_fee_policy(env: Env, admin: Address, policy: FeePolicy) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        let max_bps = policy
+            .platform_fee_bps
+            .checked_add(
+                policy
+                    .critical_surcharge_bps
+                    .max(policy.urgent_surcharge_bps),
+            )
+            .ok_or(Error::InvalidFeePolicy)?;
+        if policy.fixed_fee < 0 || max_bps > BPS_DENOMINATOR {
+            return Err(Error::InvalidFeePolicy);
+        }
+        env.storage().instance().set(&FEE_POLICY_KEY, &policy);
         Ok(())
     }
 
-    pub fn claim_vested(env: Env, donor: Address, reward_token: Address) -> Result<i128, Error> {
-        donor.require_auth();
-        Self::require_not_paused(&env)?;
-
-        let mut schedule = load_vesting(&env, &donor).ok_or(Error::VestingNotFound)?;
+    /// Stop charging fees on release. Admin only.
+    pub fn clear_fee_policy(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().remove(&FEE_POLICY_KEY);
+        Ok(())
+    }
 
-        let now = env.ledger().timestamp();
+    pub fn get_fee_policy(env: Env) -> Option<FeePolicy> {
+        env.storage().instance().get(&FEE_POLICY_KEY)
+    }
 
-        if now < schedule.cliff_timestamp {
-            return Err(Error::CliffNotReached);
-        }
+    /// Fees `release_escrow` would charge on `payment_id` right now.
+    pub fn quote_fee(env: Env, payment_id: u64) -> Result<FeeBreakdown, Error> {
+        let payment = load_payment(&env, payment_id).ok_or(Error::PaymentNotFound)?;
+        Ok(Self::fee_for(&env, &payment, remaining_escrow(&payment)))
+    }
 
-        let vested = if now >= schedule.vest_end_timestamp {
-            schedule.total_amount
-        } else {
-            let elapsed = now - schedule.cliff_timestamp;
-            let duration = schedule.vest_end_timestamp - schedule.cliff_timestamp;
-            (schedule.total_amount * elapsed as i128) / duration as i128
-        };
+    // ── Accepted tokens and pricing ────────────────────────────────────────────
 
-        let claimable = vested - schedule.claimed;
-        if claimable <= 0 {
-            return Err(Error::NothingToClaim);
+    /// Accept escrows and pledges in `token`. Admin only. Until the first
+    /// token is allowed, any token is accepted.
+    pub fn allow_token(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&TOKEN_ALLOWLIST_KEY)
+            .unwrap_or(Vec::new(&env));
+        if !tokens.contains(&token) {
+            tokens.push_back(token);
+            env.storage().instance().set(&TOKEN_ALLOWLIST_KEY, &tokens);
         }
+        Ok(())
+    }
 
-        let new_claimed = schedule.claimed + claimable;
-        if new_claimed > schedule.total_amount {
-            return Err(Error::NothingToClaim);
+    /// Stop accepting new escrows and pledges in `token`. Admin only.
+    /// Existing escrows and pledges in it settle as usual.
+    pub fn disallow_token(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if let Some(mut tokens) = env
+            .storage()
+            .instance()
+            .get::<_, Vec<Address>>(&TOKEN_ALLOWLIST_KEY)
+        {
+            if let Some(index) = tokens.first_index_of(&token) {
+                tokens.remove(index);
+                env.storage().instance().set(&TOKEN_ALLOWLIST_KEY, &tokens);
+            }
         }
+        Ok(())
+    }
 
-        schedule.claimed = new_claimed;
-        store_vesting(&env, &schedule);
+    /// The accepted tokens, or `None` while any token is accepted.
+    pub fn get_allowed_tokens(env: Env) -> Option<Vec<Address>> {
+        env.storage().instance().get(&TOKEN_ALLOWLIST_KEY)
+    }
 
-        let token_client = token::Client::new(&env, &reward_token);
-        token_client.transfer(&env.current_contract_address(), &donor, &claimable);
+    pub fn is_token_allowed(env: Env, token: Address) -> bool {
+        Self::require_token_allowed(&env, &token).is_ok()
+    }
 
-        env.events().publish(
-            (symbol_short!("vest"), symbol_short!("claimed")),
-            (donor, claimable, new_claimed),
+    /// Value stats through the SEP-40 price feed at `oracle`, ignoring prices
+    /// older than `max_age_secs`. Admin only.
+    pub fn set_price_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        max_age_secs: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(
+            &ORACLE_KEY,
+            &OracleConfig {
+                oracle,
+                max_age_secs,
+            },
         );
-
-        Ok(claimable)
+        Ok(())
     }
 
-    pub fn get_vesting(env: Env, donor: Address) -> Result<VestingSchedule, Error> {
-        load_vesting(&env, &donor).ok_or(Error::VestingNotFound)
+    pub fn get_price_oracle(env: Env) -> Option<OracleConfig> {
+        env.storage().instance().get(&ORACLE_KEY)
     }
 
     // ── Dispute timeout (#595) ─────────────────────────────────────────────────
@@ -1098,8 +3436,7 @@ impl PaymentContract {
             .get(&DISPUTE_TIMEOUT)
             .unwrap_or(DEFAULT_DISPUTE_TIMEOUT_SECS);
         let now = env.ledger().timestamp();
-        let req_contract: Option<Address> =
-            env.storage().instance().get::<_, Address>(&REQ_CONTRACT);
+        let req_contract = requests_contract(&env);
 
         let mut refunded: Vec<u64> = Vec::new(&env);
 
@@ -1120,11 +3457,8 @@ impl PaymentContract {
             }
 
             let token_client = token::Client::new(&env, payment.token.as_ref().unwrap());
-            token_client.transfer(
-                &env.current_contract_address(),
-                &payment.payer,
-                &payment.amount,
-            );
+            let refund = remaining_escrow(&payment);
+            token_client.transfer(&env.current_contract_address(), &payment.payer, &refund);
 
             let old_status = payment.status;
             payment.status = PaymentStatus::Refunded;
@@ -1132,7 +3466,7 @@ impl PaymentContract {
             store_payment(&env, &payment);
             remove_from_status_index(&env, old_status, pid);
             index_by_status(&env, PaymentStatus::Refunded, pid);
-            update_stats_on_transition(&env, payment.amount, old_status, PaymentStatus::Refunded);
+            update_stats_on_settlement(&env, &payment, old_status);
 
             if let Some(ref rc) = req_contract {
                 try_cancel_request(&env, rc, payment.request_id);
@@ -1140,7 +3474,7 @@ impl PaymentContract {
 
             env.events().publish(
                 (symbol_short!("payment"), symbol_short!("refunded")),
-                (pid, payment.payer.clone(), payment.amount),
+                (pid, payment.payer.clone(), refund),
             );
             // Request-level event for off-chain projections.
             env.events().publish(
@@ -1156,6 +3490,190 @@ impl PaymentContract {
 
     // ── Internal helpers ───────────────────────────────────────────────────────
 
+    fn require_token_allowed(env: &Env, token: &Address) -> Result<(), Error> {
+        match env
+            .storage()
+            .instance()
+            .get::<_, Vec<Address>>(&TOKEN_ALLOWLIST_KEY)
+        {
+            Some(tokens) if !tokens.contains(token) => Err(Error::TokenNotAllowed),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fees owed on releasing `amount` of `payment` under the current policy;
+    /// nothing without one. The fixed fee is only owed on the first release.
+    /// The urgency surcharge needs the request's urgency from the requests
+    /// contract; when it cannot be read no surcharge applies.
+    fn fee_for(env: &Env, payment: &Payment, amount: i128) -> FeeBreakdown {
+        let Some(policy) = env
+            .storage()
+            .instance()
+            .get::<_, FeePolicy>(&FEE_POLICY_KEY)
+        else {
+            return FeeBreakdown::none();
+        };
+        let urgency = requests_contract(env)
+            .and_then(|rc| {
+                RequestContractClient::new(env, &rc)
+                    .try_get_request(&payment.request_id)
+                    .ok()
+                    .and_then(|req| req.ok())
+            })
+            .map(|req| req.urgency);
+        let mut fee = compute_fees(&policy, amount, urgency);
+        if payment.released_amount > 0 {
+            fee.total -= fee.fixed_fee;
+            fee.fixed_fee = 0;
+        }
+        fee
+    }
+
+    /// Pay `amount` of `token_addr` to `payee`. It is pushed straight to the
+    /// payee unless they turned auto-push off or the transfer fails, for
+    /// instance without a trustline; then it is credited to their claimable
+    /// balance so settlement goes ahead.
+    fn credit_payee(env: &Env, token_addr: &Address, payee: &Address, amount: i128) {
+        if amount <= 0 {
+            return;
+        }
+        let pushed = !env.storage().persistent().has(&pull_only_key(payee))
+            && token::Client::new(env, token_addr)
+                .try_transfer(&env.current_contract_address(), payee, &amount)
+                .is_ok();
+        if pushed {
+            return;
+        }
+        let balance = get_claimable(env, payee, token_addr) + amount;
+        set_claimable(env, payee, token_addr, balance);
+        env.events().publish(
+            (
+                symbol_short!("payee"),
+                symbol_short!("credited"),
+                symbol_short!("v1"),
+            ),
+            (payee.clone(), token_addr.clone(), amount, balance),
+        );
+    }
+
+    /// Pay `amount` of the escrow to the payee less fees, which go to the
+    /// treasury. Adds to the payment's released amount and fee breakdown and
+    /// returns what the payee received.
+    fn pay_payee(env: &Env, token_addr: &Address, payment: &mut Payment, amount: i128) -> i128 {
+        let token_client = token::Client::new(env, token_addr);
+        let fee = Self::fee_for(env, payment, amount);
+        let net = amount - fee.total;
+        Self::credit_payee(env, token_addr, &payment.payee, net);
+        if let Some(treasury) = fee.treasury.as_ref().filter(|_| fee.total > 0) {
+            token_client.transfer(&env.current_contract_address(), treasury, &fee.total);
+        }
+        payment.released_amount += amount;
+        payment.fee = FeeBreakdown {
+            platform_fee: payment.fee.platform_fee + fee.platform_fee,
+            fixed_fee: payment.fee.fixed_fee + fee.fixed_fee,
+            urgency_surcharge: payment.fee.urgency_surcharge + fee.urgency_surcharge,
+            total: payment.fee.total + fee.total,
+            treasury: fee.treasury.or(payment.fee.treasury.clone()),
+        };
+        net
+    }
+
+    /// Move `amount` of `token` from `hospital` into a new Locked escrow
+    /// payment to `payee`. Callers validate the request and map it to the
+    /// payment.
+    fn lock_escrow(
+        env: &Env,
+        request_id: u64,
+        hospital: &Address,
+        payee: &Address,
+        amount: i128,
+        token: &Address,
+    ) -> Result<u64, Error> {
+        let token_client = token::Client::new(env, token);
+        let available = token_client.balance(hospital);
+        if available < amount {
+            return Err(Error::InsufficientEscrowFunds);
+        }
+        token_client.transfer(hospital, env.current_contract_address(), &amount);
+
+        let id = get_counter(env) + 1;
+        set_counter(env, id);
+
+        let now = env.ledger().timestamp();
+        let payment = Payment {
+            id,
+            request_id,
+            payer: hospital.clone(),
+            payee: payee.clone(),
+            amount,
+            status: PaymentStatus::Locked,
+            created_at: now,
+            updated_at: now,
+            dispute_reason_code: None,
+            dispute_case_id: None,
+            dispute_resolved: false,
+            token: Some(token.clone()),
+            released_amount: 0,
+            fee: FeeBreakdown::none(),
+        };
+
+        store_payment(env, &payment);
+        index_by_payer(env, hospital, id);
+        index_by_payee(env, payee, id);
+        index_by_status(env, PaymentStatus::Locked, id);
+        update_stats_on_transition(
+            env,
+            &payment.token,
+            amount,
+            PaymentStatus::Pending,
+            PaymentStatus::Locked,
+        );
+
+        env.events().publish(
+            (
+                symbol_short!("payment"),
+                symbol_short!("escrowed"),
+                symbol_short!("v1"),
+            ),
+            id,
+        );
+
+        Ok(id)
+    }
+
+    /// Up to `limit` payments from the ascending `ids` that come after
+    /// `cursor`, found by binary search so only the page itself is loaded.
+    fn load_cursor_page(
+        env: &Env,
+        ids: &Vec<u64>,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> PaymentCursorPage {
+        let limit = if limit == 0 { 20 } else { limit };
+        let start = match cursor {
+            None => 0,
+            Some(cursor) => match ids.binary_search(cursor) {
+                Ok(position) => position + 1,
+                Err(position) => position,
+            },
+        };
+        let end = start.saturating_add(limit).min(ids.len());
+        let mut items: Vec<Payment> = Vec::new(env);
+        for i in start..end {
+            if let Some(p) = load_payment(env, ids.get(i).unwrap()) {
+                items.push_back(p);
+            }
+        }
+        PaymentCursorPage {
+            items,
+            next_cursor: if end < ids.len() {
+                ids.get(end - 1)
+            } else {
+                None
+            },
+        }
+    }
+
     fn load_page(env: &Env, ids: Vec<u64>, page: u32, page_size: u32) -> PaymentPage {
         let total = ids.len() as u64;
         let start = (page as u64) * (page_size as u64);
@@ -1,7 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Address, Env};
+use lifebank_types::testutils::upload_placeholder_wasm;
+use soroban_sdk::{
+    testutils::Address as _, testutils::Events as _, testutils::Ledger as _, Address, Env,
+};
 
 fn setup() -> (Env, Address) {
     let env = Env::default();
@@ -188,10 +191,10 @@ fn test_get_payment_by_request_resolves_without_full_scan() {
 #[test]
 fn test_terminal_payment_does_not_block_new_active_payment_for_different_request() {
     // Payments for distinct request IDs must never interfere.
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
     let (id1, _, _) = make_payment(&env, &client, 100, 200);
-    client.update_status(&id1, &PaymentStatus::Refunded);
+    client.update_status(&admin, &id1, &PaymentStatus::Cancelled);
 
     // A payment for a different request must still be accepted.
     let (id2, _, _) = make_payment(&env, &client, 101, 300);
@@ -291,14 +294,14 @@ fn test_get_payments_by_payee_pagination() {
 
 #[test]
 fn test_get_payments_by_status_filters_correctly() {
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
     let (id1, _, _) = make_payment(&env, &client, 1, 100);
     let (id2, _, _) = make_payment(&env, &client, 2, 200);
     make_payment(&env, &client, 3, 300);
 
-    client.update_status(&id1, &PaymentStatus::Locked);
-    client.update_status(&id2, &PaymentStatus::Locked);
+    client.update_status(&admin, &id1, &PaymentStatus::Locked);
+    client.update_status(&admin, &id2, &PaymentStatus::Locked);
 
     let locked = client.get_payments_by_status(&PaymentStatus::Locked, &0u32, &20u32);
     assert_eq!(locked.items.len(), 2);
@@ -320,11 +323,12 @@ fn test_get_payments_by_status_empty_when_none_match() {
 
 #[test]
 fn test_get_payments_by_status_pagination() {
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
     for i in 1u64..=5 {
         let (id, _, _) = make_payment(&env, &client, i, 100);
-        client.update_status(&id, &PaymentStatus::Refunded);
+        client.update_status(&admin, &id, &PaymentStatus::Locked);
+        client.update_status(&admin, &id, &PaymentStatus::Refunded);
     }
 
     let page0 = client.get_payments_by_status(&PaymentStatus::Refunded, &0u32, &3u32);
@@ -352,7 +356,7 @@ fn test_statistics_empty_when_no_payments() {
 
 #[test]
 fn test_statistics_counts_and_totals_correctly() {
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
 
     let (id1, _, _) = make_payment(&env, &client, 1, 1000);
@@ -361,10 +365,11 @@ fn test_statistics_counts_and_totals_correctly() {
     let (id4, _, _) = make_payment(&env, &client, 4, 750);
     make_payment(&env, &client, 5, 300); // stays Pending
 
-    client.update_status(&id1, &PaymentStatus::Locked);
-    client.update_status(&id2, &PaymentStatus::Locked);
-    client.update_status(&id3, &PaymentStatus::Released);
-    client.update_status(&id4, &PaymentStatus::Refunded);
+    for id in [id1, id2, id3, id4] {
+        client.update_status(&admin, &id, &PaymentStatus::Locked);
+    }
+    client.update_status(&admin, &id3, &PaymentStatus::Released);
+    client.update_status(&admin, &id4, &PaymentStatus::Refunded);
 
     let stats = client.get_payment_statistics();
     assert_eq!(stats.count_locked, 2);
@@ -377,14 +382,15 @@ fn test_statistics_counts_and_totals_correctly() {
 
 #[test]
 fn test_statistics_ignores_pending_cancelled_disputed() {
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
     let (id1, _, _) = make_payment(&env, &client, 1, 100);
     let (id2, _, _) = make_payment(&env, &client, 2, 200);
     make_payment(&env, &client, 3, 300); // stays Pending
 
-    client.update_status(&id1, &PaymentStatus::Cancelled);
-    client.update_status(&id2, &PaymentStatus::Disputed);
+    client.update_status(&admin, &id1, &PaymentStatus::Cancelled);
+    client.update_status(&admin, &id2, &PaymentStatus::Locked);
+    client.update_status(&admin, &id2, &PaymentStatus::Disputed);
 
     let stats = client.get_payment_statistics();
     assert_eq!(stats.count_locked, 0);
@@ -400,13 +406,14 @@ fn test_timeline_returns_payments_in_chronological_order() {
     let (env, cid) = setup();
     let client = PaymentContractClient::new(&env, &cid);
 
-    env.ledger().with_mut(|l| l.timestamp = 3000);
+    // Ledger time never goes backwards, so creation order is time order.
+    env.ledger().with_mut(|l| l.timestamp = 1000);
     make_payment(&env, &client, 1, 100);
 
-    env.ledger().with_mut(|l| l.timestamp = 1000);
+    env.ledger().with_mut(|l| l.timestamp = 2000);
     make_payment(&env, &client, 2, 200);
 
-    env.ledger().with_mut(|l| l.timestamp = 2000);
+    env.ledger().with_mut(|l| l.timestamp = 3000);
     make_payment(&env, &client, 3, 300);
 
     let page = client.get_payment_timeline(&0u32, &20u32);
@@ -416,6 +423,72 @@ fn test_timeline_returns_payments_in_chronological_order() {
     assert_eq!(page.items.get(2).unwrap().created_at, 3000);
 }
 
+#[test]
+fn test_timeline_cursor_walks_every_payment_once() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    for request_id in 1..=5u64 {
+        make_payment(&env, &client, request_id, 100);
+    }
+
+    let first = client.get_payment_timeline_after(&None, &2u32);
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.next_cursor, Some(2));
+    let second = client.get_payment_timeline_after(&first.next_cursor, &2u32);
+    assert_eq!(second.items.get(0).unwrap().id, 3);
+    let last = client.get_payment_timeline_after(&second.next_cursor, &2u32);
+    assert_eq!(last.items.len(), 1);
+    assert_eq!(last.items.get(0).unwrap().id, 5);
+    assert_eq!(last.next_cursor, None);
+}
+
+#[test]
+fn test_status_cursor_pages_stay_in_id_order() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let mut ids = [0u64; 4];
+    for (index, id) in ids.iter_mut().enumerate() {
+        *id = make_payment(&env, &client, index as u64 + 1, 100).0;
+    }
+    // Lock out of creation order; the status index stays sorted by ID.
+    for id in [ids[3], ids[0], ids[2]] {
+        client.update_status(&admin, &id, &PaymentStatus::Locked);
+    }
+
+    let page = client.get_payments_by_status_after(&PaymentStatus::Locked, &None, &2u32);
+    assert_eq!(page.items.get(0).unwrap().id, ids[0]);
+    assert_eq!(page.items.get(1).unwrap().id, ids[2]);
+    let rest =
+        client.get_payments_by_status_after(&PaymentStatus::Locked, &page.next_cursor, &2u32);
+    assert_eq!(rest.items.len(), 1);
+    assert_eq!(rest.items.get(0).unwrap().id, ids[3]);
+    assert_eq!(rest.next_cursor, None);
+}
+
+#[test]
+fn test_payer_and_payee_cursor_pages() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let payer = Address::generate(&env);
+    let payee = Address::generate(&env);
+    for request_id in 1..=3u64 {
+        client.create_payment(&request_id, &payer, &payee, &100i128);
+        make_payment(&env, &client, request_id + 10, 100);
+    }
+
+    let page = client.get_payments_by_payer_after(&payer, &None, &2u32);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor, Some(3));
+    let rest = client.get_payments_by_payer_after(&payer, &page.next_cursor, &2u32);
+    assert_eq!(rest.items.len(), 1);
+    assert_eq!(rest.items.get(0).unwrap().id, 5);
+    assert_eq!(rest.next_cursor, None);
+
+    let all = client.get_payments_by_payee_after(&payee, &None, &10u32);
+    assert_eq!(all.items.len(), 3);
+    assert_eq!(all.next_cursor, None);
+}
+
 #[test]
 fn test_timeline_pagination() {
     let (env, cid) = setup();
@@ -464,342 +537,2360 @@ fn test_timeline_out_of_range_page_returns_empty() {
 
 #[test]
 fn test_update_status_changes_payment_status() {
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
     let (id, _, _) = make_payment(&env, &client, 1, 500);
 
-    client.update_status(&id, &PaymentStatus::Locked);
+    client.update_status(&admin, &id, &PaymentStatus::Locked);
     let p = client.get_payment(&id);
     assert_eq!(p.status, PaymentStatus::Locked);
 
-    client.update_status(&id, &PaymentStatus::Released);
+    client.update_status(&admin, &id, &PaymentStatus::Released);
     let p = client.get_payment(&id);
     assert_eq!(p.status, PaymentStatus::Released);
 }
 
 #[test]
 fn test_update_status_returns_not_found_for_missing_payment() {
-    let (env, cid) = setup();
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let result = client.try_update_status(&999u64, &PaymentStatus::Locked);
-    assert!(result.is_err());
+    let result = client.try_update_status(&admin, &999u64, &PaymentStatus::Locked);
+    assert_eq!(result, Err(Ok(Error::PaymentNotFound)));
 }
 
-// ── donation pledges ───────────────────────────────────────────────────────────
-
 #[test]
-fn test_create_pledge_stores_metadata() {
-    let (env, cid) = setup();
+fn test_update_status_rejects_non_settler() {
+    let (env, cid, _admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let donor = Address::generate(&env);
-    let pool = soroban_sdk::String::from_str(&env, "hospital-pool-42");
-    let cause = soroban_sdk::String::from_str(&env, "maternal_health");
-    let region = soroban_sdk::String::from_str(&env, "NG-Lagos");
-
-    let id = client.create_pledge(
-        &donor,
-        &500i128,
-        &2_592_000u64,
-        &pool,
-        &cause,
-        &region,
-        &true,
-    );
+    let (id, payer, payee) = make_payment(&env, &client, 1, 500);
 
-    let p = client.get_pledge(&id);
-    assert_eq!(p.donor, donor);
-    assert_eq!(p.amount_per_period, 500);
-    assert_eq!(p.interval_secs, 2_592_000);
-    assert!(p.emergency_pool);
-    assert!(p.active);
+    for caller in [payer, payee, Address::generate(&env)] {
+        let result = client.try_update_status(&caller, &id, &PaymentStatus::Locked);
+        assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    }
+    assert_eq!(client.get_payment(&id).status, PaymentStatus::Pending);
 }
 
 #[test]
-fn test_create_pledge_rejects_zero_interval() {
-    let (env, cid) = setup();
+fn test_update_status_allowed_for_coordinator() {
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let donor = Address::generate(&env);
-    let pool = soroban_sdk::String::from_str(&env, "pool");
-    let cause = soroban_sdk::String::from_str(&env, "c");
-    let region = soroban_sdk::String::from_str(&env, "r");
-    let r = client.try_create_pledge(&donor, &100i128, &0u64, &pool, &cause, &region, &false);
-    assert!(r.is_err());
-}
+    let coordinator = Address::generate(&env);
+    client.set_coordinator(&admin, &coordinator);
+    let (id, _, _) = make_payment(&env, &client, 1, 500);
 
-// ── Circuit breaker tests ─────────────────────────────────────────────────────
+    client.update_status(&coordinator, &id, &PaymentStatus::Locked);
+    assert_eq!(client.get_payment(&id).status, PaymentStatus::Locked);
+}
 
 #[test]
-fn test_pause_blocks_create_payment() {
-    let (env, cid) = setup();
+fn test_update_status_follows_transition_table() {
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let admin = Address::generate(&env);
-    client.initialize(&admin, &None);
+    let (id, _, _) = make_payment(&env, &client, 1, 500);
 
-    client.pause(&admin);
-    assert!(client.is_paused());
+    // Pending cannot skip straight to settlement.
+    for status in [
+        PaymentStatus::Released,
+        PaymentStatus::Refunded,
+        PaymentStatus::Disputed,
+        PaymentStatus::Pending,
+    ] {
+        assert_eq!(
+            client.try_update_status(&admin, &id, &status),
+            Err(Ok(Error::InvalidStatusTransition))
+        );
+    }
 
-    let payer = Address::generate(&env);
-    let payee = Address::generate(&env);
-    let result = client.try_create_payment(&1u64, &payer, &payee, &500i128);
-    assert!(result.is_err());
+    client.update_status(&admin, &id, &PaymentStatus::Locked);
+    client.update_status(&admin, &id, &PaymentStatus::Released);
+
+    // Released is terminal.
+    for status in [
+        PaymentStatus::Locked,
+        PaymentStatus::Refunded,
+        PaymentStatus::Disputed,
+        PaymentStatus::Cancelled,
+    ] {
+        assert_eq!(
+            client.try_update_status(&admin, &id, &status),
+            Err(Ok(Error::InvalidStatusTransition))
+        );
+    }
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.count_released, 1);
+    assert_eq!(stats.total_released, 500);
 }
 
 #[test]
-fn test_pause_allows_get_payment() {
-    let (env, cid) = setup();
+fn test_update_status_rejects_escrow_payment() {
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let admin = Address::generate(&env);
-    client.initialize(&admin, &None);
-
-    let (id, _, _) = make_payment(&env, &client, 1, 1000);
-    client.pause(&admin);
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
 
-    // Read still works
-    let p = client.get_payment(&id);
-    assert_eq!(p.id, id);
+    // Marking an escrow Released without moving the tokens is refused.
+    assert_eq!(
+        client.try_update_status(&admin, &pid, &PaymentStatus::Released),
+        Err(Ok(Error::EscrowManagedStatus))
+    );
+    assert_eq!(client.get_payment(&pid).status, PaymentStatus::Locked);
 }
 
+// ── disputes ───────────────────────────────────────────────────────────────────
+
 #[test]
-fn test_unpause_restores_payments() {
-    let (env, cid) = setup();
+fn test_record_dispute_allowed_for_payment_parties() {
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let admin = Address::generate(&env);
-    client.initialize(&admin, &None);
+    let case_id = soroban_sdk::String::from_str(&env, "case-1");
 
-    client.pause(&admin);
-    client.unpause(&admin);
-    assert!(!client.is_paused());
+    let (id1, payer, _) = make_payment(&env, &client, 1, 100);
+    let (id2, _, payee) = make_payment(&env, &client, 2, 100);
+    client.update_status(&admin, &id1, &PaymentStatus::Locked);
+    client.update_status(&admin, &id2, &PaymentStatus::Locked);
 
-    let (id, _, _) = make_payment(&env, &client, 99, 200);
-    assert!(id > 0);
+    client.record_dispute(&payer, &id1, &DisputeReason::WrongItem, &case_id);
+    client.record_dispute(&payee, &id2, &DisputeReason::PaymentContested, &case_id);
+
+    let p1 = client.get_payment(&id1);
+    assert_eq!(p1.status, PaymentStatus::Disputed);
+    assert_eq!(p1.dispute_reason_code, Some(4));
+    assert_eq!(client.get_payment(&id2).status, PaymentStatus::Disputed);
 }
 
 #[test]
-#[should_panic]
-fn test_non_admin_cannot_pause_payments() {
-    let (env, cid) = setup();
+fn test_record_dispute_rejects_outsider() {
+    let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let admin = Address::generate(&env);
-    client.initialize(&admin, &None);
-
-    let attacker = Address::generate(&env);
-    client.pause(&attacker);
-}
-
-// ── Vesting schedule tests ─────────────────────────────────────────────────────
-
-fn setup_with_admin() -> (Env, Address, Address) {
-    let env = Env::default();
-    env.mock_all_auths();
-    let contract_id = env.register(PaymentContract, ());
-    let admin = Address::generate(&env);
-    let client = PaymentContractClient::new(&env, &contract_id);
-    client.initialize(&admin, &None);
-    (env, contract_id, admin)
+    let (id, _, _) = make_payment(&env, &client, 1, 100);
+    client.update_status(&admin, &id, &PaymentStatus::Locked);
+
+    let result = client.try_record_dispute(
+        &Address::generate(&env),
+        &id,
+        &DisputeReason::Other,
+        &soroban_sdk::String::from_str(&env, "case-1"),
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.get_payment(&id).status, PaymentStatus::Locked);
 }
 
-/// Pre-cliff claim must return CliffNotReached.
 #[test]
-fn test_vesting_pre_cliff_claim_fails() {
+fn test_record_dispute_requires_locked_payment() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let donor = Address::generate(&env);
-
-    // cliff = now + 1000s, duration = 2000s
-    env.ledger().with_mut(|l| l.timestamp = 5000);
-    client.create_vesting(&admin, &donor, &1_000_000i128, &1000u64, &2000u64);
+    let case_id = soroban_sdk::String::from_str(&env, "case-1");
+    let (id, _, _) = make_payment(&env, &client, 1, 100);
 
-    // Deploy reward token and mint to contract so it can transfer
-    let token_id = deploy_token_with_balance(&env, &admin, &cid, 1_000_000);
-
-    // Try to claim at t=5500 (before cliff at t=6000)
-    env.ledger().with_mut(|l| l.timestamp = 5500);
-    let result = client.try_claim_vested(&donor, &token_id);
+    // Pending, then Released: neither can be disputed.
     assert_eq!(
-        result,
-        Err(Ok(Error::CliffNotReached)),
-        "Expected CliffNotReached before cliff"
+        client.try_record_dispute(&admin, &id, &DisputeReason::Other, &case_id),
+        Err(Ok(Error::InvalidStatusTransition))
     );
+    client.update_status(&admin, &id, &PaymentStatus::Locked);
+    client.update_status(&admin, &id, &PaymentStatus::Released);
+    assert_eq!(
+        client.try_record_dispute(&admin, &id, &DisputeReason::Other, &case_id),
+        Err(Ok(Error::InvalidStatusTransition))
+    );
+    assert_eq!(client.get_payment_statistics().count_released, 1);
 }
 
-/// At 50% of vesting duration, claimable = total/2.
 #[test]
-fn test_vesting_partial_claim_at_50_percent() {
+fn test_resolve_dispute_requires_settler_and_open_dispute() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let donor = Address::generate(&env);
+    let (id, payer, _) = make_payment(&env, &client, 1, 100);
+    client.update_status(&admin, &id, &PaymentStatus::Locked);
+    let evidence = BytesN::from_array(&env, &[1; 32]);
 
-    // cliff = now + 0 (immediate), duration = 2000s → vest_end = now + 2000
-    env.ledger().with_mut(|l| l.timestamp = 10_000);
-    client.create_vesting(&admin, &donor, &1_000_000i128, &0u64, &2000u64);
+    assert_eq!(
+        client.try_resolve_dispute(&admin, &id, &DisputeOutcome::Refund, &evidence),
+        Err(Ok(Error::PaymentNotDisputed))
+    );
 
-    let token_id = deploy_token_with_balance(&env, &admin, &cid, 1_000_000);
+    client.record_dispute(
+        &payer,
+        &id,
+        &DisputeReason::DamagedGoods,
+        &soroban_sdk::String::from_str(&env, "case-1"),
+    );
+    assert_eq!(
+        client.try_resolve_dispute(&payer, &id, &DisputeOutcome::Refund, &evidence),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_resolve_dispute(&admin, &id, &DisputeOutcome::Split(10_001), &evidence),
+        Err(Ok(Error::InvalidSplit))
+    );
 
-    // Advance to 50% of vesting duration (cliff == vest_start == 10_000, vest_end == 12_000)
-    env.ledger().with_mut(|l| l.timestamp = 11_000); // 1000s elapsed of 2000s
-    let claimed = client.claim_vested(&donor, &token_id);
-    assert_eq!(claimed, 500_000i128, "50% vesting should yield half the total");
+    client.resolve_dispute(&admin, &id, &DisputeOutcome::Refund, &evidence);
+    let p = client.get_payment(&id);
+    assert!(p.dispute_resolved);
+    assert_eq!(p.status, PaymentStatus::Refunded);
+    assert_eq!(
+        client.try_resolve_dispute(&admin, &id, &DisputeOutcome::Release, &evidence),
+        Err(Ok(Error::PaymentNotDisputed))
+    );
+}
 
-    let schedule = client.get_vesting(&donor);
-    assert_eq!(schedule.claimed, 500_000i128);
+/// Lock `amount` in escrow and dispute it, returning the payment ID, payer,
+/// payee and token.
+fn disputed_escrow(
+    env: &Env,
+    client: &PaymentContractClient,
+    admin: &Address,
+    amount: i128,
+) -> (u64, Address, Address, Address) {
+    let hospital = Address::generate(env);
+    let bank = Address::generate(env);
+    let token_id = deploy_token_with_balance(env, admin, &hospital, amount);
+    let pid = client.create_escrow(&1u64, &hospital, &bank, &amount, &token_id);
+    client.record_dispute(
+        &hospital,
+        &pid,
+        &DisputeReason::FailedDelivery,
+        &soroban_sdk::String::from_str(env, "case-1"),
+    );
+    (pid, hospital, bank, token_id)
 }
 
-/// After vesting end, donor can claim the full remaining amount.
 #[test]
-fn test_vesting_full_claim_after_vest_end() {
+fn test_resolve_dispute_refund_returns_escrow() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let donor = Address::generate(&env);
-
-    env.ledger().with_mut(|l| l.timestamp = 1_000);
-    client.create_vesting(&admin, &donor, &500_000i128, &0u64, &1000u64);
-
-    let token_id = deploy_token_with_balance(&env, &admin, &cid, 500_000);
-
-    // Advance past vest_end
-    env.ledger().with_mut(|l| l.timestamp = 3_000);
-    let claimed = client.claim_vested(&donor, &token_id);
-    assert_eq!(claimed, 500_000i128, "Full amount claimable after vest end");
+    let (pid, hospital, bank, token_id) = disputed_escrow(&env, &client, &admin, 1_000);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+
+    client.resolve_dispute(
+        &admin,
+        &pid,
+        &DisputeOutcome::Refund,
+        &BytesN::from_array(&env, &[2; 32]),
+    );
 
-    let schedule = client.get_vesting(&donor);
-    assert_eq!(schedule.claimed, 500_000i128);
-    assert_eq!(schedule.claimed, schedule.total_amount);
+    assert_eq!(token.balance(&hospital), 1_000);
+    assert_eq!(token.balance(&bank), 0);
+    assert_eq!(token.balance(&cid), 0);
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Refunded);
+    assert_eq!(p.released_amount, 0);
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.count_refunded, 1);
+    assert_eq!(stats.total_refunded, 1_000);
+    assert_eq!(stats.count_locked, 0);
+    let refunded = client.get_payments_by_status(&PaymentStatus::Refunded, &0u32, &10u32);
+    assert_eq!(refunded.total, 1);
+    let disputed = client.get_payments_by_status(&PaymentStatus::Disputed, &0u32, &10u32);
+    assert_eq!(disputed.total, 0);
 }
 
-/// Donor cannot claim more than total_amount across multiple claims.
 #[test]
-fn test_vesting_cannot_exceed_total_amount() {
+fn test_resolve_dispute_release_pays_payee() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
-    let donor = Address::generate(&env);
+    let (pid, hospital, bank, token_id) = disputed_escrow(&env, &client, &admin, 1_000);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+
+    client.resolve_dispute(
+        &admin,
+        &pid,
+        &DisputeOutcome::Release,
+        &BytesN::from_array(&env, &[3; 32]),
+    );
 
-    env.ledger().with_mut(|l| l.timestamp = 1_000);
-    client.create_vesting(&admin, &donor, &1_000_000i128, &0u64, &1000u64);
+    assert_eq!(token.balance(&bank), 1_000);
+    assert_eq!(token.balance(&hospital), 0);
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Released);
+    assert_eq!(p.released_amount, 1_000);
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.count_released, 1);
+    assert_eq!(stats.total_released, 1_000);
+    assert_eq!(stats.total_refunded, 0);
+}
 
-    let token_id = deploy_token_with_balance(&env, &admin, &cid, 1_000_000);
+#[test]
+fn test_resolve_dispute_split_records_resolution() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let coordinator = Address::generate(&env);
+    client.set_coordinator(&admin, &coordinator);
+    let (pid, hospital, bank, token_id) = disputed_escrow(&env, &client, &admin, 1_000);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    let evidence = BytesN::from_array(&env, &[4; 32]);
+    assert_eq!(client.get_dispute_resolution(&pid), None);
 
-    // Claim full amount after vest end
     env.ledger().with_mut(|l| l.timestamp = 5_000);
-    let first = client.claim_vested(&donor, &token_id);
-    assert_eq!(first, 1_000_000i128);
+    let resolution =
+        client.resolve_dispute(&coordinator, &pid, &DisputeOutcome::Split(2_500), &evidence);
 
-    // Second claim should fail with NothingToClaim
-    let result = client.try_claim_vested(&donor, &token_id);
+    assert_eq!(token.balance(&bank), 250);
+    assert_eq!(token.balance(&hospital), 750);
+    assert_eq!(token.balance(&cid), 0);
     assert_eq!(
-        result,
-        Err(Ok(Error::NothingToClaim)),
-        "Second claim after full vest should fail"
+        resolution,
+        DisputeResolution {
+            payment_id: pid,
+            arbiter: coordinator,
+            outcome: DisputeOutcome::Split(2_500),
+            payee_amount: 250,
+            payer_amount: 750,
+            evidence_hash: evidence,
+            resolved_at: 5_000,
+        }
     );
+    assert_eq!(client.get_dispute_resolution(&pid), Some(resolution));
+
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Released);
+    assert_eq!(p.released_amount, 250);
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.count_released, 1);
+    assert_eq!(stats.total_released, 250);
+    assert_eq!(stats.total_refunded, 750);
 }
 
-/// Non-admin cannot create a vesting schedule.
-#[test]
-fn test_vesting_only_admin_can_create() {
-    let (env, cid, _admin) = setup_with_admin();
-    let client = PaymentContractClient::new(&env, &cid);
-    let attacker = Address::generate(&env);
-    let donor = Address::generate(&env);
+// ── milestones ─────────────────────────────────────────────────────────────────
 
-    env.ledger().with_mut(|l| l.timestamp = 1_000);
-    let result = client.try_create_vesting(&attacker, &donor, &1_000i128, &100u64, &500u64);
-    assert!(result.is_err(), "Non-admin must not create vesting");
+/// Lock 1 000 in escrow split into milestones of 300, 300 and 400, returning
+/// the payment ID, payer, payee and token.
+fn milestone_escrow(
+    env: &Env,
+    client: &PaymentContractClient,
+    admin: &Address,
+) -> (u64, Address, Address, Address) {
+    let hospital = Address::generate(env);
+    let bank = Address::generate(env);
+    let token_id = deploy_token_with_balance(env, admin, &hospital, 1_000);
+    let pid = client.create_escrow(&1u64, &hospital, &bank, &1_000, &token_id);
+    client.set_milestones(
+        admin,
+        &pid,
+        &soroban_sdk::vec![
+            env,
+            (300i128, MilestoneCondition::LegDelivered(0)),
+            (300i128, MilestoneCondition::LegDelivered(1)),
+            (400i128, MilestoneCondition::Manual),
+        ],
+    );
+    (pid, hospital, bank, token_id)
 }
 
-// ── process_expired_disputes (#595) ─────────────────────────────────────────────────
-
 #[test]
-fn test_process_expired_disputes_refunds_after_timeout() {
+fn test_release_milestones_until_escrow_is_settled() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
+    let (pid, _, bank, token_id) = milestone_escrow(&env, &client, &admin);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
 
-    let hospital = Address::generate(&env);
-    let payee = Address::generate(&env);
-    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
-
-    env.ledger().with_mut(|l| l.timestamp = 1_000);
-    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+    assert_eq!(client.release_milestone(&admin, &pid, &1), 700);
+    assert_eq!(
+        client.try_release_milestone(&admin, &pid, &1),
+        Err(Ok(Error::MilestoneAlreadyReleased))
+    );
+    assert_eq!(
+        client.try_release_milestone(&admin, &pid, &3),
+        Err(Ok(Error::MilestoneNotFound))
+    );
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.status, PaymentStatus::Locked);
+    assert_eq!(payment.released_amount, 300);
+    assert_eq!(token.balance(&bank), 300);
+    assert_eq!(client.get_remaining_escrow(&pid), 700);
+    assert!(client
+        .get_milestones(&pid)
+        .get(1)
+        .unwrap()
+        .released_at
+        .is_some());
+
+    client.release_milestone(&admin, &pid, &0);
+    assert_eq!(client.release_milestone(&admin, &pid, &2), 0);
+    assert_eq!(client.get_payment(&pid).status, PaymentStatus::Released);
+    assert_eq!(token.balance(&bank), 1_000);
+    assert_eq!(client.get_remaining_escrow(&pid), 0);
+
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.total_locked, 0);
+    assert_eq!(stats.total_released, 1_000);
+    assert_eq!(stats.count_released, 1);
+}
+
+#[test]
+fn test_refund_after_milestone_returns_only_remainder() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (pid, hospital, bank, token_id) = milestone_escrow(&env, &client, &admin);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+
+    client.release_milestone(&admin, &pid, &0);
+    client.refund_escrow(&admin, &pid);
+
+    assert_eq!(token.balance(&bank), 300);
+    assert_eq!(token.balance(&hospital), 700);
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.status, PaymentStatus::Refunded);
+    assert_eq!(payment.released_amount, 300);
+
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.total_released, 300);
+    assert_eq!(stats.total_refunded, 700);
+    assert_eq!(stats.count_refunded, 1);
+}
+
+#[test]
+fn test_dispute_after_milestone_settles_remainder() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (pid, hospital, bank, token_id) = milestone_escrow(&env, &client, &admin);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+
+    client.release_milestone(&admin, &pid, &0);
+    client.record_dispute(
+        &hospital,
+        &pid,
+        &DisputeReason::DamagedGoods,
+        &soroban_sdk::String::from_str(&env, "case-2"),
+    );
+    let resolution = client.resolve_dispute(
+        &admin,
+        &pid,
+        &DisputeOutcome::Refund,
+        &BytesN::from_array(&env, &[1; 32]),
+    );
+
+    // The delivered milestone stays paid; only the disputed 700 is refunded.
+    assert_eq!(resolution.payer_amount, 700);
+    assert_eq!(token.balance(&bank), 300);
+    assert_eq!(token.balance(&hospital), 700);
+    assert_eq!(client.get_payment(&pid).status, PaymentStatus::Released);
+}
+
+#[test]
+fn test_set_milestones_validation() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let pid = client.create_escrow(
+        &1u64,
+        &hospital,
+        &Address::generate(&env),
+        &1_000,
+        &token_id,
+    );
+
+    let short = soroban_sdk::vec![&env, (600i128, MilestoneCondition::Manual)];
+    assert_eq!(
+        client.try_set_milestones(&admin, &pid, &short),
+        Err(Ok(Error::InvalidMilestones))
+    );
+    let negative = soroban_sdk::vec![
+        &env,
+        (1_100i128, MilestoneCondition::Manual),
+        (-100i128, MilestoneCondition::Manual)
+    ];
+    assert_eq!(
+        client.try_set_milestones(&admin, &pid, &negative),
+        Err(Ok(Error::InvalidMilestones))
+    );
+
+    let split = soroban_sdk::vec![
+        &env,
+        (500i128, MilestoneCondition::UnitDelivered(7)),
+        (500i128, MilestoneCondition::UnitDelivered(8))
+    ];
+    assert_eq!(
+        client.try_set_milestones(&hospital, &pid, &split),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_milestones(&admin, &pid, &split);
+    assert_eq!(
+        client.try_set_milestones(&admin, &pid, &split),
+        Err(Ok(Error::InvalidMilestones))
+    );
+}
+
+// ── donation pledges ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_create_pledge_stores_metadata() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+    let pool = soroban_sdk::String::from_str(&env, "hospital-pool-42");
+    let cause = soroban_sdk::String::from_str(&env, "maternal_health");
+    let region = soroban_sdk::String::from_str(&env, "NG-Lagos");
+
+    let id = client.create_pledge(
+        &donor,
+        &Address::generate(&env),
+        &500i128,
+        &2_592_000u64,
+        &pool,
+        &cause,
+        &region,
+        &true,
+    );
+
+    let p = client.get_pledge(&id);
+    assert_eq!(p.donor, donor);
+    assert_eq!(p.amount_per_period, 500);
+    assert_eq!(p.interval_secs, 2_592_000);
+    assert!(p.emergency_pool);
+    assert!(p.active);
+}
+
+#[test]
+fn test_create_pledge_rejects_zero_interval() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+    let pool = soroban_sdk::String::from_str(&env, "pool");
+    let cause = soroban_sdk::String::from_str(&env, "c");
+    let region = soroban_sdk::String::from_str(&env, "r");
+    let token = Address::generate(&env);
+    let r = client.try_create_pledge(
+        &donor, &token, &100i128, &0u64, &pool, &cause, &region, &false,
+    );
+    assert!(r.is_err());
+}
+
+/// Create a monthly pledge of 100 from a donor holding `balance` and
+/// allowing the contract to pull `allowance`. Returns the pledge ID, donor,
+/// token and pool.
+fn funded_pledge(
+    env: &Env,
+    client: &PaymentContractClient,
+    admin: &Address,
+    balance: i128,
+    allowance: i128,
+) -> (u64, Address, Address, PoolId) {
+    let donor = Address::generate(env);
+    let token_id = deploy_token_with_balance(env, admin, &donor, balance);
+    soroban_sdk::token::Client::new(env, &token_id).approve(
+        &donor,
+        &client.address,
+        &allowance,
+        &(env.ledger().sequence() + 10_000),
+    );
+    let pool = PoolId {
+        name: soroban_sdk::String::from_str(env, "hospital-pool-42"),
+        region: soroban_sdk::String::from_str(env, "NG-Lagos"),
+    };
+    let id = client.create_pledge(
+        &donor,
+        &token_id,
+        &100i128,
+        &MONTH,
+        &pool.name,
+        &soroban_sdk::String::from_str(env, "maternal_health"),
+        &pool.region,
+        &false,
+    );
+    (id, donor, token_id, pool)
+}
+
+const MONTH: u64 = 2_592_000;
+
+#[test]
+fn test_execute_due_pledges_collects_each_period() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (id, donor, token_id, pool) = funded_pledge(&env, &client, &admin, 1_000, 1_000);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+
+    // The first period is due at creation.
+    let handled = client.execute_due_pledges(&10u32);
+    assert_eq!(handled.len(), 1);
+    assert!(handled.get(0).unwrap().collected);
+    assert_eq!(client.execute_due_pledges(&10u32).len(), 0);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000 + MONTH);
+    client.execute_due_pledges(&10u32);
+
+    assert_eq!(token.balance(&donor), 800);
+    assert_eq!(token.balance(&cid), 200);
+    assert_eq!(client.get_pool_balance(&pool, &token_id), 200);
+    assert_eq!(client.get_donor_total(&donor, &token_id), 200);
+
+    let funding = client.get_pledge_funding(&id);
+    assert_eq!(funding.periods_executed, 2);
+    assert_eq!(funding.total_given, 200);
+    assert_eq!(funding.next_due, 1_000 + 2 * MONTH);
+    assert!(!funding.underfunded);
+
+    let history = client.get_pledge_history(&id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(1).unwrap().period, 2);
+    assert_eq!(history.get(1).unwrap().executed_at, 1_000 + MONTH);
+}
+
+#[test]
+fn test_pledge_history_is_paged() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (id, _donor, _token_id, _pool) = funded_pledge(&env, &client, &admin, 10_000, 10_000);
+
+    // 60 periods due, collected over two calls.
+    env.ledger().with_mut(|l| l.timestamp = 1_000 + 59 * MONTH);
+    assert_eq!(client.execute_due_pledges(&40u32).len(), 40);
+    assert_eq!(client.execute_due_pledges(&40u32).len(), 20);
+
+    assert_eq!(client.get_pledge_history_page_count(&id), 1);
+    let first = client.get_pledge_history_page(&id, &0);
+    assert_eq!(first.len(), 50);
+    assert_eq!(first.get(0).unwrap().period, 1);
+    let last = client.get_pledge_history_page(&id, &1);
+    assert_eq!(last.len(), 10);
+    assert_eq!(last.get(9).unwrap().period, 60);
+    assert_eq!(client.get_pledge_history_page(&id, &2).len(), 0);
+
+    let history = client.get_pledge_history(&id);
+    assert_eq!(history.len(), 60);
+    assert_eq!(history.get(50).unwrap().period, 51);
+}
+
+#[test]
+fn test_execute_due_pledges_skips_and_flags_short_allowance() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (id, donor, token_id, pool) = funded_pledge(&env, &client, &admin, 1_000, 150);
+
+    client.execute_due_pledges(&10u32);
+    env.ledger().with_mut(|l| l.timestamp += MONTH);
+    let handled = client.execute_due_pledges(&10u32);
+
+    // 50 of allowance is left, so the second period is skipped.
+    assert!(!handled.get(0).unwrap().collected);
+    let funding = client.get_pledge_funding(&id);
+    assert!(funding.underfunded);
+    assert_eq!(funding.periods_executed, 1);
+    assert_eq!(funding.periods_skipped, 1);
+    assert_eq!(client.get_donor_total(&donor, &token_id), 100);
+    assert_eq!(client.get_pool_balance(&pool, &token_id), 100);
+
+    // A topped-up allowance clears the flag on the next period.
+    soroban_sdk::token::Client::new(&env, &token_id).approve(
+        &donor,
+        &cid,
+        &500,
+        &(env.ledger().sequence() + 10_000),
+    );
+    env.ledger().with_mut(|l| l.timestamp += MONTH);
+    client.execute_due_pledges(&10u32);
+    let funding = client.get_pledge_funding(&id);
+    assert!(!funding.underfunded);
+    assert_eq!(funding.total_given, 200);
+    assert_eq!(client.get_pledge_history(&id).len(), 3);
+}
+
+#[test]
+fn test_execute_due_pledges_skips_short_balance() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (id, _, _, _) = funded_pledge(&env, &client, &admin, 50, 1_000);
+
+    let handled = client.execute_due_pledges(&10u32);
+    assert!(!handled.get(0).unwrap().collected);
+    assert!(client.get_pledge_funding(&id).underfunded);
+}
+
+#[test]
+fn test_execute_due_pledges_respects_max() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (id, _, _, _) = funded_pledge(&env, &client, &admin, 10_000, 10_000);
+
+    // Five periods are due after four months; two calls of three catch up.
+    env.ledger().with_mut(|l| l.timestamp += 4 * MONTH);
+    assert_eq!(client.execute_due_pledges(&3u32).len(), 3);
+    assert_eq!(client.execute_due_pledges(&3u32).len(), 2);
+    assert_eq!(client.get_pledge_funding(&id).periods_executed, 5);
+}
+
+#[test]
+fn test_execute_due_pledges_resumes_after_scan_limit() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (_, donor, token_id, pool) = funded_pledge(&env, &client, &admin, 100_000, 100_000);
+    for _ in 1..60 {
+        client.create_pledge(
+            &donor,
+            &token_id,
+            &100i128,
+            &MONTH,
+            &pool.name,
+            &soroban_sdk::String::from_str(&env, "maternal_health"),
+            &pool.region,
+            &false,
+        );
+    }
+
+    // One call visits 50 pledges; the next picks up the remaining ten.
+    assert_eq!(client.execute_due_pledges(&1_000u32).len(), 50);
+    assert_eq!(client.execute_due_pledges(&1_000u32).len(), 10);
+    assert_eq!(client.execute_due_pledges(&1_000u32).len(), 0);
+    for id in 1..=60u64 {
+        assert_eq!(client.get_pledge_funding(&id).periods_executed, 1);
+    }
+}
+
+#[test]
+fn test_execute_due_pledges_flags_pledge_without_funding() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (id, _, _, _) = funded_pledge(&env, &client, &admin, 1_000, 1_000);
+    // A pledge made before pledges carried a token has no funding record.
+    env.as_contract(&cid, || {
+        env.storage().persistent().remove(&pledge_funding_key(id));
+    });
+
+    assert_eq!(client.execute_due_pledges(&10u32).len(), 0);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let kind: soroban_sdk::Symbol =
+        soroban_sdk::TryFromVal::try_from_val(&env, &topics.get(1).unwrap()).unwrap();
+    assert_eq!(kind, symbol_short!("nofund"));
+    let pledge_id: u64 = soroban_sdk::TryFromVal::try_from_val(&env, &data).unwrap();
+    assert_eq!(pledge_id, id);
+}
+
+#[test]
+fn test_paused_pledge_is_not_charged_for_missed_periods() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let (id, donor, _, _) = funded_pledge(&env, &client, &admin, 10_000, 10_000);
+    client.execute_due_pledges(&10u32);
+
+    client.set_pledge_active(&id, &donor, &false);
+    env.ledger().with_mut(|l| l.timestamp += 3 * MONTH);
+    assert_eq!(client.execute_due_pledges(&10u32).len(), 0);
+
+    client.set_pledge_active(&id, &donor, &true);
+    assert_eq!(client.execute_due_pledges(&10u32).len(), 1);
+    assert_eq!(client.get_pledge_funding(&id).periods_executed, 2);
+}
+
+// ── Donation pools ────────────────────────────────────────────────────────────
+
+/// Requests contract stand-in that serves seeded requests to `get_request`.
+#[soroban_sdk::contract]
+struct MockRequestContract;
+
+#[soroban_sdk::contractimpl]
+impl MockRequestContract {
+    pub fn seed_request(env: Env, id: u64, hospital: Address, status: ReqStatus) {
+        let request = BloodRequest {
+            id,
+            hospital_id: hospital,
+            blood_type: lifebank_types::BloodType::OPositive,
+            component: lifebank_types::BloodComponent::WholeBlood,
+            quantity_ml: 450,
+            urgency: lifebank_types::Urgency::Urgent,
+            created_timestamp: 0,
+            required_by_timestamp: 3_600,
+            status,
+            assigned_units: soroban_sdk::Vec::new(&env),
+            fulfilled_quantity_ml: 0,
+            reservation_id: None,
+        };
+        env.storage().persistent().set(&id, &request);
+    }
+
+    pub fn get_request(env: Env, request_id: u64) -> BloodRequest {
+        env.storage().persistent().get(&request_id).unwrap()
+    }
+}
+
+/// Payments wired to a mock requests contract holding request 1 (Approved)
+/// and request 2 (Fulfilled) for the returned hospital. The pool from
+/// `funded_pledge` holds 300 of the returned token.
+fn funded_pool() -> (
+    Env,
+    PaymentContractClient<'static>,
+    Address,
+    Address,
+    PoolId,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let requests = env.register(MockRequestContract, ());
+    let requests_client = MockRequestContractClient::new(&env, &requests);
+    let hospital = Address::generate(&env);
+    requests_client.seed_request(&1, &hospital, &ReqStatus::Approved);
+    requests_client.seed_request(&2, &hospital, &ReqStatus::Fulfilled);
+
+    let client = PaymentContractClient::new(&env, &env.register(PaymentContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &Some(requests));
+
+    let (_, _, token_id, pool) = funded_pledge(&env, &client, &admin, 300, 300);
+    for _ in 0..3 {
+        client.execute_due_pledges(&10u32);
+        env.ledger().with_mut(|l| l.timestamp += MONTH);
+    }
+    (env, client, admin, token_id, pool, hospital)
+}
+
+#[test]
+fn test_create_pool_rejects_duplicate_and_bad_policy() {
+    let (env, client, admin, _, pool, _) = funded_pool();
+    client.create_pool(&admin, &pool, &false, &DisbursementPolicy::Admin);
+    assert_eq!(
+        client.try_create_pool(&admin, &pool, &false, &DisbursementPolicy::Admin),
+        Err(Ok(Error::PoolExists))
+    );
+
+    let other = PoolId {
+        name: soroban_sdk::String::from_str(&env, "emergency"),
+        region: soroban_sdk::String::from_str(&env, "NG-Lagos"),
+    };
+    let policy = DisbursementPolicy::Multisig(MultisigPolicy {
+        signers: soroban_sdk::vec![&env, Address::generate(&env)],
+        threshold: 2,
+    });
+    assert_eq!(
+        client.try_create_pool(&admin, &other, &true, &policy),
+        Err(Ok(Error::InvalidPolicy))
+    );
+    assert_eq!(
+        client.try_create_pool(
+            &Address::generate(&env),
+            &other,
+            &true,
+            &DisbursementPolicy::Admin
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_disburse_from_pool_pays_request_hospital() {
+    let (env, client, admin, token_id, pool, hospital) = funded_pool();
+    client.create_pool(&admin, &pool, &false, &DisbursementPolicy::Admin);
+
+    let index = client.disburse_from_pool(
+        &soroban_sdk::vec![&env, admin.clone()],
+        &pool,
+        &token_id,
+        &1,
+        &120,
+    );
+
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&hospital), 120);
+    assert_eq!(client.get_pool_balance(&pool, &token_id), 180);
+
+    // Three deposits, then the disbursement.
+    assert_eq!(index, 3);
+    assert_eq!(client.get_pool_ledger_len(&pool), 4);
+    let ledger = client.get_pool_ledger(&pool, &0, &10);
+    assert_eq!(ledger.get(0).unwrap().kind, PoolEntryKind::Deposit);
+    assert_eq!(ledger.get(0).unwrap().pledge_id, Some(1));
+    let entry = ledger.get(3).unwrap();
+    assert_eq!(entry.kind, PoolEntryKind::Disbursement);
+    assert_eq!(entry.counterparty, hospital);
+    assert_eq!(entry.request_id, Some(1));
+    assert_eq!(entry.amount, 120);
+
+    let page = client.get_pool_ledger(&pool, &1, &3);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap(), entry);
+}
+
+#[test]
+fn test_disburse_from_pool_rejects_bad_requests() {
+    let (env, client, admin, token_id, pool, _) = funded_pool();
+    let approvers = soroban_sdk::vec![&env, admin.clone()];
+    assert_eq!(
+        client.try_disburse_from_pool(&approvers, &pool, &token_id, &1, &100),
+        Err(Ok(Error::PoolNotFound))
+    );
+
+    client.create_pool(&admin, &pool, &false, &DisbursementPolicy::Admin);
+    assert_eq!(
+        client.try_disburse_from_pool(&approvers, &pool, &token_id, &2, &100),
+        Err(Ok(Error::RequestNotPayable))
+    );
+    assert_eq!(
+        client.try_disburse_from_pool(&approvers, &pool, &token_id, &1, &301),
+        Err(Ok(Error::InsufficientPoolBalance))
+    );
+    assert_eq!(
+        client.try_disburse_from_pool(
+            &soroban_sdk::vec![&env, Address::generate(&env)],
+            &pool,
+            &token_id,
+            &1,
+            &100
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_disburse_from_pool_multisig_threshold() {
+    let (env, client, admin, token_id, pool, hospital) = funded_pool();
+    let signers = soroban_sdk::vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env)
+    ];
+    let policy = DisbursementPolicy::Multisig(MultisigPolicy {
+        signers: signers.clone(),
+        threshold: 2,
+    });
+    client.create_pool(&admin, &pool, &true, &policy);
+    assert!(client.get_pool(&pool).emergency);
+
+    // The admin is not a signer, and a repeated signer counts once.
+    let short = soroban_sdk::vec![
+        &env,
+        signers.get(0).unwrap(),
+        signers.get(0).unwrap(),
+        admin
+    ];
+    assert_eq!(
+        client.try_disburse_from_pool(&short, &pool, &token_id, &1, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    let enough = soroban_sdk::vec![&env, signers.get(0).unwrap(), signers.get(2).unwrap()];
+    client.disburse_from_pool(&enough, &pool, &token_id, &1, &100);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token_id).balance(&hospital),
+        100
+    );
+}
+
+// ── Circuit breaker tests ─────────────────────────────────────────────────────
+
+#[test]
+fn test_pause_blocks_create_payment() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &None);
+
+    client.pause(&admin);
+    assert!(client.is_paused());
+
+    let payer = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let result = client.try_create_payment(&1u64, &payer, &payee, &500i128);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_pause_allows_get_payment() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &None);
+
+    let (id, _, _) = make_payment(&env, &client, 1, 1000);
+    client.pause(&admin);
+
+    // Read still works
+    let p = client.get_payment(&id);
+    assert_eq!(p.id, id);
+}
+
+#[test]
+fn test_unpause_restores_payments() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &None);
+
+    client.pause(&admin);
+    client.unpause(&admin);
+    assert!(!client.is_paused());
+
+    let (id, _, _) = make_payment(&env, &client, 99, 200);
+    assert!(id > 0);
+}
+
+#[test]
+#[should_panic]
+fn test_non_admin_cannot_pause_payments() {
+    let (env, cid) = setup();
+    let client = PaymentContractClient::new(&env, &cid);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &None);
+
+    let attacker = Address::generate(&env);
+    client.pause(&attacker);
+}
+
+// ── Vesting schedule tests ─────────────────────────────────────────────────────
+
+fn setup_with_admin() -> (Env, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(PaymentContract, ());
+    let admin = Address::generate(&env);
+    let client = PaymentContractClient::new(&env, &contract_id);
+    client.initialize(&admin, &None);
+    (env, contract_id, admin)
+}
+
+/// Mint `amount` of a fresh reward token to the admin and vest it for
+/// `donor`, returning the schedule ID and token.
+fn funded_vesting(
+    env: &Env,
+    client: &PaymentContractClient,
+    admin: &Address,
+    donor: &Address,
+    amount: i128,
+    cliff_secs: u64,
+    duration_secs: u64,
+) -> (u64, Address) {
+    let token_id = deploy_token_with_balance(env, admin, admin, amount);
+    let id = client.create_vesting(
+        admin,
+        donor,
+        &token_id,
+        &amount,
+        &cliff_secs,
+        &duration_secs,
+    );
+    (id, token_id)
+}
+
+/// Pre-cliff claim must return CliffNotReached.
+#[test]
+fn test_vesting_pre_cliff_claim_fails() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+
+    // cliff = now + 1000s, duration = 2000s
+    env.ledger().with_mut(|l| l.timestamp = 5000);
+    let (id, _) = funded_vesting(&env, &client, &admin, &donor, 1_000_000, 1000, 2000);
+
+    // Try to claim at t=5500 (before cliff at t=6000)
+    env.ledger().with_mut(|l| l.timestamp = 5500);
+    let result = client.try_claim_vested(&donor, &id);
+    assert_eq!(
+        result,
+        Err(Ok(Error::CliffNotReached)),
+        "Expected CliffNotReached before cliff"
+    );
+}
+
+/// At 50% of vesting duration, claimable = total/2.
+#[test]
+fn test_vesting_partial_claim_at_50_percent() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+
+    // cliff = now + 0 (immediate), duration = 2000s → vest_end = now + 2000
+    env.ledger().with_mut(|l| l.timestamp = 10_000);
+    let (id, _) = funded_vesting(&env, &client, &admin, &donor, 1_000_000, 0, 2000);
+
+    // Advance to 50% of vesting duration (cliff == vest_start == 10_000, vest_end == 12_000)
+    env.ledger().with_mut(|l| l.timestamp = 11_000); // 1000s elapsed of 2000s
+    let claimed = client.claim_vested(&donor, &id);
+    assert_eq!(claimed, 500_000i128, "50% vesting should yield half the total");
+
+    let schedule = client.get_vesting(&id);
+    assert_eq!(schedule.claimed, 500_000i128);
+}
+
+/// After vesting end, donor can claim the full remaining amount.
+#[test]
+fn test_vesting_full_claim_after_vest_end() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (id, token_id) = funded_vesting(&env, &client, &admin, &donor, 500_000, 0, 1000);
+
+    // Advance past vest_end
+    env.ledger().with_mut(|l| l.timestamp = 3_000);
+    let claimed = client.claim_vested(&donor, &id);
+    assert_eq!(claimed, 500_000i128, "Full amount claimable after vest end");
+
+    let schedule = client.get_vesting(&id);
+    assert_eq!(schedule.claimed, 500_000i128);
+    assert_eq!(schedule.claimed, schedule.total_amount);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token_id).balance(&donor),
+        500_000
+    );
+}
+
+/// Donor cannot claim more than total_amount across multiple claims.
+#[test]
+fn test_vesting_cannot_exceed_total_amount() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (id, _) = funded_vesting(&env, &client, &admin, &donor, 1_000_000, 0, 1000);
+
+    // Claim full amount after vest end
+    env.ledger().with_mut(|l| l.timestamp = 5_000);
+    let first = client.claim_vested(&donor, &id);
+    assert_eq!(first, 1_000_000i128);
+
+    // Second claim should fail with NothingToClaim
+    let result = client.try_claim_vested(&donor, &id);
+    assert_eq!(
+        result,
+        Err(Ok(Error::NothingToClaim)),
+        "Second claim after full vest should fail"
+    );
+}
+
+/// Non-admin cannot create a vesting schedule.
+#[test]
+fn test_vesting_only_admin_can_create() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let attacker = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &attacker, 1_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let result =
+        client.try_create_vesting(&attacker, &donor, &token_id, &1_000i128, &100u64, &500u64);
+    assert!(result.is_err(), "Non-admin must not create vesting");
+}
+
+/// Creating a schedule pulls the reward tokens into the contract.
+#[test]
+fn test_vesting_is_funded_on_creation() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+    let (id, token_id) = funded_vesting(&env, &client, &admin, &donor, 1_000, 0, 1000);
+
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&cid), 1_000);
+    assert_eq!(token.balance(&admin), 0);
+    assert_eq!(client.get_vesting(&id).token, token_id);
+
+    // The admin cannot promise more than they hold.
+    assert!(client
+        .try_create_vesting(&admin, &donor, &token_id, &1i128, &0u64, &1000u64)
+        .is_err());
+}
+
+/// A second reward adds a schedule instead of replacing the first.
+#[test]
+fn test_donor_can_hold_several_schedules() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (first, _) = funded_vesting(&env, &client, &admin, &donor, 1_000, 0, 1000);
+    let (second, _) = funded_vesting(&env, &client, &admin, &donor, 4_000, 0, 4000);
+    assert_ne!(first, second);
+
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    assert_eq!(client.claim_vested(&donor, &first), 1_000);
+    assert_eq!(client.claim_vested(&donor, &second), 1_000);
+
+    let schedules = client.get_donor_vestings(&donor);
+    assert_eq!(schedules.len(), 2);
+    assert_eq!(schedules.get(1).unwrap().total_amount, 4_000);
+
+    // Another donor cannot claim someone else's schedule.
+    assert_eq!(
+        client.try_claim_vested(&Address::generate(&env), &first),
+        Err(Ok(Error::VestingNotFound))
+    );
+}
+
+/// Revocation returns the unvested part and leaves the vested part claimable.
+#[test]
+fn test_revoke_vesting_returns_unvested_tokens() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (id, token_id) = funded_vesting(&env, &client, &admin, &donor, 1_000, 0, 1000);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_250);
+    assert_eq!(client.claim_vested(&donor, &id), 250);
+    env.ledger().with_mut(|l| l.timestamp = 1_400);
+    assert_eq!(
+        client.try_revoke_vesting(&donor, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.revoke_vesting(&admin, &id), 600);
+    assert_eq!(token.balance(&admin), 600);
+    assert_eq!(
+        client.try_revoke_vesting(&admin, &id),
+        Err(Ok(Error::VestingRevoked))
+    );
+
+    // Nothing vests after revocation.
+    env.ledger().with_mut(|l| l.timestamp = 5_000);
+    assert_eq!(client.claim_vested(&donor, &id), 150);
+    assert_eq!(
+        client.try_claim_vested(&donor, &id),
+        Err(Ok(Error::NothingToClaim))
+    );
+    let schedule = client.get_vesting(&id);
+    assert!(schedule.revoked);
+    assert_eq!(schedule.total_amount, 400);
+    assert_eq!(token.balance(&cid), 0);
+}
+
+// ── process_expired_disputes (#595) ─────────────────────────────────────────────────
+
+#[test]
+fn test_process_expired_disputes_refunds_after_timeout() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+
+    // Record dispute at t=1000; updated_at becomes 1000.
+    client.record_dispute(
+        &admin,
+        &pid,
+        &DisputeReason::FailedDelivery,
+        &soroban_sdk::String::from_str(&env, "case-1"),
+    );
+
+    // Set a short timeout of 500s.
+    client.set_dispute_timeout(&admin, &500u64);
+
+    // Advance time past timeout.
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+
+    let mut ids = soroban_sdk::Vec::new(&env);
+    ids.push_back(pid);
+    let refunded = client.process_expired_disputes(&admin, &ids);
+    assert_eq!(refunded.len(), 1);
+    assert_eq!(refunded.get(0).unwrap(), pid);
+
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Refunded);
+}
+
+#[test]
+fn test_process_expired_disputes_skips_non_expired() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let pid = client.create_escrow(&2u64, &hospital, &payee, &500i128, &token_id);
+    client.record_dispute(
+        &admin,
+        &pid,
+        &DisputeReason::Other,
+        &soroban_sdk::String::from_str(&env, "case-2"),
+    );
+
+    client.set_dispute_timeout(&admin, &5_000u64);
+
+    // Only 100s elapsed — not expired.
+    env.ledger().with_mut(|l| l.timestamp = 1_100);
+
+    let mut ids = soroban_sdk::Vec::new(&env);
+    ids.push_back(pid);
+    let refunded = client.process_expired_disputes(&admin, &ids);
+    assert_eq!(refunded.len(), 0);
+
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Disputed);
+}
+
+#[test]
+fn test_process_expired_disputes_skips_non_disputed_payments() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (pid, _, _) = make_payment(&env, &client, 3, 200);
+    // Payment is Pending, not Disputed.
+    client.set_dispute_timeout(&admin, &1u64);
+    env.ledger().with_mut(|l| l.timestamp = 9_000);
+
+    let mut ids = soroban_sdk::Vec::new(&env);
+    ids.push_back(pid);
+    let refunded = client.process_expired_disputes(&admin, &ids);
+    assert_eq!(refunded.len(), 0);
+}
+
+/// VestingCreated and VestingClaimed events are emitted.
+#[test]
+fn test_vesting_events_emitted() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let donor = Address::generate(&env);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (id, _) = funded_vesting(&env, &client, &admin, &donor, 200_000, 0, 1000);
+
+    env.ledger().with_mut(|l| l.timestamp = 2_500); // past vest_end
+    client.claim_vested(&donor, &id);
+
+    // Events are published — verify no panic and schedule is updated
+    let schedule = client.get_vesting(&id);
+    assert_eq!(schedule.claimed, 200_000i128);
+}
+
+// ── Coordinator settlement ─────────────────────────────────────────────────────
+
+#[test]
+fn test_coordinator_can_release_escrow() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let coordinator = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+
+    // Not yet registered as a settler.
+    assert_eq!(
+        client.try_release_escrow(&coordinator, &pid),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_coordinator(&admin, &coordinator);
+    assert_eq!(client.get_coordinator(), Some(coordinator.clone()));
+    client.release_escrow(&coordinator, &pid);
+
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&payee), 1_000);
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Released);
+    assert_eq!(p.released_amount, 1_000);
+}
+
+#[test]
+fn test_release_partial_splits_escrow() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+
+    client.release_partial(&admin, &pid, &600i128);
+
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&payee), 600);
+    assert_eq!(token.balance(&hospital), 9_400);
+    assert_eq!(token.balance(&cid), 0);
+
+    let p = client.get_payment(&pid);
+    assert_eq!(p.status, PaymentStatus::Released);
+    assert_eq!(p.released_amount, 600);
+
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.total_locked, 0);
+    assert_eq!(stats.total_released, 600);
+    assert_eq!(stats.total_refunded, 400);
+    assert_eq!(stats.count_released, 1);
+}
+
+#[test]
+fn test_release_partial_rejects_invalid_amount() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+
+    assert_eq!(
+        client.try_release_partial(&admin, &pid, &0i128),
+        Err(Ok(Error::InvalidSettlementAmount))
+    );
+    assert_eq!(
+        client.try_release_partial(&admin, &pid, &1_001i128),
+        Err(Ok(Error::InvalidSettlementAmount))
+    );
+}
+
+// ── Fees ───────────────────────────────────────────────────────────────────────
+
+fn fee_policy(env: &Env) -> FeePolicy {
+    FeePolicy {
+        treasury: Address::generate(env),
+        platform_fee_bps: 250,
+        fixed_fee: 10,
+        critical_surcharge_bps: 200,
+        urgent_surcharge_bps: 100,
+    }
+}
+
+#[test]
+fn test_release_escrow_without_fee_policy_pays_in_full() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let pid = client.create_escrow(&1, &hospital, &bank, &1_000, &token_id);
+
+    assert_eq!(client.quote_fee(&pid), FeeBreakdown::none());
+    client.release_escrow(&admin, &pid);
+
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.fee, FeeBreakdown::none());
+    assert_eq!(payment.released_amount, 1_000);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token_id).balance(&bank),
+        1_000
+    );
+}
+
+#[test]
+fn test_release_escrow_routes_fees_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let requests = env.register(MockRequestContract, ());
+    let hospital = Address::generate(&env);
+    MockRequestContractClient::new(&env, &requests).seed_request(
+        &1,
+        &hospital,
+        &ReqStatus::Approved,
+    );
+    let client = PaymentContractClient::new(&env, &env.register(PaymentContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &Some(requests));
+    let policy = fee_policy(&env);
+    client.set_fee_policy(&admin, &policy);
+
+    let bank = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let pid = client.create_escrow(&1, &hospital, &bank, &1_000, &token_id);
+    client.release_escrow(&admin, &pid);
+
+    // 2.5% platform fee, the fixed fee and 1% for an Urgent request.
+    let expected = FeeBreakdown {
+        platform_fee: 25,
+        fixed_fee: 10,
+        urgency_surcharge: 10,
+        total: 45,
+        treasury: Some(policy.treasury.clone()),
+    };
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.fee, expected);
+    assert_eq!(payment.released_amount, 1_000);
+
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&bank), 955);
+    assert_eq!(token.balance(&policy.treasury), 45);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_release_partial_charges_fees_on_payee_share() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let policy = fee_policy(&env);
+    client.set_fee_policy(&admin, &policy);
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let pid = client.create_escrow(&1, &hospital, &bank, &1_000, &token_id);
+
+    client.release_partial(&admin, &pid, &600);
+
+    // 2.5% of 600 plus the fixed fee; the refunded 400 is not charged.
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.fee.total, 25);
+    assert_eq!(payment.released_amount, 600);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&bank), 575);
+    assert_eq!(token.balance(&policy.treasury), 25);
+    assert_eq!(token.balance(&hospital), 400);
+}
+
+#[test]
+fn test_resolve_dispute_charges_fees_on_payee_share() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let policy = fee_policy(&env);
+    client.set_fee_policy(&admin, &policy);
+    let (pid, hospital, bank, token_id) = disputed_escrow(&env, &client, &admin, 1_000);
+
+    client.resolve_dispute(
+        &admin,
+        &pid,
+        &DisputeOutcome::Split(4_000),
+        &BytesN::from_array(&env, &[3; 32]),
+    );
+
+    // 2.5% of the 400 released plus the fixed fee.
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.fee.total, 20);
+    assert_eq!(payment.released_amount, 400);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&bank), 380);
+    assert_eq!(token.balance(&policy.treasury), 20);
+    assert_eq!(token.balance(&hospital), 600);
+}
+
+#[test]
+fn test_fixed_fee_never_exceeds_escrow() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let mut policy = fee_policy(&env);
+    policy.fixed_fee = 50;
+    client.set_fee_policy(&admin, &policy);
+
+    let hospital = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 40);
+    let pid = client.create_escrow(&1, &hospital, &Address::generate(&env), &40, &token_id);
+
+    // Without a requests contract there is no urgency surcharge.
+    let fee = client.quote_fee(&pid);
+    assert_eq!(fee.platform_fee, 1);
+    assert_eq!(fee.urgency_surcharge, 0);
+    assert_eq!(fee.fixed_fee, 39);
+    assert_eq!(fee.total, 40);
+}
+
+#[test]
+fn test_set_fee_policy_validates_and_requires_admin() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let mut policy = fee_policy(&env);
+    assert_eq!(
+        client.try_set_fee_policy(&Address::generate(&env), &policy),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    policy.platform_fee_bps = 9_900;
+    assert_eq!(
+        client.try_set_fee_policy(&admin, &policy),
+        Err(Ok(Error::InvalidFeePolicy))
+    );
+    policy.platform_fee_bps = u32::MAX;
+    assert_eq!(
+        client.try_set_fee_policy(&admin, &policy),
+        Err(Ok(Error::InvalidFeePolicy))
+    );
+    policy.platform_fee_bps = 250;
+    policy.fixed_fee = -1;
+    assert_eq!(
+        client.try_set_fee_policy(&admin, &policy),
+        Err(Ok(Error::InvalidFeePolicy))
+    );
+
+    policy.fixed_fee = 0;
+    client.set_fee_policy(&admin, &policy);
+    assert_eq!(client.get_fee_policy(), Some(policy));
+    client.clear_fee_policy(&admin);
+    assert_eq!(client.get_fee_policy(), None);
+}
+
+// ── Multi-asset stats ──────────────────────────────────────────────────────────
+
+/// SEP-40 price feed stand-in with 14 decimals and prices set by the test.
+#[soroban_sdk::contract]
+struct MockPriceOracle;
+
+#[soroban_sdk::contractimpl]
+impl MockPriceOracle {
+    pub fn set_price(env: Env, asset: OracleAsset, price: i128, timestamp: u64) {
+        env.storage()
+            .persistent()
+            .set(&asset, &PriceData { price, timestamp });
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
 
-    // Record dispute at t=1000; updated_at becomes 1000.
-    client.record_dispute(&pid, &DisputeReason::FailedDelivery,
-        &soroban_sdk::String::from_str(&env, "case-1"));
+    pub fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData> {
+        env.storage().persistent().get(&asset)
+    }
+}
 
-    // Set a short timeout of 500s.
-    client.set_dispute_timeout(&admin, &500u64);
+/// One whole unit of a token with 7 decimals.
+const UNIT: i128 = 10_000_000;
 
-    // Advance time past timeout.
-    env.ledger().with_mut(|l| l.timestamp = 2_000);
+/// One whole unit of the oracle's reference currency.
+const ORACLE_UNIT: i128 = 100_000_000_000_000;
 
-    let mut ids = soroban_sdk::Vec::new(&env);
-    ids.push_back(pid);
-    let refunded = client.process_expired_disputes(&admin, &ids);
-    assert_eq!(refunded.len(), 1);
-    assert_eq!(refunded.get(0).unwrap(), pid);
+#[test]
+fn test_stats_are_kept_per_token() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let usdc = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let xlm = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
 
-    let p = client.get_payment(&pid);
-    assert_eq!(p.status, PaymentStatus::Refunded);
+    client.create_escrow(&1, &hospital, &bank, &300, &usdc);
+    let pid = client.create_escrow(&2, &hospital, &bank, &700, &xlm);
+    client.release_escrow(&admin, &pid);
+
+    assert_eq!(
+        client.get_stats_tokens(),
+        soroban_sdk::vec![&env, usdc.clone(), xlm.clone()]
+    );
+    let usdc_stats = client.get_token_statistics(&usdc);
+    assert_eq!(usdc_stats.total_locked, 300);
+    assert_eq!(usdc_stats.total_released, 0);
+    let xlm_stats = client.get_token_statistics(&xlm);
+    assert_eq!(xlm_stats.total_locked, 0);
+    assert_eq!(xlm_stats.total_released, 700);
+    assert_eq!(xlm_stats.count_released, 1);
+
+    // Contract-wide stats add the two tokens' raw units up.
+    let stats = client.get_payment_statistics();
+    assert_eq!(stats.total_locked, 300);
+    assert_eq!(stats.total_released, 700);
 }
 
 #[test]
-fn test_process_expired_disputes_skips_non_expired() {
+fn test_token_allowlist_gates_escrows_and_pledges() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let usdc = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let other = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+
+    assert_eq!(client.get_allowed_tokens(), None);
+    assert!(client.is_token_allowed(&other));
+
+    client.allow_token(&admin, &usdc);
+    assert!(!client.is_token_allowed(&other));
+    assert_eq!(
+        client.try_create_escrow(&1, &hospital, &bank, &100, &other),
+        Err(Ok(Error::TokenNotAllowed))
+    );
+    let name = soroban_sdk::String::from_str(&env, "pool");
+    assert_eq!(
+        client.try_create_pledge(&hospital, &other, &10, &MONTH, &name, &name, &name, &false),
+        Err(Ok(Error::TokenNotAllowed))
+    );
+    client.create_escrow(&1, &hospital, &bank, &100, &usdc);
+
+    client.disallow_token(&admin, &usdc);
+    assert_eq!(
+        client.get_allowed_tokens(),
+        Some(soroban_sdk::Vec::new(&env))
+    );
+    assert_eq!(
+        client.try_create_escrow(&2, &hospital, &bank, &100, &usdc),
+        Err(Ok(Error::TokenNotAllowed))
+    );
+    assert_eq!(
+        client.try_allow_token(&hospital, &usdc),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_normalized_statistics_use_fresh_oracle_prices() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    env.ledger().with_mut(|l| l.timestamp = 10_000);
+    assert_eq!(
+        client.try_get_normalized_statistics(),
+        Err(Ok(Error::OracleNotSet))
+    );
+
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let usdc = deploy_token_with_balance(&env, &admin, &hospital, 1_000 * UNIT);
+    let xlm = deploy_token_with_balance(&env, &admin, &hospital, 1_000 * UNIT);
+    let stale = deploy_token_with_balance(&env, &admin, &hospital, 1_000 * UNIT);
+    client.create_escrow(&1, &hospital, &bank, &(100 * UNIT), &usdc);
+    let pid = client.create_escrow(&2, &hospital, &bank, &(400 * UNIT), &xlm);
+    client.release_escrow(&admin, &pid);
+    client.create_escrow(&3, &hospital, &bank, &(50 * UNIT), &stale);
+
+    let oracle_id = env.register(MockPriceOracle, ());
+    let oracle = MockPriceOracleClient::new(&env, &oracle_id);
+    oracle.set_price(&OracleAsset::Stellar(usdc.clone()), &ORACLE_UNIT, &9_900);
+    oracle.set_price(
+        &OracleAsset::Stellar(xlm.clone()),
+        &(ORACLE_UNIT / 4),
+        &9_900,
+    );
+    oracle.set_price(&OracleAsset::Stellar(stale.clone()), &ORACLE_UNIT, &1_000);
+    client.set_price_oracle(&admin, &oracle_id, &3_600);
+
+    let normalized = client.get_normalized_statistics();
+    assert_eq!(normalized.decimals, 14);
+    assert_eq!(normalized.total_locked, 100 * ORACLE_UNIT);
+    assert_eq!(normalized.total_released, 100 * ORACLE_UNIT);
+    assert_eq!(normalized.total_refunded, 0);
+    assert_eq!(normalized.unpriced, soroban_sdk::vec![&env, stale]);
+}
+
+#[test]
+fn test_normalized_statistics_reject_overflowing_values() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    env.ledger().with_mut(|l| l.timestamp = 10_000);
+    let hospital = Address::generate(&env);
+    let token = deploy_token_with_balance(&env, &admin, &hospital, 1_000 * UNIT);
+    client.create_escrow(
+        &1,
+        &hospital,
+        &Address::generate(&env),
+        &(100 * UNIT),
+        &token,
+    );
+
+    let oracle_id = env.register(MockPriceOracle, ());
+    let oracle = MockPriceOracleClient::new(&env, &oracle_id);
+    oracle.set_price(&OracleAsset::Stellar(token), &(i128::MAX / 2), &9_900);
+    client.set_price_oracle(&admin, &oracle_id, &3_600);
+
+    assert_eq!(
+        client.try_get_normalized_statistics(),
+        Err(Ok(Error::NormalizationOverflow))
+    );
+}
+
+// ── Payee balances ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_release_credits_payee_who_cannot_receive() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    asset
+        .issuer()
+        .set_flag(soroban_sdk::testutils::IssuerFlags::RevocableFlag);
+    let token_id = asset.address();
+    let asset_admin = soroban_sdk::token::StellarAssetClient::new(&env, &token_id);
+    asset_admin.mint(&hospital, &1_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+
+    // A frozen balance cannot receive; settlement must still go through.
+    asset_admin.set_authorized(&payee, &false);
+    client.release_escrow(&admin, &pid);
+
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(client.get_payment(&pid).status, PaymentStatus::Released);
+    assert_eq!(token.balance(&payee), 0);
+    assert_eq!(client.get_claimable_balance(&payee, &token_id), 1_000);
 
+    let wallet = Address::generate(&env);
+    assert_eq!(client.withdraw(&payee, &token_id, &400, &wallet), 600);
+    assert_eq!(token.balance(&wallet), 400);
+    assert_eq!(
+        client.try_withdraw(&payee, &token_id, &700, &wallet),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_withdraw(&payee, &token_id, &0, &wallet),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(client.withdraw(&payee, &token_id, &600, &wallet), 0);
+    assert_eq!(token.balance(&cid), 0);
+}
+
+#[test]
+fn test_payee_can_turn_auto_push_off() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
     let hospital = Address::generate(&env);
     let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert!(client.get_auto_push(&payee));
+
+    client.set_auto_push(&payee, &false);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+    client.release_partial(&admin, &pid, &700);
+    assert_eq!(token.balance(&payee), 0);
+    assert_eq!(token.balance(&hospital), 300);
+    assert_eq!(client.get_claimable_balance(&payee, &token_id), 700);
+
+    client.set_auto_push(&payee, &true);
+    let pid = client.create_escrow(&2u64, &hospital, &payee, &300i128, &token_id);
+    client.release_escrow(&admin, &pid);
+    assert_eq!(token.balance(&payee), 300);
+    assert_eq!(client.get_claimable_balance(&payee, &token_id), 700);
+}
+
+// ── Invoices ───────────────────────────────────────────────────────────────────
+
+/// Payments wired to a mock requests contract. Requests 1 and 2 are
+/// fulfilled for the returned hospital, 3 is only approved, 4 is fulfilled
+/// for another hospital. The hospital holds 10 000 of the returned token.
+fn invoicing() -> (
+    Env,
+    PaymentContractClient<'static>,
+    Address,
+    Address,
+    Address,
+    Address,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let requests = env.register(MockRequestContract, ());
+    let requests_client = MockRequestContractClient::new(&env, &requests);
+    let hospital = Address::generate(&env);
+    requests_client.seed_request(&1, &hospital, &ReqStatus::Fulfilled);
+    requests_client.seed_request(&2, &hospital, &ReqStatus::Fulfilled);
+    requests_client.seed_request(&3, &hospital, &ReqStatus::Approved);
+    requests_client.seed_request(&4, &Address::generate(&env), &ReqStatus::Fulfilled);
+
+    let client = PaymentContractClient::new(&env, &env.register(PaymentContract, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &Some(requests));
+    let bank = Address::generate(&env);
     let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+    (env, client, admin, bank, hospital, token_id)
+}
+
+fn invoice_line(request_id: u64, units: u32, unit_price: i128) -> InvoiceLine {
+    InvoiceLine {
+        request_id,
+        units,
+        component: BloodComponent::RedCells,
+        unit_price,
+    }
+}
+
+#[test]
+fn test_accepted_invoice_becomes_one_escrow() {
+    let (env, client, admin, bank, hospital, token_id) = invoicing();
+    let lines = soroban_sdk::vec![&env, invoice_line(1, 2, 150), invoice_line(2, 1, 200)];
+    let invoice_id = client.issue_invoice(&bank, &hospital, &token_id, &lines);
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.total, 500);
+    assert_eq!(invoice.status, InvoiceStatus::Issued);
+
+    let pid = client.accept_invoice(&hospital, &invoice_id);
+    let payment = client.get_payment(&pid);
+    assert_eq!(payment.amount, 500);
+    assert_eq!(payment.payer, hospital);
+    assert_eq!(payment.payee, bank);
+    assert_eq!(payment.status, PaymentStatus::Locked);
+    assert_eq!(client.get_payment_by_request(&1).id, pid);
+    assert_eq!(client.get_payment_by_request(&2).id, pid);
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Accepted);
+    assert_eq!(invoice.payment_id, Some(pid));
+
+    assert_eq!(
+        client.try_accept_invoice(&hospital, &invoice_id),
+        Err(Ok(Error::InvoiceNotOpen))
+    );
+    assert_eq!(
+        client.try_issue_invoice(
+            &bank,
+            &hospital,
+            &token_id,
+            &soroban_sdk::vec![&env, invoice_line(2, 1, 200)]
+        ),
+        Err(Ok(Error::DuplicatePayment))
+    );
+
+    client.release_escrow(&admin, &pid);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&bank), 500);
+    assert_eq!(token.balance(&hospital), 9_500);
+}
+
+#[test]
+fn test_invoiced_request_cannot_be_paid_twice() {
+    let (env, client, _admin, bank, hospital, token_id) = invoicing();
+    let lines = soroban_sdk::vec![&env, invoice_line(1, 2, 150), invoice_line(2, 1, 200)];
+    let invoice_id = client.issue_invoice(&bank, &hospital, &token_id, &lines);
+
+    assert_eq!(
+        client.try_create_payment(&2u64, &hospital, &bank, &200i128),
+        Err(Ok(Error::RequestAlreadyInvoiced))
+    );
+
+    // A payment recorded against request 2 after the invoice was issued,
+    // as builds without the check above allowed.
+    env.as_contract(&client.address, || index_by_request(&env, 2, 99));
+    assert_eq!(
+        client.try_accept_invoice(&hospital, &invoice_id),
+        Err(Ok(Error::DuplicatePayment))
+    );
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Issued
+    );
+}
+
+#[test]
+fn test_issue_invoice_validation_and_cancel() {
+    let (env, client, _admin, bank, hospital, token_id) = invoicing();
+    let issue = |lines: soroban_sdk::Vec<InvoiceLine>| {
+        client.try_issue_invoice(&bank, &hospital, &token_id, &lines)
+    };
+
+    assert_eq!(
+        issue(soroban_sdk::Vec::new(&env)),
+        Err(Ok(Error::InvalidInvoice))
+    );
+    assert_eq!(
+        issue(soroban_sdk::vec![&env, invoice_line(1, 0, 150)]),
+        Err(Ok(Error::InvalidInvoice))
+    );
+    assert_eq!(
+        issue(soroban_sdk::vec![
+            &env,
+            invoice_line(1, 1, 150),
+            invoice_line(1, 2, 150)
+        ]),
+        Err(Ok(Error::InvalidInvoice))
+    );
+    assert_eq!(
+        issue(soroban_sdk::vec![&env, invoice_line(3, 1, 150)]),
+        Err(Ok(Error::RequestNotDelivered))
+    );
+    assert_eq!(
+        issue(soroban_sdk::vec![&env, invoice_line(4, 1, 150)]),
+        Err(Ok(Error::InvalidInvoice))
+    );
+    assert_eq!(
+        issue(soroban_sdk::vec![&env, invoice_line(99, 1, 150)]),
+        Err(Ok(Error::RequestNotFound))
+    );
+
+    let invoice_id = client.issue_invoice(
+        &bank,
+        &hospital,
+        &token_id,
+        &soroban_sdk::vec![&env, invoice_line(1, 1, 150)],
+    );
+    assert_eq!(
+        issue(soroban_sdk::vec![&env, invoice_line(1, 1, 100)]),
+        Err(Ok(Error::RequestAlreadyInvoiced))
+    );
+    assert_eq!(
+        client.try_accept_invoice(&bank, &invoice_id),
+        Err(Ok(Error::InvoiceNotFound))
+    );
+    assert_eq!(
+        client.try_cancel_invoice(&Address::generate(&env), &invoice_id),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.cancel_invoice(&hospital, &invoice_id);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Cancelled
+    );
+    assert_eq!(
+        client.try_accept_invoice(&hospital, &invoice_id),
+        Err(Ok(Error::InvoiceNotOpen))
+    );
+    // The request is free to be billed again.
+    client.issue_invoice(
+        &bank,
+        &hospital,
+        &token_id,
+        &soroban_sdk::vec![&env, invoice_line(1, 1, 100)],
+    );
+}
+
+#[test]
+fn test_issue_invoice_requires_verified_bank() {
+    let (env, client, admin, bank, hospital, token_id) = invoicing();
+    let identity_id = env.register(identity_contract::IdentityContract, ());
+    let identity = identity_contract::IdentityContractClient::new(&env, &identity_id);
+    identity.initialize(&admin);
+    let registry_id = env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+    registry.register_service(&Symbol::new(&env, "identity"), &identity_id);
+    client.set_registry(&admin, &registry_id);
+
+    let lines = soroban_sdk::vec![&env, invoice_line(1, 1, 150)];
+    let register = |owner: &Address, org_type: OrgType, license: &str| {
+        identity.register_organization(
+            owner,
+            &org_type,
+            &soroban_sdk::String::from_str(&env, "Org"),
+            &soroban_sdk::String::from_str(&env, license),
+            &soroban_sdk::BytesN::from_array(&env, &[1; 32]),
+            &soroban_sdk::Vec::new(&env),
+        )
+    };
+    assert_eq!(
+        client.try_issue_invoice(&bank, &hospital, &token_id, &lines),
+        Err(Ok(Error::BankNotVerified))
+    );
+    register(&bank, OrgType::BloodBank, "LIC-BANK");
+    assert_eq!(
+        client.try_issue_invoice(&bank, &hospital, &token_id, &lines),
+        Err(Ok(Error::BankNotVerified))
+    );
+
+    // A verified organization that is not a blood bank cannot bill either.
+    let clinic = Address::generate(&env);
+    register(&clinic, OrgType::Hospital, "LIC-CLINIC");
+    identity.verify_organization(&admin, &clinic);
+    assert_eq!(
+        client.try_issue_invoice(&clinic, &hospital, &token_id, &lines),
+        Err(Ok(Error::BankNotVerified))
+    );
+
+    identity.verify_organization(&admin, &bank);
+    let invoice_id = client.issue_invoice(&bank, &hospital, &token_id, &lines);
+    assert_eq!(client.get_invoice(&invoice_id).bank, bank);
+}
+
+#[test]
+fn test_statement_summarises_period() {
+    let (env, client, admin, bank, hospital, token_id) = invoicing();
 
     env.ledger().with_mut(|l| l.timestamp = 1_000);
-    let pid = client.create_escrow(&2u64, &hospital, &payee, &500i128, &token_id);
-    client.record_dispute(&pid, &DisputeReason::Other,
-        &soroban_sdk::String::from_str(&env, "case-2"));
+    let first = client.issue_invoice(
+        &bank,
+        &hospital,
+        &token_id,
+        &soroban_sdk::vec![&env, invoice_line(1, 2, 150)],
+    );
+    client.accept_invoice(&hospital, &first);
 
-    client.set_dispute_timeout(&admin, &5_000u64);
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    let pid = client.create_escrow(&3u64, &hospital, &bank, &300i128, &token_id);
+    client.record_dispute(
+        &hospital,
+        &pid,
+        &DisputeReason::WrongItem,
+        &soroban_sdk::String::from_str(&env, "case-3"),
+    );
+    client.resolve_dispute(
+        &admin,
+        &pid,
+        &DisputeOutcome::Refund,
+        &BytesN::from_array(&env, &[3; 32]),
+    );
 
-    // Only 100s elapsed — not expired.
-    env.ledger().with_mut(|l| l.timestamp = 1_100);
+    env.ledger().with_mut(|l| l.timestamp = 3_000);
+    client.issue_invoice(
+        &bank,
+        &hospital,
+        &token_id,
+        &soroban_sdk::vec![&env, invoice_line(2, 1, 200)],
+    );
 
-    let mut ids = soroban_sdk::Vec::new(&env);
-    ids.push_back(pid);
-    let refunded = client.process_expired_disputes(&admin, &ids);
-    assert_eq!(refunded.len(), 0);
+    let statement = client.get_statement(&hospital, &1_000, &3_000);
+    assert_eq!(statement.invoices_issued, 2);
+    assert_eq!(statement.invoices_accepted, 1);
+    assert_eq!(statement.invoices_open, 1);
+    assert_eq!(statement.payments, 2);
+    assert_eq!(statement.refunds, 1);
+    assert_eq!(statement.disputes, 1);
+    assert_eq!(statement.open_disputes, 0);
+    assert_eq!(
+        statement.totals,
+        soroban_sdk::vec![
+            &env,
+            StatementTotals {
+                token: Some(token_id.clone()),
+                invoiced: 500,
+                paid: 600,
+                released: 0,
+                refunded: 300,
+                fees: 0,
+            }
+        ]
+    );
 
-    let p = client.get_payment(&pid);
-    assert_eq!(p.status, PaymentStatus::Disputed);
+    let middle = client.get_statement(&hospital, &1_500, &2_500);
+    assert_eq!(middle.invoices_issued, 0);
+    assert_eq!(middle.payments, 1);
+    assert_eq!(middle.totals.get(0).unwrap().paid, 300);
+
+    assert_eq!(
+        client.try_get_statement(&hospital, &3_000, &1_000),
+        Err(Ok(Error::InvalidPeriod))
+    );
 }
 
 #[test]
-fn test_process_expired_disputes_skips_non_disputed_payments() {
+fn test_settler_coordinator_follows_registry_rotation() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
+    let old = Address::generate(&env);
+    client.set_coordinator(&admin, &old);
+
+    let registry_id = env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+    let name = Symbol::new(&env, "coordinator");
+    registry.register_service(&name, &old);
+    assert_eq!(
+        client.try_set_registry(&Address::generate(&env), &registry_id),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_registry(&admin, &registry_id);
 
-    env.ledger().with_mut(|l| l.timestamp = 1_000);
-    let (pid, _, _) = make_payment(&env, &client, 3, 200);
-    // Payment is Pending, not Disputed.
-    client.set_dispute_timeout(&admin, &1u64);
-    env.ledger().with_mut(|l| l.timestamp = 9_000);
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let pid = client.create_escrow(&1u64, &hospital, &bank, &1_000i128, &token_id);
 
-    let mut ids = soroban_sdk::Vec::new(&env);
-    ids.push_back(pid);
-    let refunded = client.process_expired_disputes(&admin, &ids);
-    assert_eq!(refunded.len(), 0);
+    let new = Address::generate(&env);
+    registry.rotate_service(&name, &new);
+    assert_eq!(client.get_coordinator(), Some(new.clone()));
+    assert_eq!(
+        client.try_release_escrow(&old, &pid),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.release_escrow(&new, &pid);
+    assert_eq!(client.get_payment(&pid).status, PaymentStatus::Released);
 }
 
-/// VestingCreated and VestingClaimed events are emitted.
+// ── Upgrades ──────────────────────────────────────────────────────────────────
+
 #[test]
-fn test_vesting_events_emitted() {
+fn test_upgrade_keeps_locked_escrow() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+
+    let hospital = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 10_000);
+    let pid = client.create_escrow(&1u64, &hospital, &payee, &1_000i128, &token_id);
+    assert_eq!(client.schema_version(), 3);
+
+    let hash = upload_placeholder_wasm(&env);
+    client.upgrade(&admin, &hash);
+    env.register_at(&cid, PaymentContract, ());
+
+    assert!(client.migrate(&admin, &3));
+    assert_eq!(client.schema_version(), 3);
+    assert_eq!(client.get_payment(&pid).status, PaymentStatus::Locked);
+    assert_eq!(client.get_payment_by_request(&1u64).id, pid);
+
+    client.release_escrow(&admin, &pid);
+    let token = soroban_sdk::token::Client::new(&env, &token_id);
+    assert_eq!(token.balance(&payee), 1_000);
+    assert_eq!(token.balance(&cid), 0);
+}
+
+/// `Payment` as deployments before schema versioning stored it, written out
+/// here rather than borrowed from the contract so the test cannot drift
+/// along with `PaymentV1`.
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BaselinePayment {
+    id: u64,
+    request_id: u64,
+    payer: Address,
+    payee: Address,
+    amount: i128,
+    status: PaymentStatus,
+    created_at: u64,
+    updated_at: u64,
+    dispute_reason_code: Option<u32>,
+    dispute_case_id: Option<soroban_sdk::String>,
+    dispute_resolved: bool,
+    token: Option<Address>,
+}
+
+/// Rewind storage to the version 1 layout: no schema version and payments
+/// without a released amount or fee breakdown.
+fn rewind_to_schema_v1(env: &Env, contract_id: &Address, last_id: u64) {
+    env.as_contract(contract_id, || {
+        env.storage().instance().remove(&SCHEMA_KEY);
+        for id in 1..=last_id {
+            let p: Payment = env.storage().persistent().get(&payment_key(id)).unwrap();
+            let old = BaselinePayment {
+                id: p.id,
+                request_id: p.request_id,
+                payer: p.payer,
+                payee: p.payee,
+                amount: p.amount,
+                status: p.status,
+                created_at: p.created_at,
+                updated_at: p.updated_at,
+                dispute_reason_code: p.dispute_reason_code,
+                dispute_case_id: p.dispute_case_id,
+                dispute_resolved: p.dispute_resolved,
+                token: p.token,
+            };
+            env.storage().persistent().set(&payment_key(id), &old);
+        }
+    });
+}
+
+fn has_fee_field(env: &Env, contract_id: &Address, id: u64) -> bool {
+    env.as_contract(contract_id, || {
+        env.storage()
+            .persistent()
+            .get::<_, Map<Symbol, Val>>(&payment_key(id))
+            .unwrap()
+            .contains_key(soroban_sdk::symbol_short!("fee"))
+    })
+}
+
+#[test]
+fn test_upgrade_then_migrate_backfills_payment_fees() {
+    let (env, cid, admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+
+    // 70 payments span two migration batches of 50; the last is an escrow.
+    for request_id in 1..70u64 {
+        make_payment(&env, &client, request_id, 100);
+    }
+    let hospital = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let token_id = deploy_token_with_balance(&env, &admin, &hospital, 1_000);
+    let escrow = client.create_escrow(&70u64, &hospital, &bank, &1_000i128, &token_id);
+    rewind_to_schema_v1(&env, &cid, escrow);
+    assert_eq!(client.schema_version(), 1);
+    assert!(!has_fee_field(&env, &cid, 1));
+
+    let hash = upload_placeholder_wasm(&env);
+    client.upgrade(&admin, &hash);
+    env.register_at(&cid, PaymentContract, ());
+
+    assert_eq!(
+        client.try_migrate(&admin, &0),
+        Err(Ok(Error::SchemaMismatch))
+    );
+
+    // Bounded and resumable: each call covers one batch.
+    assert!(!client.migrate(&admin, &1));
+    assert_eq!(client.schema_version(), 1);
+    assert!(has_fee_field(&env, &cid, 50));
+    assert!(!has_fee_field(&env, &cid, 51));
+    assert!(!client.migrate(&admin, &1));
+    assert_eq!(client.schema_version(), 2);
+    assert!(client.migrate(&admin, &2));
+    assert_eq!(client.schema_version(), 3);
+
+    // Idempotent once current.
+    assert!(client.migrate(&admin, &1));
+    assert!(client.migrate(&admin, &3));
+
+    for id in 1..=escrow {
+        let payment = client.get_payment(&id);
+        assert_eq!(payment.released_amount, 0);
+        assert_eq!(payment.fee, FeeBreakdown::none());
+    }
+    let payment = client.get_payment(&escrow);
+    assert_eq!(payment.status, PaymentStatus::Locked);
+    assert_eq!(payment.amount, 1_000);
+
+    client.release_escrow(&admin, &escrow);
+    assert_eq!(
+        soroban_sdk::token::Client::new(&env, &token_id).balance(&bank),
+        1_000
+    );
+}
+
+#[test]
+fn test_migrate_vesting_rekeys_legacy_schedules() {
     let (env, cid, admin) = setup_with_admin();
     let client = PaymentContractClient::new(&env, &cid);
+    env.ledger().set_timestamp(1_000);
     let donor = Address::generate(&env);
+    let other = Address::generate(&env);
+    let (current_id, token_id) = funded_vesting(&env, &client, &admin, &donor, 500, 100, 1_000);
+
+    // Version 2 storage: schedules under the donor's address, paid from
+    // tokens already held by the contract.
+    env.as_contract(&cid, || {
+        env.storage().instance().set(&SCHEMA_KEY, &2u32);
+        for (who, total) in [(&donor, 1_000i128), (&other, 400)] {
+            env.storage().persistent().set(
+                &(who.clone(), "vest"),
+                &VestingScheduleV1 {
+                    donor: who.clone(),
+                    total_amount: total,
+                    cliff_timestamp: 1_100,
+                    vest_end_timestamp: 2_000,
+                    claimed: 100,
+                },
+            );
+        }
+    });
+    soroban_sdk::token::StellarAssetClient::new(&env, &token_id).mint(&cid, &1_200);
+
+    let hash = upload_placeholder_wasm(&env);
+    client.upgrade(&admin, &hash);
+    env.register_at(&cid, PaymentContract, ());
 
-    env.ledger().with_mut(|l| l.timestamp = 1_000);
-    client.create_vesting(&admin, &donor, &200_000i128, &0u64, &1000u64);
+    let stranger = Address::generate(&env);
+    let donors = soroban_sdk::vec![&env, donor.clone(), other.clone(), stranger.clone()];
+    assert_eq!(
+        client.try_migrate_vesting(&stranger, &token_id, &donors),
+        Err(Ok(Error::Unauthorized))
+    );
+    let mut too_many = Vec::new(&env);
+    for _ in 0..51 {
+        too_many.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        client.try_migrate_vesting(&admin, &token_id, &too_many),
+        Err(Ok(Error::MigrationBatchTooLarge))
+    );
 
-    let token_id = deploy_token_with_balance(&env, &admin, &cid, 200_000);
+    // Donors without a legacy schedule are skipped and repeats are no-ops.
+    assert_eq!(client.migrate_vesting(&admin, &token_id, &donors), 2);
+    assert_eq!(client.migrate_vesting(&admin, &token_id, &donors), 0);
+    assert!(client.migrate(&admin, &2));
+    assert_eq!(client.schema_version(), 3);
+    assert_eq!(
+        client.try_migrate_vesting(&admin, &token_id, &donors),
+        Err(Ok(Error::SchemaMismatch))
+    );
 
-    env.ledger().with_mut(|l| l.timestamp = 2_500); // past vest_end
-    client.claim_vested(&donor, &token_id);
+    let schedules = client.get_donor_vestings(&donor);
+    assert_eq!(schedules.len(), 2);
+    assert_eq!(schedules.get(0).unwrap().id, current_id);
+    let legacy = schedules.get(1).unwrap();
+    assert_eq!(legacy.token, token_id);
+    assert_eq!(legacy.total_amount, 1_000);
+    assert_eq!(legacy.claimed, 100);
+    assert!(!legacy.revoked);
+    assert_eq!(client.get_donor_vestings(&other).len(), 1);
+
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(client.claim_vested(&donor, &legacy.id), 900);
+    assert_eq!(client.claim_vested(&other, &(legacy.id + 1)), 300);
+}
 
-    // Events are published — verify no panic and schedule is updated
-    let schedule = client.get_vesting(&donor);
-    assert_eq!(schedule.claimed, 200_000i128);
+#[test]
+fn test_upgrade_rejects_non_admin() {
+    let (env, cid, _admin) = setup_with_admin();
+    let client = PaymentContractClient::new(&env, &cid);
+    let hash = upload_placeholder_wasm(&env);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(Error::Unauthorized))
+    );
 }
@@ -0,0 +1,16 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RegistryError {
+    AlreadyInitialized = 1000,
+    NotInitialized = 1001,
+    Unauthorized = 1002,
+
+    ServiceNotFound = 1010,
+    ServiceAlreadyRegistered = 1011,
+    AddressUnchanged = 1012,
+
+    SchemaMismatch = 1020,
+}
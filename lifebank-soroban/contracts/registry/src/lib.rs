@@ -0,0 +1,241 @@
+#![no_std]
+
+/// Deployment registry for the lifebank contract suite.
+///
+/// Maps a service name to the address of its current deployment. Contracts
+/// that are pointed at the registry resolve their peers here, so moving a
+/// service to a new address is a single `rotate_service` call. Every address
+/// a service has had is kept for audit.
+///
+/// Service names used by the suite:
+///   `requests`, `inventory`, `payments`, `coordinator`, `reputation`,
+///   `matching`, `temperature`.
+mod error;
+mod types;
+
+#[cfg(test)]
+mod test;
+
+pub use error::RegistryError;
+pub use types::{DataKey, ServiceRecord};
+
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+// ── Storage helpers ───────────────────────────────────────────────────────────
+
+fn get_admin(env: &Env) -> Result<Address, RegistryError> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(RegistryError::NotInitialized)
+}
+
+fn require_admin(env: &Env) -> Result<Address, RegistryError> {
+    let admin = get_admin(env)?;
+    admin.require_auth();
+    Ok(admin)
+}
+
+fn load_service(env: &Env, name: &Symbol) -> Option<ServiceRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Service(name.clone()))
+}
+
+/// Make `record` the current version of `name` and append it to the history.
+fn save_service(env: &Env, name: &Symbol, record: &ServiceRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Service(name.clone()), record);
+
+    let key = DataKey::History(name.clone());
+    let mut history: Vec<ServiceRecord> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    history.push_back(record.clone());
+    env.storage().persistent().set(&key, &history);
+}
+
+// ── Contract ──────────────────────────────────────────────────────────────────
+
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
+
+#[contract]
+pub struct RegistryContract;
+
+#[contractimpl]
+impl RegistryContract {
+    pub fn initialize(env: Env, admin: Address) -> Result<(), RegistryError> {
+        admin.require_auth();
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(RegistryError::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.events().publish(
+            (
+                symbol_short!("registry"),
+                symbol_short!("init"),
+                symbol_short!("v1"),
+            ),
+            admin,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), RegistryError> {
+        admin.require_auth();
+        if admin != get_admin(&env)? {
+            return Err(RegistryError::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("registry"), symbol_short!("upgraded")),
+            new_wasm_hash,
+        );
+        Ok(())
+    }
+
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate service records out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, RegistryError> {
+        admin.require_auth();
+        if admin != get_admin(&env)? {
+            return Err(RegistryError::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(RegistryError::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
+    /// Register a new service at version 1. Admin only.
+    ///
+    /// # Errors
+    /// - `ServiceAlreadyRegistered` - Use `rotate_service` to move it instead
+    pub fn register_service(env: Env, name: Symbol, address: Address) -> Result<(), RegistryError> {
+        require_admin(&env)?;
+        if load_service(&env, &name).is_some() {
+            return Err(RegistryError::ServiceAlreadyRegistered);
+        }
+
+        save_service(
+            &env,
+            &name,
+            &ServiceRecord {
+                address: address.clone(),
+                version: 1,
+                registered_at: env.ledger().timestamp(),
+            },
+        );
+
+        let mut services = Self::list_services(env.clone());
+        services.push_back(name.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Services, &services);
+
+        env.events().publish(
+            (symbol_short!("registry"), symbol_short!("register")),
+            (name, address),
+        );
+        Ok(())
+    }
+
+    /// Point a registered service at a new address and return its new
+    /// version. Admin only. The previous address stays in the history.
+    ///
+    /// # Errors
+    /// - `ServiceNotFound`  - The service was never registered
+    /// - `AddressUnchanged` - `new_addr` is already the current address
+    pub fn rotate_service(env: Env, name: Symbol, new_addr: Address) -> Result<u32, RegistryError> {
+        require_admin(&env)?;
+        let current = load_service(&env, &name).ok_or(RegistryError::ServiceNotFound)?;
+        if current.address == new_addr {
+            return Err(RegistryError::AddressUnchanged);
+        }
+
+        let version = current.version + 1;
+        save_service(
+            &env,
+            &name,
+            &ServiceRecord {
+                address: new_addr.clone(),
+                version,
+                registered_at: env.ledger().timestamp(),
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("registry"), symbol_short!("rotate")),
+            (name, current.address, new_addr, version),
+        );
+        Ok(version)
+    }
+
+    /// Current address of a service.
+    pub fn get_service(env: Env, name: Symbol) -> Result<Address, RegistryError> {
+        load_service(&env, &name)
+            .map(|record| record.address)
+            .ok_or(RegistryError::ServiceNotFound)
+    }
+
+    pub fn get_service_record(env: Env, name: Symbol) -> Result<ServiceRecord, RegistryError> {
+        load_service(&env, &name).ok_or(RegistryError::ServiceNotFound)
+    }
+
+    /// Every version of a service, oldest first; the last is current.
+    pub fn get_service_history(env: Env, name: Symbol) -> Vec<ServiceRecord> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::History(name))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Names of all registered services in registration order.
+    pub fn list_services(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Services)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_admin(env: Env) -> Result<Address, RegistryError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(RegistryError::NotInitialized)
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(test)]
+
+use lifebank_types::testutils::upload_placeholder_wasm;
+use soroban_sdk::{
+    testutils::{Address as _, Events as _, Ledger},
+    Address, Env, Symbol,
+};
+
+use super::{RegistryContract, RegistryContractClient, RegistryError};
+
+fn setup<'a>() -> (Env, Address, RegistryContractClient<'a>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let id = env.register(RegistryContract, ());
+    let client = RegistryContractClient::new(&env, &id);
+    client.initialize(&admin);
+
+    (env, admin, client)
+}
+
+#[test]
+fn test_double_initialize_fails() {
+    let (env, _, client) = setup();
+    let result = client.try_initialize(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(RegistryError::AlreadyInitialized)));
+}
+
+#[test]
+fn test_register_and_resolve_service() {
+    let (env, _, client) = setup();
+    let name = Symbol::new(&env, "inventory");
+    let inventory = Address::generate(&env);
+
+    client.register_service(&name, &inventory);
+
+    assert_eq!(client.get_service(&name), inventory);
+    let record = client.get_service_record(&name);
+    assert_eq!(record.version, 1);
+    assert_eq!(client.list_services().len(), 1);
+
+    let result = client.try_register_service(&name, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(RegistryError::ServiceAlreadyRegistered)));
+}
+
+#[test]
+fn test_unknown_service_not_found() {
+    let (env, _, client) = setup();
+    let name = Symbol::new(&env, "matching");
+    assert_eq!(
+        client.try_get_service(&name),
+        Err(Ok(RegistryError::ServiceNotFound))
+    );
+    assert_eq!(
+        client.try_rotate_service(&name, &Address::generate(&env)),
+        Err(Ok(RegistryError::ServiceNotFound))
+    );
+    assert!(client.get_service_history(&name).is_empty());
+}
+
+#[test]
+fn test_rotate_service_bumps_version_and_keeps_history() {
+    let (env, _, client) = setup();
+    let name = Symbol::new(&env, "coordinator");
+    let v1 = Address::generate(&env);
+    let v2 = Address::generate(&env);
+    client.register_service(&name, &v1);
+
+    env.ledger().set_timestamp(5_000);
+    let version = client.rotate_service(&name, &v2);
+    assert_eq!(env.events().all().len(), 1);
+
+    assert_eq!(version, 2);
+    assert_eq!(client.get_service(&name), v2);
+    let history = client.get_service_history(&name);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().address, v1);
+    assert_eq!(history.get(1).unwrap().address, v2);
+    assert_eq!(history.get(1).unwrap().registered_at, 5_000);
+
+    let result = client.try_rotate_service(&name, &v2);
+    assert_eq!(result, Err(Ok(RegistryError::AddressUnchanged)));
+}
+
+#[test]
+#[should_panic]
+fn test_rotate_service_requires_admin() {
+    let env = Env::default();
+    let id = env.register(RegistryContract, ());
+    let client = RegistryContractClient::new(&env, &id);
+    let admin = Address::generate(&env);
+    let name = Symbol::new(&env, "payments");
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_service(&name, &Address::generate(&env));
+
+    env.set_auths(&[]);
+    client.rotate_service(&name, &Address::generate(&env));
+}
+
+#[test]
+fn test_upgrade_keeps_services_and_migrate_is_admin_only() {
+    let (env, admin, client) = setup();
+    let name = Symbol::new(&env, "inventory");
+    let inventory = Address::generate(&env);
+    client.register_service(&name, &inventory);
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(RegistryError::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    env.register_at(&client.address, RegistryContract, ());
+
+    assert_eq!(client.get_service(&name), inventory);
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(RegistryError::Unauthorized))
+    );
+    assert!(client.migrate(&admin, &1));
+}
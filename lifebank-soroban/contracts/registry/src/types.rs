@@ -0,0 +1,28 @@
+use soroban_sdk::{contracttype, Address, Symbol};
+
+/// One deployed version of a named service.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServiceRecord {
+    pub address: Address,
+    /// Starts at 1 and increases with every rotation.
+    pub version: u32,
+    /// Ledger timestamp when this version was registered.
+    pub registered_at: u64,
+}
+
+/// Storage keys.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    /// Registry admin — instance storage.
+    Admin,
+    /// Current ServiceRecord of a service — persistent storage.
+    Service(Symbol),
+    /// Every ServiceRecord of a service, oldest first — persistent storage.
+    History(Symbol),
+    /// Names of all registered services — persistent storage.
+    Services,
+    /// Storage schema version — instance storage.
+    SchemaVersion,
+}
@@ -1,6 +1,6 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Vec,
 };
 
 // ── Constants (all arithmetic is integer, scaled ×100 for two decimal places) ──
@@ -157,6 +157,7 @@ pub enum Error {
     AlreadyInitialized = 405,
     NotInitialized = 406,
     ContractPaused = 407,
+    SchemaMismatch = 408,
 }
 
 /// Storage key for persisted reputation scores.
@@ -171,10 +172,16 @@ pub enum DataKey {
     MinimumInteractions,
     BadgeConfig,
     Paused,
+    BreachReporter, // Address allowed to report SLA breaches
+    SchemaVersion,  // u32 storage schema version
 }
 
 // ── Contract ───────────────────────────────────────────────────────────────────
 
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
+
 #[contract]
 pub struct ReputationContract;
 
@@ -219,9 +226,70 @@ impl ReputationContract {
         env.events()
             .publish((symbol_short!("init"), symbol_short!("v1")), admin);
 
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
         Ok(())
     }
 
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
+        if admin != stored {
+            return Err(Error::NotAuthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("upgraded"), symbol_short!("v1")),
+            new_wasm_hash,
+        );
+        Ok(())
+    }
+
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate scores and penalties out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
+        if admin != stored {
+            return Err(Error::NotAuthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(Error::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
     /// Pause all state-mutating functions. Admin only.
     pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
         admin.require_auth();
@@ -464,6 +532,69 @@ impl ReputationContract {
         Self::calculate_reputation(env, entity_id)
     }
 
+    /// Register the contract (normally the coordinator) allowed to report
+    /// SLA breaches. Admin only.
+    pub fn set_breach_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::BreachReporter, &reporter);
+        Ok(())
+    }
+
+    /// Record an SLA breach as a Minor penalty. Only the registered breach
+    /// reporter may call this; entities without history are created.
+    pub fn report_sla_breach(env: Env, reporter: Address, entity_id: u64) -> Result<(), Error> {
+        reporter.require_auth();
+        Self::require_not_paused(&env)?;
+        let stored: Option<Address> = env.storage().instance().get(&DataKey::BreachReporter);
+        if stored != Some(reporter) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let now = env.ledger().timestamp();
+        let mut input: ReputationInput = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Input(entity_id))
+            .unwrap_or(ReputationInput {
+                ratings: Vec::new(&env),
+                total_assigned: 0,
+                total_completed: 0,
+                total_response_secs: 0,
+                response_count: 0,
+                fraud_flags: 0,
+                last_active_at: now,
+                penalties: Vec::new(&env),
+            });
+
+        let id = input.penalties.len();
+        input.penalties.push_back(PenaltyRecord {
+            id,
+            violation_type: ViolationType::Minor,
+            timestamp: now,
+            is_resolved: false,
+            is_appealed: false,
+        });
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Input(entity_id), &input);
+
+        env.events().publish(
+            (symbol_short!("sla"), symbol_short!("breach")),
+            (entity_id, id),
+        );
+
+        Self::calculate_reputation(env, entity_id)?;
+        Ok(())
+    }
+
     /// File an appeal for a specific penalty.
     pub fn appeal_penalty(env: Env, entity_id: u64, penalty_id: u32) -> Result<(), Error> {
         Self::require_not_paused(&env)?;
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use super::*;
+use lifebank_types::testutils::upload_placeholder_wasm;
 use soroban_sdk::{testutils::Ledger as _, testutils::Address as _, testutils::Events as _, Env};
 
 const DAY: u64 = 24 * 3600;
@@ -833,3 +834,52 @@ fn test_reputation_non_admin_cannot_pause() {
     let attacker = Address::generate(&env);
     c.pause(&attacker);
 }
+
+#[test]
+fn test_report_sla_breach_only_from_registered_reporter() {
+    let (env, cid) = setup();
+    let c = client(&env, &cid);
+    let admin = Address::generate(&env);
+    let reporter = Address::generate(&env);
+    c.init(&admin);
+
+    let result = c.try_report_sla_breach(&reporter, &ENTITY);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    c.set_breach_reporter(&reporter);
+    c.report_sla_breach(&reporter, &ENTITY);
+    assert_eq!(c.get_score(&ENTITY).unwrap().penalty_points, PENALTY_MINOR);
+
+    let input = c.get_input(&ENTITY).unwrap();
+    assert_eq!(input.penalties.len(), 1);
+    assert_eq!(
+        input.penalties.get(0).unwrap().violation_type,
+        ViolationType::Minor
+    );
+}
+
+#[test]
+fn test_upgrade_keeps_scores_and_migrate_is_admin_only() {
+    let (env, cid) = setup();
+    let c = client(&env, &cid);
+    let admin = Address::generate(&env);
+    c.initialize(&admin);
+    env.ledger().with_mut(|l| l.timestamp = 1000);
+    c.submit_rating(&ENTITY, &4, &1000);
+    let score = c.get_score(&ENTITY);
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        c.try_upgrade(&stranger, &hash),
+        Err(Ok(Error::NotAuthorized))
+    );
+
+    c.upgrade(&admin, &hash);
+    env.register_at(&cid, ReputationContract, ());
+
+    assert!(score.is_some());
+    assert_eq!(c.get_score(&ENTITY), score);
+    assert_eq!(c.schema_version(), 1);
+    assert_eq!(c.try_migrate(&stranger, &1), Err(Ok(Error::NotAuthorized)));
+    assert!(c.migrate(&admin, &1));
+}
@@ -15,4 +15,6 @@ pub enum ContractError {
     InvalidRequestStatus = 307,
     /// Caller is not the hospital that owns this request.
     NotRequestOwner = 308,
+    /// `migrate` was called with a version other than the stored schema version.
+    SchemaMismatch = 309,
 }
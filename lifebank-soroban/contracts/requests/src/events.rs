@@ -1,5 +1,5 @@
 use crate::types::{BloodRequest, RequestCreatedEvent, RequestStatus};
-use soroban_sdk::{symbol_short, Address, Env, Symbol};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, Symbol, Vec};
 
 pub fn emit_initialized(env: &Env, admin: &Address, inventory_contract: &Address) {
     env.events().publish(
@@ -49,3 +49,23 @@ pub fn emit_request_status_updated(
         (request_id, actor.clone(), old_status, new_status, timestamp),
     );
 }
+
+pub fn emit_units_assigned(
+    env: &Env,
+    request_id: u64,
+    actor: &Address,
+    unit_ids: &Vec<u64>,
+    timestamp: u64,
+) {
+    env.events().publish(
+        (Symbol::new(env, "units_assigned"), symbol_short!("v1")),
+        (request_id, actor.clone(), unit_ids.clone(), timestamp),
+    );
+}
+
+pub fn emit_upgraded(env: &Env, new_wasm_hash: &BytesN<32>) {
+    env.events().publish(
+        (Symbol::new(env, "contract_upgraded"), symbol_short!("v1")),
+        new_wasm_hash.clone(),
+    );
+}
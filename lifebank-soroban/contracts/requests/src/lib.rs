@@ -10,24 +10,18 @@ mod test;
 
 pub use crate::error::ContractError;
 pub use crate::types::{
-    BloodComponent, BloodRequest, BloodType, ContractMetadata, DataKey, RequestCreatedEvent,
-    RequestStatus, Urgency,
+    BloodComponent, BloodRequest, BloodType, ContractMetadata, DataKey, RarePhenotype,
+    RequestCreatedEvent, RequestStatus, Urgency,
 };
 
 mod validation;
 
-use soroban_sdk::{contract, contractimpl, Address, Env};
+use lifebank_types::clients::InventoryContractClient;
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
 
-mod inventory_client {
-    use soroban_sdk::{contractclient, Env};
-
-    #[contractclient(name = "InventoryContractClient")]
-    pub trait InventoryContractInterface {
-        fn release_reservation(env: Env, reservation_id: u64);
-    }
-}
-
-use inventory_client::InventoryContractClient;
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
 
 #[contract]
 pub struct RequestContract;
@@ -54,9 +48,56 @@ impl RequestContract {
 
         events::emit_initialized(&env, &admin, &inventory_contract);
 
+        storage::set_schema_version(&env, SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        storage::require_initialized(&env)?;
+        if admin != storage::get_admin(&env) {
+            return Err(ContractError::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::emit_upgraded(&env, &new_wasm_hash);
         Ok(())
     }
 
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        storage::get_schema_version(&env)
+    }
+
+    /// Migrate blood requests out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, ContractError> {
+        admin.require_auth();
+        storage::require_initialized(&env)?;
+        if admin != storage::get_admin(&env) {
+            return Err(ContractError::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(ContractError::SchemaMismatch);
+        }
+        storage::set_schema_version(&env, SCHEMA_VERSION);
+        Ok(true)
+    }
+
     pub fn authorize_hospital(env: Env, hospital: Address) -> Result<(), ContractError> {
         storage::require_initialized(&env)?;
         storage::get_admin(&env).require_auth();
@@ -71,6 +112,26 @@ impl RequestContract {
         Ok(())
     }
 
+    /// Register the workflow coordinator contract. Admin only.
+    ///
+    /// The coordinator may then move request status and record assigned
+    /// units alongside the admin.
+    pub fn set_coordinator(env: Env, coordinator: Address) -> Result<(), ContractError> {
+        storage::require_initialized(&env)?;
+        storage::get_admin(&env).require_auth();
+        storage::set_coordinator(&env, &coordinator);
+        Ok(())
+    }
+
+    /// Resolve the inventory contract through a deployment registry, falling
+    /// back to the address given at initialization. Admin only.
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), ContractError> {
+        storage::require_initialized(&env)?;
+        storage::get_admin(&env).require_auth();
+        storage::set_registry(&env, &registry);
+        Ok(())
+    }
+
     pub fn create_request(
         env: Env,
         hospital: Address,
@@ -156,6 +217,35 @@ impl RequestContract {
         Ok(ids)
     }
 
+    /// Record that a pending request is for a recipient with a rare red-cell
+    /// phenotype. Only the owning hospital may set it; matching reads it for
+    /// the nationwide rare search.
+    pub fn set_rare_phenotype(
+        env: Env,
+        hospital: Address,
+        request_id: u64,
+        phenotype: RarePhenotype,
+    ) -> Result<(), ContractError> {
+        hospital.require_auth();
+        storage::require_initialized(&env)?;
+
+        let request =
+            storage::get_request(&env, request_id).ok_or(ContractError::RequestNotFound)?;
+        if hospital != request.hospital_id {
+            return Err(ContractError::NotRequestOwner);
+        }
+        if request.status != RequestStatus::Pending {
+            return Err(ContractError::InvalidRequestStatus);
+        }
+
+        storage::set_rare_phenotype(&env, request_id, phenotype);
+        Ok(())
+    }
+
+    pub fn get_rare_phenotype(env: Env, request_id: u64) -> Option<RarePhenotype> {
+        storage::get_rare_phenotype(&env, request_id)
+    }
+
     /// Cancel a blood request. Only the owning hospital or the admin may cancel.
     /// The request must be in Pending or Approved status.
     pub fn cancel_request(
@@ -198,7 +288,7 @@ impl RequestContract {
         Ok(())
     }
 
-    /// Update the status of a blood request. Admin only.
+    /// Update the status of a blood request. Admin or coordinator only.
     /// Records the caller as the actor in the emitted event.
     pub fn update_request_status(
         env: Env,
@@ -209,8 +299,7 @@ impl RequestContract {
         caller.require_auth();
         storage::require_initialized(&env)?;
 
-        let admin = storage::get_admin(&env);
-        if caller != admin {
+        if !storage::is_status_manager(&env, &caller) {
             return Err(ContractError::Unauthorized);
         }
 
@@ -237,6 +326,84 @@ impl RequestContract {
         Ok(())
     }
 
+    /// Record the inventory units allocated to a pending request and move it
+    /// to `Approved`. Admin or coordinator only.
+    pub fn assign_units(
+        env: Env,
+        caller: Address,
+        request_id: u64,
+        unit_ids: Vec<u64>,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        storage::require_initialized(&env)?;
+
+        if !storage::is_status_manager(&env, &caller) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let mut request =
+            storage::get_request(&env, request_id).ok_or(ContractError::RequestNotFound)?;
+
+        if request.status != RequestStatus::Pending {
+            return Err(ContractError::InvalidRequestStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        let old_status = request.status;
+        request.status = RequestStatus::Approved;
+        request.assigned_units = unit_ids.clone();
+        storage::set_request(&env, &request);
+
+        events::emit_units_assigned(&env, request_id, &caller, &unit_ids, now);
+        events::emit_request_status_updated(
+            &env,
+            request_id,
+            &caller,
+            old_status,
+            RequestStatus::Approved,
+            now,
+        );
+
+        Ok(())
+    }
+
+    /// Replace the units recorded on an approved request, for instance when
+    /// a unit is swapped out after a temperature breach. Admin or
+    /// coordinator only.
+    pub fn update_assigned_units(
+        env: Env,
+        caller: Address,
+        request_id: u64,
+        unit_ids: Vec<u64>,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+        storage::require_initialized(&env)?;
+
+        if !storage::is_status_manager(&env, &caller) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let mut request =
+            storage::get_request(&env, request_id).ok_or(ContractError::RequestNotFound)?;
+
+        if request.status != RequestStatus::Approved {
+            return Err(ContractError::InvalidRequestStatus);
+        }
+
+        request.assigned_units = unit_ids.clone();
+        storage::set_request(&env, &request);
+
+        events::emit_units_assigned(
+            &env,
+            request_id,
+            &caller,
+            &unit_ids,
+            env.ledger().timestamp(),
+        );
+
+        Ok(())
+    }
+
     pub fn get_request(env: Env, request_id: u64) -> Result<BloodRequest, ContractError> {
         storage::require_initialized(&env)?;
         storage::get_request(&env, request_id).ok_or(ContractError::RequestNotFound)
@@ -262,6 +429,10 @@ impl RequestContract {
         Ok(storage::get_metadata(&env))
     }
 
+    pub fn get_coordinator(env: Env) -> Option<Address> {
+        storage::get_coordinator(&env)
+    }
+
     pub fn is_hospital_authorized(env: Env, hospital: Address) -> bool {
         storage::is_hospital_authorized(&env, &hospital)
     }
@@ -1,5 +1,6 @@
 use crate::error::ContractError;
-use crate::types::{BloodRequest, ContractMetadata, DataKey};
+use crate::types::{BloodRequest, ContractMetadata, DataKey, RarePhenotype};
+use lifebank_types::registry::resolve_service;
 use soroban_sdk::{Address, Env, String};
 
 pub fn is_initialized(env: &Env) -> bool {
@@ -38,11 +39,58 @@ pub fn set_inventory_contract(env: &Env, inventory_contract: &Address) {
         .set(&DataKey::InventoryContract, inventory_contract);
 }
 
+/// The registry's `inventory` entry when a registry is set, otherwise the
+/// address given at initialization.
 pub fn get_inventory_contract(env: &Env) -> Address {
+    resolve_service(env, get_registry(env), "inventory").unwrap_or_else(|| {
+        env.storage()
+            .instance()
+            .get(&DataKey::InventoryContract)
+            .expect("inventory contract must be set after initialization")
+    })
+}
+
+pub fn set_registry(env: &Env, registry: &Address) {
+    env.storage().instance().set(&DataKey::Registry, registry);
+}
+
+pub fn get_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Registry)
+}
+
+/// Unset on deployments initialized before versioning, which are version 1.
+pub fn get_schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(1)
+}
+
+pub fn set_schema_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::SchemaVersion, &version);
+}
+
+pub fn set_coordinator(env: &Env, coordinator: &Address) {
     env.storage()
         .instance()
-        .get(&DataKey::InventoryContract)
-        .expect("inventory contract must be set after initialization")
+        .set(&DataKey::Coordinator, coordinator);
+}
+
+/// The registry's `coordinator` entry when a registry is set, otherwise the
+/// coordinator set by the admin.
+pub fn get_coordinator(env: &Env) -> Option<Address> {
+    resolve_service(env, get_registry(env), "coordinator")
+        .or_else(|| env.storage().instance().get(&DataKey::Coordinator))
+}
+
+/// The admin and the registered workflow coordinator may drive request status.
+pub fn is_status_manager(env: &Env, caller: &Address) -> bool {
+    if *caller == get_admin(env) {
+        return true;
+    }
+    get_coordinator(env).is_some_and(|c| c == *caller)
 }
 
 pub fn set_request_counter(env: &Env, value: u64) {
@@ -84,6 +132,18 @@ pub fn is_hospital_authorized(env: &Env, hospital: &Address) -> bool {
         .unwrap_or(false)
 }
 
+pub fn set_rare_phenotype(env: &Env, request_id: u64, phenotype: RarePhenotype) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RarePhenotype(request_id), &phenotype);
+}
+
+pub fn get_rare_phenotype(env: &Env, request_id: u64) -> Option<RarePhenotype> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RarePhenotype(request_id))
+}
+
 pub fn set_request(env: &Env, request: &BloodRequest) {
     env.storage()
         .persistent()
@@ -1,11 +1,12 @@
 use crate::storage;
 use crate::{
-    BloodComponent, BloodType, ContractMetadata, RequestContract, RequestContractClient,
-    RequestStatus, Urgency,
+    BloodComponent, BloodType, ContractMetadata, RarePhenotype, RequestContract,
+    RequestContractClient, RequestStatus, Urgency,
 };
+use lifebank_types::testutils::upload_placeholder_wasm;
 use soroban_sdk::{
     testutils::{Address as _, Events as _, Ledger as _},
-    Address, Env, String,
+    vec, Address, Env, String,
 };
 
 fn create_uninitialized_contract<'a>() -> (Env, RequestContractClient<'a>, Address) {
@@ -214,3 +215,197 @@ fn test_create_request_rejects_zero_quantity() {
     );
 }
 
+#[test]
+fn test_coordinator_can_assign_units_and_update_status() {
+    let (env, client, _contract_id, _admin, _inventory_contract) = create_initialized_contract();
+    let hospital = authorize_hospital(&env, &client);
+    let coordinator = Address::generate(&env);
+    client.set_coordinator(&coordinator);
+    assert_eq!(client.get_coordinator(), Some(coordinator.clone()));
+
+    env.ledger().set_timestamp(1_000);
+    let request_id = client.create_request(
+        &hospital,
+        &BloodType::OPositive,
+        &BloodComponent::WholeBlood,
+        &450u32,
+        &Urgency::Urgent,
+        &5_000u64,
+    );
+
+    client.assign_units(&coordinator, &request_id, &vec![&env, 7u64, 8u64]);
+    let request = client.get_request(&request_id);
+    assert_eq!(request.status, RequestStatus::Approved);
+    assert_eq!(request.assigned_units, vec![&env, 7u64, 8u64]);
+
+    client.update_request_status(&coordinator, &request_id, &RequestStatus::Fulfilled);
+    assert_eq!(
+        client.get_request(&request_id).status,
+        RequestStatus::Fulfilled
+    );
+}
+
+#[test]
+fn test_status_manager_follows_registry_coordinator() {
+    let (env, client, _contract_id, admin, _inventory_contract) = create_initialized_contract();
+    let hospital = authorize_hospital(&env, &client);
+    let old = Address::generate(&env);
+    client.set_coordinator(&old);
+
+    let registry_id = env.register(registry_contract::RegistryContract, ());
+    let registry = registry_contract::RegistryContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+    let name = soroban_sdk::Symbol::new(&env, "coordinator");
+    registry.register_service(&name, &old);
+    client.set_registry(&registry_id);
+
+    // Rotating the registry entry moves status management to the new
+    // coordinator without touching this contract.
+    let new = Address::generate(&env);
+    registry.rotate_service(&name, &new);
+    assert_eq!(client.get_coordinator(), Some(new.clone()));
+
+    env.ledger().set_timestamp(1_000);
+    let request_id = client.create_request(
+        &hospital,
+        &BloodType::BNegative,
+        &BloodComponent::RedCells,
+        &300u32,
+        &Urgency::Routine,
+        &5_000u64,
+    );
+    assert_eq!(
+        client.try_assign_units(&old, &request_id, &vec![&env, 1u64]),
+        Err(Ok(crate::ContractError::Unauthorized))
+    );
+    client.assign_units(&new, &request_id, &vec![&env, 1u64]);
+    assert_eq!(
+        client.get_request(&request_id).status,
+        RequestStatus::Approved
+    );
+}
+
+#[test]
+fn test_update_assigned_units_only_while_approved() {
+    let (env, client, _contract_id, admin, _inventory_contract) = create_initialized_contract();
+    let hospital = authorize_hospital(&env, &client);
+
+    env.ledger().set_timestamp(1_000);
+    let request_id = client.create_request(
+        &hospital,
+        &BloodType::ANegative,
+        &BloodComponent::RedCells,
+        &300u32,
+        &Urgency::Routine,
+        &5_000u64,
+    );
+    assert_eq!(
+        client.try_update_assigned_units(&admin, &request_id, &vec![&env, 2u64]),
+        Err(Ok(crate::ContractError::InvalidRequestStatus))
+    );
+
+    client.assign_units(&admin, &request_id, &vec![&env, 1u64, 2u64]);
+    assert_eq!(
+        client.try_update_assigned_units(&Address::generate(&env), &request_id, &vec![&env, 3u64]),
+        Err(Ok(crate::ContractError::Unauthorized))
+    );
+    client.update_assigned_units(&admin, &request_id, &vec![&env, 2u64, 3u64]);
+    assert_eq!(
+        client.get_request(&request_id).assigned_units,
+        vec![&env, 2u64, 3u64]
+    );
+}
+
+#[test]
+fn test_assign_units_rejects_non_pending_and_strangers() {
+    let (env, client, _contract_id, admin, _inventory_contract) = create_initialized_contract();
+    let hospital = authorize_hospital(&env, &client);
+
+    env.ledger().set_timestamp(1_000);
+    let request_id = client.create_request(
+        &hospital,
+        &BloodType::ANegative,
+        &BloodComponent::RedCells,
+        &300u32,
+        &Urgency::Routine,
+        &5_000u64,
+    );
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_assign_units(&stranger, &request_id, &vec![&env, 1u64]),
+        Err(Ok(crate::ContractError::Unauthorized))
+    );
+
+    client.assign_units(&admin, &request_id, &vec![&env, 1u64]);
+    assert_eq!(
+        client.try_assign_units(&admin, &request_id, &vec![&env, 2u64]),
+        Err(Ok(crate::ContractError::InvalidRequestStatus))
+    );
+}
+
+#[test]
+fn test_rare_phenotype_is_set_by_owner_while_pending() {
+    let (env, client, _contract_id, admin, _inventory_contract) = create_initialized_contract();
+    let hospital = authorize_hospital(&env, &client);
+
+    env.ledger().set_timestamp(1_000);
+    let request_id = client.create_request(
+        &hospital,
+        &BloodType::ONegative,
+        &BloodComponent::RedCells,
+        &450u32,
+        &Urgency::Critical,
+        &5_000u64,
+    );
+    assert_eq!(client.get_rare_phenotype(&request_id), None);
+    assert_eq!(
+        client.try_set_rare_phenotype(&admin, &request_id, &RarePhenotype::RhNull),
+        Err(Ok(crate::ContractError::NotRequestOwner))
+    );
+
+    client.set_rare_phenotype(&hospital, &request_id, &RarePhenotype::RhNull);
+    assert_eq!(
+        client.get_rare_phenotype(&request_id),
+        Some(RarePhenotype::RhNull)
+    );
+
+    client.assign_units(&admin, &request_id, &vec![&env, 1u64]);
+    assert_eq!(
+        client.try_set_rare_phenotype(&hospital, &request_id, &RarePhenotype::Bombay),
+        Err(Ok(crate::ContractError::InvalidRequestStatus))
+    );
+}
+
+#[test]
+fn test_upgrade_keeps_requests_and_migrate_is_admin_only() {
+    let (env, client, contract_id, admin, _) = create_initialized_contract();
+    let hospital = authorize_hospital(&env, &client);
+    let request_id = client.create_request(
+        &hospital,
+        &BloodType::ONegative,
+        &BloodComponent::WholeBlood,
+        &450,
+        &Urgency::Urgent,
+        &(env.ledger().timestamp() + 3_600),
+    );
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(crate::ContractError::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    env.register_at(&contract_id, RequestContract, ());
+
+    let request = client.get_request(&request_id);
+    assert_eq!(request.hospital_id, hospital);
+    assert_eq!(request.status, RequestStatus::Pending);
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(crate::ContractError::Unauthorized))
+    );
+    assert!(client.migrate(&admin, &1));
+}
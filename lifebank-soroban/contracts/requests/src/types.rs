@@ -1,4 +1,7 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+pub use lifebank_types::{
+    BloodComponent, BloodRequest, BloodType, RarePhenotype, RequestStatus, Urgency,
+};
+use soroban_sdk::{contracttype, Address, String};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
@@ -10,6 +13,10 @@ pub enum DataKey {
     Metadata,
     AuthorizedHospital(Address),
     Request(u64),
+    Coordinator,
+    Registry,
+    SchemaVersion,
+    RarePhenotype(u64),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -19,76 +26,6 @@ pub struct ContractMetadata {
     pub version: u32,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[contracttype]
-pub enum BloodType {
-    APositive,
-    ANegative,
-    BPositive,
-    BNegative,
-    ABPositive,
-    ABNegative,
-    OPositive,
-    ONegative,
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[contracttype]
-pub enum BloodComponent {
-    WholeBlood,
-    RedCells,
-    Plasma,
-    Platelets,
-    Cryoprecipitate,
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[contracttype]
-pub enum Urgency {
-    Critical,
-    Urgent,
-    Routine,
-    Scheduled,
-}
-
-impl Urgency {
-    pub fn priority(&self) -> u32 {
-        match self {
-            Self::Critical => 4,
-            Self::Urgent => 3,
-            Self::Routine => 2,
-            Self::Scheduled => 1,
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[contracttype]
-pub enum RequestStatus {
-    Pending,
-    Approved,
-    Fulfilled,
-    Cancelled,
-}
-
-#[derive(Clone, Debug, Eq, PartialEq)]
-#[contracttype]
-pub struct BloodRequest {
-    pub id: u64,
-    pub hospital_id: Address,
-    pub blood_type: BloodType,
-    pub component: BloodComponent,
-    pub quantity_ml: u32,
-    pub urgency: Urgency,
-    pub created_timestamp: u64,
-    pub required_by_timestamp: u64,
-    pub status: RequestStatus,
-    pub assigned_units: Vec<u64>,
-    pub fulfilled_quantity_ml: u32,
-    /// Reservation ID on the inventory contract, set when units are reserved.
-    pub reservation_id: Option<u64>,
-}
-
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct RequestCreatedEvent {
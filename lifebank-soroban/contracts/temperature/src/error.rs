@@ -14,4 +14,6 @@ pub enum ContractError {
     CoordinatorNotSet = 606,
     /// Cross-contract call to coordinator failed
     CoordinatorCallFailed = 607,
+    /// `migrate` was called with a version other than the stored schema version
+    SchemaMismatch = 608,
 }
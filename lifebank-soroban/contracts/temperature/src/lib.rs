@@ -4,26 +4,26 @@ mod error;
 mod storage;
 mod types;
 
+#[cfg(test)]
+mod test;
+
 use crate::error::ContractError;
-use crate::types::{DataKey, ExcursionSummary, TemperatureReading, TemperatureSummary, TemperatureThreshold};
-use soroban_sdk::{contract, contractclient, contractimpl, contracttype, Address, Env, Vec};
+use crate::types::{
+    DataKey, ExcursionSummary, TemperatureReading, TemperatureSummary, TemperatureThreshold,
+};
+use lifebank_types::clients::CoordinatorContractClient;
+use lifebank_types::registry::resolve_service;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Vec};
 
 const PAGE_SIZE: u32 = 20;
 
+/// Storage schema version written by this build; bump it alongside a new
+/// step in `migrate`.
+const SCHEMA_VERSION: u32 = 1;
+
 #[contract]
 pub struct TemperatureContract;
 
-/// Minimal coordinator interface for cross-contract excursion reporting.
-#[contractclient(name = "CoordinatorContractClient")]
-pub trait CoordinatorContractInterface {
-    fn flag_temperature_breach(
-        env: soroban_sdk::Env,
-        caller: Address,
-        payment_id: u64,
-        excursion_summary: ExcursionSummary,
-    ) -> Result<(), soroban_sdk::Error>;
-}
-
 #[contractimpl]
 impl TemperatureContract {
     pub fn initialize(env: Env, admin: Address) -> Result<(), ContractError> {
@@ -34,9 +34,64 @@ impl TemperatureContract {
         }
 
         storage::set_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
         Ok(())
     }
 
+    /// Replace the contract code with a previously uploaded WASM. Admin only.
+    /// Storage is kept as is; run `migrate` afterwards when the new build
+    /// raises the schema version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        if admin != storage::get_admin(&env) {
+            return Err(ContractError::Unauthorized);
+        }
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("upgraded"), symbol_short!("v1")),
+            new_wasm_hash,
+        );
+        Ok(())
+    }
+
+    /// Storage schema version currently in effect. Deployments that predate
+    /// versioning report 1.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Migrate threshold and reading storage out of `from_version`. Admin only.
+    ///
+    /// Returns `true` once the stored schema matches this build; later calls
+    /// are no-ops. Version 1 is the current layout, so there are no steps yet.
+    pub fn migrate(env: Env, admin: Address, from_version: u32) -> Result<bool, ContractError> {
+        admin.require_auth();
+        if admin != storage::get_admin(&env) {
+            return Err(ContractError::Unauthorized);
+        }
+        let current = Self::schema_version(env.clone());
+        if current >= SCHEMA_VERSION {
+            return Ok(true);
+        }
+        if from_version != current {
+            return Err(ContractError::SchemaMismatch);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Ok(true)
+    }
+
     /// Pause all state-mutating functions. Admin only.
     pub fn pause(env: Env, admin: Address) -> Result<(), ContractError> {
         admin.require_auth();
@@ -375,6 +430,18 @@ impl TemperatureContract {
         Ok(())
     }
 
+    /// Resolve the coordinator through a deployment registry, falling back to
+    /// the address set via `set_coordinator`. Admin only.
+    pub fn set_registry(env: Env, admin: Address, registry: Address) -> Result<(), ContractError> {
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env);
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        Ok(())
+    }
+
     /// Whitelist an IoT oracle address that may call report_excursion_to_coordinator.
     pub fn add_oracle(
         env: Env,
@@ -393,9 +460,12 @@ impl TemperatureContract {
     }
 
     /// Report a sustained temperature excursion to the coordinator contract,
-    /// which will transition the linked payment from Locked → Disputed.
+    /// which compromises the unit and transitions the linked payment from
+    /// Locked → Disputed.
     ///
-    /// Only the admin or a whitelisted IoT oracle may call this function.
+    /// Only the admin or a whitelisted IoT oracle may call this function. The
+    /// coordinator is called as this contract, so it must be registered there
+    /// via `set_temperature_contract`.
     ///
     /// # Arguments
     /// * `caller`            - Admin or whitelisted oracle address
@@ -430,15 +500,18 @@ impl TemperatureContract {
             return Err(ContractError::Unauthorized);
         }
 
-        let coordinator_addr: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::CoordinatorContract)
+        let registry = env.storage().instance().get(&DataKey::Registry);
+        let coordinator_addr: Address = resolve_service(&env, registry, "coordinator")
+            .or_else(|| env.storage().instance().get(&DataKey::CoordinatorContract))
             .ok_or(ContractError::CoordinatorNotSet)?;
 
         let coord_client = CoordinatorContractClient::new(&env, &coordinator_addr);
         coord_client
-            .try_flag_temperature_breach(&caller, &payment_id, &excursion_summary)
+            .try_flag_temperature_breach(
+                &env.current_contract_address(),
+                &payment_id,
+                &excursion_summary,
+            )
             .map_err(|_| ContractError::CoordinatorCallFailed)?
             .map_err(|_| ContractError::CoordinatorCallFailed)?;
 
@@ -0,0 +1,38 @@
+#![cfg(test)]
+
+use lifebank_types::testutils::upload_placeholder_wasm;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::error::ContractError;
+use crate::{TemperatureContract, TemperatureContractClient};
+
+#[test]
+fn test_upgrade_keeps_readings_and_migrate_is_admin_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(TemperatureContract, ());
+    let client = TemperatureContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_threshold(&admin, &1, &200, &600);
+    client.log_reading(&1, &450, &1_000);
+    let hash = upload_placeholder_wasm(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    env.register_at(&contract_id, TemperatureContract, ());
+
+    let readings = client.get_readings(&1);
+    assert_eq!(readings.len(), 1);
+    assert_eq!(readings.get(0).unwrap().temperature_celsius_x100, 450);
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert!(client.migrate(&admin, &1));
+}
@@ -1,3 +1,4 @@
+pub use lifebank_types::ExcursionSummary;
 use soroban_sdk::contracttype;
 
 #[contracttype]
@@ -35,21 +36,6 @@ pub struct TemperatureSummary {
     pub violation_count: u32,
 }
 
-/// Summary of a sustained temperature excursion, passed to the coordinator
-/// when automatically raising a payment dispute.
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct ExcursionSummary {
-    /// Blood unit affected
-    pub unit_id: u64,
-    /// Number of consecutive violations that triggered this excursion
-    pub violation_count: u32,
-    /// Peak temperature recorded during the excursion (×100 scale)
-    pub peak_celsius_x100: i32,
-    /// Ledger timestamp when the excursion was first detected
-    pub detected_at: u64,
-}
-
 #[contracttype]
 #[derive(Clone, Eq, PartialEq)]
 pub enum DataKey {
@@ -66,4 +52,8 @@ pub enum DataKey {
     CoordinatorContract,
     /// Whitelisted IoT oracle addresses allowed to report excursions
     OracleWhitelist(soroban_sdk::Address),
+    /// Deployment registry; its `coordinator` entry overrides CoordinatorContract
+    Registry,
+    /// Storage schema version in effect; absent means 1
+    SchemaVersion,
 }
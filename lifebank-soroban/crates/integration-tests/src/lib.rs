@@ -0,0 +1,10 @@
+//! End-to-end tests for the lifebank contract suite.
+//!
+//! The crate has no library code. `tests/common` deploys every contract into
+//! one test environment, funded with a Stellar asset token and wired together
+//! the way a production deployment is, and each file under `tests/` drives
+//! one workflow scenario through the real contracts. Unlike the per-contract
+//! unit tests there are no mocks, so a contract calling a function its peer
+//! does not expose, or decoding a value its peer no longer returns, fails here.
+
+#![no_std]
@@ -0,0 +1,109 @@
+//! Dispatch and settlement with only the signatures they need. The rest of
+//! the scenarios mock every authorisation; here the signers are spelled out
+//! so a call that starts needing, or stops checking, a signature fails.
+
+mod common;
+
+use common::Suite;
+use coordinator_contract::WorkflowStatus;
+use lifebank_types::{BloodType, PaymentStatus, Urgency};
+use soroban_sdk::{
+    testutils::{MockAuth, MockAuthInvoke},
+    IntoVal,
+};
+
+/// A request with one unit allocated from the bank, escrowed for 500.
+fn allocated(suite: &Suite) -> (u64, u64) {
+    let units = suite.stock(BloodType::BNegative, 1);
+    let request_id = suite.open_request(BloodType::BNegative, Urgency::Urgent, 1);
+    let payment_id = suite.escrow(request_id, 500);
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+    (request_id, payment_id)
+}
+
+#[test]
+fn test_dispatch_needs_bank_signature_alongside_rider() {
+    let suite = Suite::builder().build();
+    let env = &suite.env;
+    let (request_id, _) = allocated(&suite);
+    let eta = env.ledger().timestamp() + 3_600;
+
+    env.mock_auths(&[MockAuth {
+        address: &suite.rider,
+        invoke: &MockAuthInvoke {
+            contract: &suite.coordinator.address,
+            fn_name: "dispatch",
+            args: (request_id, suite.rider.clone(), eta).into_val(env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(suite
+        .coordinator
+        .try_dispatch(&request_id, &suite.rider, &eta)
+        .is_err());
+    assert_eq!(
+        suite.coordinator.get_workflow(&request_id).status,
+        WorkflowStatus::Allocated
+    );
+
+    env.mock_all_auths_allowing_non_root_auth();
+    suite.coordinator.dispatch(&request_id, &suite.rider, &eta);
+    let signers = suite.signers();
+    assert!(signers.contains(&suite.rider));
+    assert!(signers.contains(&suite.bank));
+}
+
+#[test]
+fn test_settlement_signed_by_hospital_alone() {
+    let suite = Suite::builder().build();
+    let env = &suite.env;
+    let (request_id, payment_id) = allocated(&suite);
+    suite.coordinator.dispatch(
+        &request_id,
+        &suite.rider,
+        &(env.ledger().timestamp() + 3_600),
+    );
+    suite
+        .coordinator
+        .confirm_delivery(&request_id, &suite.hospital);
+
+    let args = (request_id, suite.hospital.clone()).into_val(env);
+    let settle = MockAuthInvoke {
+        contract: &suite.coordinator.address,
+        fn_name: "settle_payment",
+        args,
+        sub_invokes: &[],
+    };
+
+    // Nobody else can sign for the hospital.
+    env.mock_auths(&[MockAuth {
+        address: &suite.rider,
+        invoke: &settle,
+    }]);
+    assert!(suite
+        .coordinator
+        .try_settle_payment(&request_id, &suite.hospital)
+        .is_err());
+    assert_eq!(
+        suite.payments.get_payment(&payment_id).status,
+        PaymentStatus::Locked
+    );
+
+    // The hospital's signature covers the whole settlement: payments and the
+    // token transfer are authorised by the contracts themselves.
+    env.mock_auths(&[MockAuth {
+        address: &suite.hospital,
+        invoke: &settle,
+    }]);
+    suite
+        .coordinator
+        .settle_payment(&request_id, &suite.hospital);
+    assert_eq!(suite.signers(), std::slice::from_ref(&suite.hospital));
+    assert_eq!(
+        suite.payments.get_payment(&payment_id).status,
+        PaymentStatus::Released
+    );
+    assert_eq!(suite.balance(&suite.bank), 500);
+}
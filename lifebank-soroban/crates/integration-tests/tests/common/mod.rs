@@ -0,0 +1,312 @@
+//! Fixture deploying the whole lifebank suite into one test environment.
+//!
+//! Every contract is the real implementation, initialised and cross-wired as
+//! in a production deployment: the coordinator drives requests, inventory and
+//! payments, re-matches through matching, accepts breaches from temperature,
+//! checks identity proofs and delivery attestations, and reports SLA breaches
+//! to reputation. Escrow is funded with a Stellar asset token.
+//!
+//! Authorisation is mocked; each contract's own tests cover who may call what.
+//! Non-root authorisation is allowed because the coordinator moves units in
+//! inventory on the inventory admin's authority, which in a deployment the
+//! bank signs alongside the coordinator call. Scenarios check the signers of
+//! dispatch and settlement with `signers`, and `authorization.rs` runs those
+//! steps with only the expected signatures mocked.
+
+// Each scenario binary uses a different subset of the fixture.
+#![allow(dead_code)]
+
+use analytics_contract::{AnalyticsContract, AnalyticsContractClient};
+use coordinator_contract::{
+    CoordinatorContract, CoordinatorContractClient, DeliveryChecks, SlaConfig,
+};
+use delivery_contract::{DeliveryContract, DeliveryContractClient};
+use identity_contract::{IdentityContract, IdentityContractClient, OrgType};
+use inventory_contract::{InventoryContract, InventoryContractClient};
+use lifebank_types::{BloodComponent, BloodType, Urgency};
+use matching_contract::{MatchingContract, MatchingContractClient};
+use payment_contract::{PaymentContract, PaymentContractClient};
+use reputation_contract::{ReputationContract, ReputationContractClient};
+use request_contract::{RequestContract, RequestContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Bytes, BytesN, Env, String, Vec,
+};
+use temperature_contract::{TemperatureContract, TemperatureContractClient};
+
+/// Ledger time the suite is deployed at.
+pub const START: u64 = 1_000;
+
+/// Reputation entity ID registered for the bank.
+pub const BANK_ENTITY: u64 = 1;
+
+/// Volume of every unit registered through the fixture.
+pub const UNIT_ML: u32 = 450;
+
+pub struct Suite {
+    pub env: Env,
+    /// Admin of every contract except inventory.
+    pub admin: Address,
+    /// The blood bank. Inventory only accepts units from its admin, so the
+    /// bank administers inventory.
+    pub bank: Address,
+    /// Identity organisation ID of the bank.
+    pub bank_org: Address,
+    pub hospital: Address,
+    pub rider: Address,
+    /// IoT oracle allowed to report temperature excursions.
+    pub oracle: Address,
+    pub token: Address,
+    pub identity: IdentityContractClient<'static>,
+    pub inventory: InventoryContractClient<'static>,
+    pub requests: RequestContractClient<'static>,
+    pub matching: MatchingContractClient<'static>,
+    pub coordinator: CoordinatorContractClient<'static>,
+    pub payments: PaymentContractClient<'static>,
+    pub temperature: TemperatureContractClient<'static>,
+    pub delivery: DeliveryContractClient<'static>,
+    pub reputation: ReputationContractClient<'static>,
+    pub analytics: AnalyticsContractClient<'static>,
+}
+
+pub struct SuiteBuilder {
+    hospital_funds: i128,
+    rematch: bool,
+    delivery_checks: bool,
+    sla: Option<(Urgency, SlaConfig)>,
+    dispute_timeout: Option<u64>,
+}
+
+impl SuiteBuilder {
+    /// Token balance minted to the hospital for escrow.
+    pub fn hospital_funds(mut self, amount: i128) -> Self {
+        self.hospital_funds = amount;
+        self
+    }
+
+    /// Whether the coordinator re-matches breached units through matching.
+    pub fn rematch(mut self, enabled: bool) -> Self {
+        self.rematch = enabled;
+        self
+    }
+
+    /// Whether `confirm_delivery` requires an identity proof and a delivery
+    /// attestation for the request.
+    pub fn delivery_checks(mut self, enabled: bool) -> Self {
+        self.delivery_checks = enabled;
+        self
+    }
+
+    pub fn sla(mut self, urgency: Urgency, config: SlaConfig) -> Self {
+        self.sla = Some((urgency, config));
+        self
+    }
+
+    /// Seconds a dispute may stay open before payments auto-refunds it.
+    pub fn dispute_timeout(mut self, secs: u64) -> Self {
+        self.dispute_timeout = Some(secs);
+        self
+    }
+
+    pub fn build(self) -> Suite {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+        env.ledger().set_timestamp(START);
+
+        let admin = Address::generate(&env);
+        let bank = Address::generate(&env);
+        let hospital = Address::generate(&env);
+        let rider = Address::generate(&env);
+        let oracle = Address::generate(&env);
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        StellarAssetClient::new(&env, &token).mint(&hospital, &self.hospital_funds);
+
+        let identity = IdentityContractClient::new(&env, &env.register(IdentityContract, ()));
+        let inventory = InventoryContractClient::new(&env, &env.register(InventoryContract, ()));
+        let requests = RequestContractClient::new(&env, &env.register(RequestContract, ()));
+        let matching = MatchingContractClient::new(&env, &env.register(MatchingContract, ()));
+        let coordinator =
+            CoordinatorContractClient::new(&env, &env.register(CoordinatorContract, ()));
+        let payments = PaymentContractClient::new(&env, &env.register(PaymentContract, ()));
+        let temperature =
+            TemperatureContractClient::new(&env, &env.register(TemperatureContract, ()));
+        let delivery = DeliveryContractClient::new(&env, &env.register(DeliveryContract, ()));
+        let reputation = ReputationContractClient::new(&env, &env.register(ReputationContract, ()));
+        let analytics = AnalyticsContractClient::new(&env, &env.register(AnalyticsContract, ()));
+
+        identity.initialize(&admin);
+        let bank_org = identity.register_organization(
+            &bank,
+            &OrgType::BloodBank,
+            &String::from_str(&env, "City Blood Bank"),
+            &String::from_str(&env, "BB-0001"),
+            &BytesN::from_array(&env, &[7; 32]),
+            &Vec::new(&env),
+        );
+
+        inventory.initialize(&bank);
+
+        requests.initialize(&admin, &inventory.address);
+        requests.authorize_hospital(&hospital);
+        requests.set_coordinator(&coordinator.address);
+
+        matching.initialize(&admin, &inventory.address, &requests.address);
+
+        payments.initialize(&admin, &Some(requests.address.clone()));
+        payments.set_coordinator(&admin, &coordinator.address);
+        if let Some(secs) = self.dispute_timeout {
+            payments.set_dispute_timeout(&admin, &secs);
+        }
+
+        temperature.initialize(&admin);
+        temperature.set_coordinator(&admin, &coordinator.address);
+        temperature.add_oracle(&admin, &oracle);
+
+        delivery.initialize(&admin, &requests.address);
+
+        reputation.initialize(&admin);
+        reputation.set_breach_reporter(&coordinator.address);
+
+        analytics.initialize(
+            &admin,
+            &inventory.address,
+            &requests.address,
+            &payments.address,
+            &reputation.address,
+        );
+        analytics.set_coordinator_contract(&coordinator.address);
+
+        coordinator.initialize(
+            &admin,
+            &requests.address,
+            &inventory.address,
+            &payments.address,
+        );
+        coordinator.set_temperature_contract(&admin, &temperature.address);
+        coordinator.set_reputation_contract(&admin, &reputation.address);
+        coordinator.set_bank_entity(&admin, &bank, &BANK_ENTITY);
+        if self.rematch {
+            coordinator.set_matching_contract(&admin, &Some(matching.address.clone()));
+        }
+        if self.delivery_checks {
+            coordinator.set_delivery_checks(
+                &admin,
+                &DeliveryChecks {
+                    proof_contract: Some(identity.address.clone()),
+                    compliance_contract: Some(delivery.address.clone()),
+                },
+            );
+        }
+        if let Some((urgency, config)) = self.sla {
+            coordinator.set_sla_config(&admin, &urgency, &config);
+        }
+
+        Suite {
+            env,
+            admin,
+            bank,
+            bank_org,
+            hospital,
+            rider,
+            oracle,
+            token,
+            identity,
+            inventory,
+            requests,
+            matching,
+            coordinator,
+            payments,
+            temperature,
+            delivery,
+            reputation,
+            analytics,
+        }
+    }
+}
+
+impl Suite {
+    /// Every contract wired together with 10 000 tokens for the hospital,
+    /// re-matching on and delivery checks off.
+    pub fn builder() -> SuiteBuilder {
+        SuiteBuilder {
+            hospital_funds: 10_000,
+            rematch: true,
+            delivery_checks: false,
+            sla: None,
+            dispute_timeout: None,
+        }
+    }
+
+    pub fn advance(&self, secs: u64) {
+        let now = self.env.ledger().timestamp();
+        self.env.ledger().set_timestamp(now + secs);
+    }
+
+    /// Addresses whose authorisation the last top-level call required.
+    pub fn signers(&self) -> std::vec::Vec<Address> {
+        self.env.auths().into_iter().map(|(who, _)| who).collect()
+    }
+
+    pub fn balance(&self, who: &Address) -> i128 {
+        TokenClient::new(&self.env, &self.token).balance(who)
+    }
+
+    /// Register `count` units of `blood_type` at the bank.
+    pub fn stock(&self, blood_type: BloodType, count: u32) -> Vec<u64> {
+        let mut ids = Vec::new(&self.env);
+        for _ in 0..count {
+            ids.push_back(
+                self.inventory
+                    .register_blood(&self.bank, &blood_type, &UNIT_ML, &None),
+            );
+        }
+        ids
+    }
+
+    /// Raise a request for `units` red-cell units due in one day.
+    pub fn open_request(&self, blood_type: BloodType, urgency: Urgency, units: u32) -> u64 {
+        self.requests.create_request(
+            &self.hospital,
+            &blood_type,
+            &BloodComponent::RedCells,
+            &(UNIT_ML * units),
+            &urgency,
+            &(self.env.ledger().timestamp() + 86_400),
+        )
+    }
+
+    /// Lock `amount` of the hospital's tokens in escrow for the bank.
+    pub fn escrow(&self, request_id: u64, amount: i128) -> u64 {
+        self.payments.create_escrow(
+            &request_id,
+            &self.hospital,
+            &self.bank,
+            &amount,
+            &self.token,
+        )
+    }
+
+    /// Record the evidence `confirm_delivery` checks when delivery checks are
+    /// on: a verified proof in identity and a compliant attestation in
+    /// delivery, both keyed by the request ID.
+    pub fn record_delivery_evidence(&self, request_id: u64, quantity_ml: u32) {
+        self.identity.verify_delivery(
+            &self.admin,
+            &request_id,
+            &self.bank_org,
+            &self.hospital,
+            &quantity_ml,
+            &true,
+        );
+        self.delivery.record_compliance_attestation(
+            &self.admin,
+            &request_id,
+            &Bytes::from_array(&self.env, &[0xc0; 32]),
+            &true,
+        );
+    }
+}
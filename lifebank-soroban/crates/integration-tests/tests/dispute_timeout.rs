@@ -0,0 +1,75 @@
+//! A dispatched delivery runs late. The SLA sweep disputes the escrow and
+//! reports the bank to reputation; once the dispute outlives the payments
+//! timeout the hospital is refunded.
+
+mod common;
+
+use common::{Suite, BANK_ENTITY};
+use coordinator_contract::SlaConfig;
+use lifebank_types::{BloodType, PaymentStatus, Urgency};
+use soroban_sdk::vec;
+
+const DISPUTE_TIMEOUT: u64 = 2 * 86_400;
+
+#[test]
+fn test_late_delivery_dispute_times_out_to_refund() {
+    let suite = Suite::builder()
+        .sla(
+            Urgency::Urgent,
+            SlaConfig {
+                dispatch_within_secs: 3_600,
+                delivery_grace_secs: 900,
+            },
+        )
+        .dispute_timeout(DISPUTE_TIMEOUT)
+        .build();
+    let env = &suite.env;
+    let units = suite.stock(BloodType::OPositive, 1);
+    let request_id = suite.open_request(BloodType::OPositive, Urgency::Urgent, 1);
+    let payment_id = suite.escrow(request_id, 500);
+
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+    let eta = env.ledger().timestamp() + 1_800;
+    suite.coordinator.dispatch(&request_id, &suite.rider, &eta);
+
+    // Within the grace period nothing is flagged.
+    suite.advance(1_800 + 900);
+    assert_eq!(suite.coordinator.enforce_slas(&10).len(), 0);
+
+    suite.advance(1);
+    assert_eq!(suite.coordinator.enforce_slas(&10), vec![env, request_id]);
+    let payment = suite.payments.get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Disputed);
+    assert_eq!(payment.dispute_reason_code, Some(6));
+    let input = suite.reputation.get_input(&BANK_ENTITY).unwrap();
+    assert_eq!(input.penalties.len(), 1);
+
+    // The leg is flagged once; later sweeps leave it alone.
+    suite.advance(600);
+    assert_eq!(suite.coordinator.enforce_slas(&10).len(), 0);
+
+    let ids = vec![env, payment_id];
+    suite.advance(DISPUTE_TIMEOUT - 601);
+    assert_eq!(
+        suite
+            .payments
+            .process_expired_disputes(&suite.admin, &ids)
+            .len(),
+        0
+    );
+    assert_eq!(suite.balance(&suite.hospital), 9_500);
+
+    suite.advance(1);
+    assert_eq!(
+        suite.payments.process_expired_disputes(&suite.admin, &ids),
+        ids
+    );
+    assert_eq!(
+        suite.payments.get_payment(&payment_id).status,
+        PaymentStatus::Refunded
+    );
+    assert_eq!(suite.balance(&suite.hospital), 10_000);
+    assert_eq!(suite.balance(&suite.payments.address), 0);
+}
@@ -0,0 +1,93 @@
+//! A request fulfilled end to end: allocation, dispatch, a checked delivery
+//! and settlement of the escrow to the bank.
+
+mod common;
+
+use common::{Suite, UNIT_ML};
+use coordinator_contract::WorkflowStatus;
+use lifebank_types::{BloodStatus, BloodType, PaymentStatus, RequestStatus, Urgency};
+
+#[test]
+fn test_request_fulfilled_and_settled() {
+    let suite = Suite::builder().delivery_checks(true).build();
+    let units = suite.stock(BloodType::ONegative, 2);
+    let request_id = suite.open_request(BloodType::ONegative, Urgency::Urgent, 2);
+    let payment_id = suite.escrow(request_id, 1_000);
+    assert_eq!(suite.balance(&suite.hospital), 9_000);
+    assert_eq!(suite.balance(&suite.payments.address), 1_000);
+
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+    let request = suite.requests.get_request(&request_id);
+    assert_eq!(request.status, RequestStatus::Approved);
+    assert_eq!(request.assigned_units, units);
+    for unit_id in units.iter() {
+        assert_eq!(
+            suite.inventory.get_blood_unit(&unit_id).status,
+            BloodStatus::Reserved
+        );
+    }
+
+    suite.advance(600);
+    suite.coordinator.dispatch(
+        &request_id,
+        &suite.rider,
+        &(suite.env.ledger().timestamp() + 3_600),
+    );
+    let signers = suite.signers();
+    assert!(signers.contains(&suite.rider));
+    assert!(signers.contains(&suite.bank));
+    for unit_id in units.iter() {
+        assert_eq!(
+            suite.inventory.get_blood_unit(&unit_id).status,
+            BloodStatus::InTransit
+        );
+    }
+
+    // Delivery checks are on, so confirmation waits for the evidence.
+    suite.advance(1_800);
+    assert!(suite
+        .coordinator
+        .try_confirm_delivery(&request_id, &suite.hospital)
+        .is_err());
+    suite.record_delivery_evidence(request_id, UNIT_ML * 2);
+    suite
+        .coordinator
+        .confirm_delivery(&request_id, &suite.hospital);
+    assert_eq!(
+        suite.requests.get_request(&request_id).status,
+        RequestStatus::Fulfilled
+    );
+    for unit_id in units.iter() {
+        assert_eq!(
+            suite.inventory.get_blood_unit(&unit_id).status,
+            BloodStatus::Delivered
+        );
+    }
+
+    suite
+        .coordinator
+        .settle_payment(&request_id, &suite.hospital);
+    assert_eq!(suite.signers(), std::slice::from_ref(&suite.hospital));
+    let payment = suite.payments.get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Released);
+    assert_eq!(payment.released_amount, 1_000);
+    assert_eq!(suite.balance(&suite.bank), 1_000);
+    assert_eq!(suite.balance(&suite.payments.address), 0);
+
+    let workflow = suite.coordinator.get_workflow(&request_id);
+    assert_eq!(workflow.status, WorkflowStatus::Settled);
+    let timeline = suite.coordinator.get_workflow_timeline(&request_id);
+    assert!(timeline.settled_at.is_some());
+
+    // Off-chain reporting feeds the same lifecycle into analytics.
+    suite.analytics.record_request();
+    suite.analytics.record_delivery();
+    suite
+        .analytics
+        .record_payment_released(&payment.released_amount);
+    let totals = suite.analytics.get_lifetime_totals();
+    assert_eq!(totals.total_deliveries, 1);
+    assert_eq!(totals.total_volume, 1_000);
+}
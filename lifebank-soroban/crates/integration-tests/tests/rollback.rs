@@ -0,0 +1,116 @@
+//! Admin rollbacks at each workflow stage, compensating inventory, payments
+//! and requests in one call.
+
+mod common;
+
+use common::Suite;
+use coordinator_contract::{CompensationStep, WorkflowStatus};
+use lifebank_types::{BloodStatus, BloodType, PaymentStatus, RequestStatus, Urgency};
+
+#[test]
+fn test_rollback_allocated_releases_units() {
+    let suite = Suite::builder().build();
+    let units = suite.stock(BloodType::ABPositive, 2);
+    let request_id = suite.open_request(BloodType::ABPositive, Urgency::Routine, 2);
+    let payment_id = suite.escrow(request_id, 1_200);
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+
+    let log = suite.coordinator.rollback(&request_id);
+    assert!(log.iter().all(|entry| entry.success));
+    assert!(log
+        .iter()
+        .any(|entry| entry.step == CompensationStep::ReleaseUnit));
+
+    for unit_id in units.iter() {
+        assert_eq!(
+            suite.inventory.get_blood_unit(&unit_id).status,
+            BloodStatus::Available
+        );
+    }
+    assert_eq!(
+        suite.payments.get_payment(&payment_id).status,
+        PaymentStatus::Refunded
+    );
+    assert_eq!(suite.balance(&suite.hospital), 10_000);
+    assert_eq!(
+        suite.requests.get_request(&request_id).status,
+        RequestStatus::Cancelled
+    );
+    assert_eq!(
+        suite.coordinator.get_workflow(&request_id).status,
+        WorkflowStatus::RolledBack
+    );
+}
+
+#[test]
+fn test_rollback_dispatched_recalls_units() {
+    let suite = Suite::builder().build();
+    let units = suite.stock(BloodType::ONegative, 2);
+    let request_id = suite.open_request(BloodType::ONegative, Urgency::Critical, 2);
+    let payment_id = suite.escrow(request_id, 1_500);
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+    suite.coordinator.dispatch(
+        &request_id,
+        &suite.rider,
+        &(suite.env.ledger().timestamp() + 3_600),
+    );
+
+    let log = suite.coordinator.rollback(&request_id);
+    let steps: std::vec::Vec<CompensationStep> = log.iter().map(|entry| entry.step).collect();
+    assert_eq!(
+        steps,
+        [
+            CompensationStep::RecallUnit,
+            CompensationStep::RecallUnit,
+            CompensationStep::RefundPayment,
+            CompensationStep::CancelRequest,
+        ]
+    );
+    assert_eq!(suite.coordinator.get_compensation_log(&request_id), log);
+
+    // Units that left the bank cannot go back on the shelf.
+    for unit_id in units.iter() {
+        assert_eq!(
+            suite.inventory.get_blood_unit(&unit_id).status,
+            BloodStatus::Compromised
+        );
+    }
+    assert_eq!(suite.balance(&suite.hospital), 10_000);
+    assert_eq!(
+        suite.requests.get_request(&request_id).status,
+        RequestStatus::Cancelled
+    );
+}
+
+#[test]
+fn test_rollback_rejected_after_settlement() {
+    let suite = Suite::builder().build();
+    let units = suite.stock(BloodType::APositive, 1);
+    let request_id = suite.open_request(BloodType::APositive, Urgency::Urgent, 1);
+    let payment_id = suite.escrow(request_id, 700);
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+    suite.coordinator.dispatch(
+        &request_id,
+        &suite.rider,
+        &(suite.env.ledger().timestamp() + 3_600),
+    );
+    suite
+        .coordinator
+        .confirm_delivery(&request_id, &suite.hospital);
+    suite
+        .coordinator
+        .settle_payment(&request_id, &suite.hospital);
+
+    assert!(suite.coordinator.try_rollback(&request_id).is_err());
+    assert_eq!(suite.balance(&suite.bank), 700);
+    assert_eq!(
+        suite.requests.get_request(&request_id).status,
+        RequestStatus::Fulfilled
+    );
+}
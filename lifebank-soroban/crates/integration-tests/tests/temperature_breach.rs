@@ -0,0 +1,184 @@
+//! A unit overheats in transit. Temperature reports the excursion to the
+//! coordinator, which compromises the unit and swaps in a replacement found
+//! by matching, or disputes the escrow when no replacement is available.
+
+mod common;
+
+use common::Suite;
+use coordinator_contract::WorkflowStatus;
+use lifebank_types::{BloodStatus, BloodType, ExcursionSummary, PaymentStatus, Urgency};
+use payment_contract::DisputeOutcome;
+use soroban_sdk::{vec, BytesN};
+
+#[test]
+fn test_breach_compromises_unit_and_rematches() {
+    let suite = Suite::builder().build();
+    let env = &suite.env;
+    let units = suite.stock(BloodType::ANegative, 3);
+    let allocated = vec![env, units.get(0).unwrap(), units.get(1).unwrap()];
+    let spare = units.get(2).unwrap();
+    let request_id = suite.open_request(BloodType::ANegative, Urgency::Critical, 2);
+    let payment_id = suite.escrow(request_id, 2_000);
+
+    suite
+        .coordinator
+        .allocate_units(&request_id, &allocated, &payment_id, &suite.bank);
+    suite.coordinator.dispatch(
+        &request_id,
+        &suite.rider,
+        &(env.ledger().timestamp() + 3_600),
+    );
+
+    // Three consecutive readings above the 6 °C ceiling mark the unit.
+    let hot = allocated.get(0).unwrap();
+    suite
+        .temperature
+        .set_threshold(&suite.admin, &hot, &200, &600);
+    for minute in 1..=3u64 {
+        suite.advance(60);
+        suite
+            .temperature
+            .log_reading(&hot, &950, &env.ledger().timestamp());
+        assert_eq!(suite.temperature.is_compromised(&hot), minute == 3);
+    }
+
+    let summary = ExcursionSummary {
+        unit_id: hot,
+        violation_count: suite.temperature.get_consecutive_violation_streak(&hot),
+        peak_celsius_x100: 950,
+        detected_at: env.ledger().timestamp(),
+    };
+    suite
+        .temperature
+        .report_excursion_to_coordinator(&suite.oracle, &hot, &payment_id, &summary);
+
+    assert_eq!(
+        suite.inventory.get_blood_unit(&hot).status,
+        BloodStatus::Compromised
+    );
+    // The replacement makes the leg whole, so the escrow is not disputed.
+    let payment = suite.payments.get_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Locked);
+    assert_eq!(payment.dispute_reason_code, None);
+    assert_eq!(suite.balance(&suite.payments.address), 2_000);
+
+    // The spare unit from the same bank replaces the compromised one and
+    // follows the dispatched leg into transit.
+    let leg = suite
+        .coordinator
+        .get_workflow(&request_id)
+        .legs
+        .get(0)
+        .unwrap();
+    assert_eq!(leg.breached_units, vec![env, hot]);
+    assert_eq!(leg.unit_ids, vec![env, allocated.get(1).unwrap(), spare]);
+    assert_eq!(
+        suite.inventory.get_blood_unit(&spare).status,
+        BloodStatus::InTransit
+    );
+
+    // A second report for the same unit leaves the workflow as it is.
+    suite
+        .temperature
+        .report_excursion_to_coordinator(&suite.oracle, &hot, &payment_id, &summary);
+    let leg = suite
+        .coordinator
+        .get_workflow(&request_id)
+        .legs
+        .get(0)
+        .unwrap();
+    assert_eq!(leg.unit_ids.len(), 2);
+    assert_eq!(leg.breached_units.len(), 1);
+    assert_eq!(
+        suite.requests.get_request(&request_id).assigned_units,
+        vec![env, allocated.get(1).unwrap(), spare]
+    );
+
+    // The leg is delivered and settled with the replacement on board.
+    suite
+        .coordinator
+        .confirm_delivery(&request_id, &suite.hospital);
+    assert_eq!(
+        suite.inventory.get_blood_unit(&spare).status,
+        BloodStatus::Delivered
+    );
+    suite
+        .coordinator
+        .settle_payment(&request_id, &suite.hospital);
+    assert_eq!(
+        suite.coordinator.get_workflow(&request_id).status,
+        WorkflowStatus::Settled
+    );
+    assert_eq!(
+        suite.payments.get_payment(&payment_id).status,
+        PaymentStatus::Released
+    );
+    assert_eq!(suite.balance(&suite.bank), 2_000);
+    assert_eq!(suite.balance(&suite.payments.address), 0);
+}
+
+#[test]
+fn test_breach_without_spare_stock_shrinks_leg() {
+    let suite = Suite::builder().build();
+    let env = &suite.env;
+    let units = suite.stock(BloodType::BPositive, 2);
+    let request_id = suite.open_request(BloodType::BPositive, Urgency::Routine, 2);
+    let payment_id = suite.escrow(request_id, 800);
+    suite
+        .coordinator
+        .allocate_units(&request_id, &units, &payment_id, &suite.bank);
+
+    let hot = units.get(1).unwrap();
+    suite.temperature.report_excursion_to_coordinator(
+        &suite.oracle,
+        &hot,
+        &payment_id,
+        &ExcursionSummary {
+            unit_id: hot,
+            violation_count: 3,
+            peak_celsius_x100: 1_100,
+            detected_at: env.ledger().timestamp(),
+        },
+    );
+
+    let leg = suite
+        .coordinator
+        .get_workflow(&request_id)
+        .legs
+        .get(0)
+        .unwrap();
+    assert_eq!(leg.unit_ids, vec![env, units.get(0).unwrap()]);
+    assert_eq!(
+        suite.inventory.get_blood_unit(&hot).status,
+        BloodStatus::Compromised
+    );
+    assert_eq!(
+        suite.payments.get_payment(&payment_id).status,
+        PaymentStatus::Disputed
+    );
+
+    // Once the dispute is split, the remaining unit's leg settles.
+    suite.payments.resolve_dispute(
+        &suite.admin,
+        &payment_id,
+        &DisputeOutcome::Split(5_000),
+        &BytesN::from_array(env, &[1; 32]),
+    );
+    suite.coordinator.dispatch(
+        &request_id,
+        &suite.rider,
+        &(env.ledger().timestamp() + 3_600),
+    );
+    suite
+        .coordinator
+        .confirm_delivery(&request_id, &suite.hospital);
+    suite
+        .coordinator
+        .settle_payment(&request_id, &suite.hospital);
+    assert_eq!(
+        suite.coordinator.get_workflow(&request_id).status,
+        WorkflowStatus::Settled
+    );
+    assert_eq!(suite.balance(&suite.bank), 400);
+    assert_eq!(suite.balance(&suite.hospital), 9_600);
+}
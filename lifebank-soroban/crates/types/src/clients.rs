@@ -0,0 +1,119 @@
+//! Cross-contract client interfaces.
+//!
+//! Each trait lists the functions other lifebank contracts call on one
+//! contract, with the argument and return types the real contract exposes.
+//! Add a function here before calling it from another contract so that
+//! `tests/clients.rs` can check it against the implementation.
+
+use crate::blood::{BloodStatus, BloodType, BloodUnit, RarePhenotype};
+use crate::identity::Organization;
+use crate::logistics::{DeliveryProof, ExcursionSummary};
+use crate::payment::{DisputeReason, Payment, PaymentStatus};
+use crate::request::{BloodRequest, RequestStatus, Urgency};
+use soroban_sdk::{contractclient, Address, Bytes, Env, String, Symbol, Vec};
+
+/// Inventory functions used by the coordinator, matching and requests contracts.
+#[contractclient(name = "InventoryContractClient")]
+pub trait InventoryContractInterface {
+    fn get_admin(env: Env) -> Address;
+    fn get_blood_unit(env: Env, blood_unit_id: u64) -> BloodUnit;
+    fn get_units_by_blood_type(env: Env, blood_type: BloodType) -> Vec<u64>;
+    fn get_units_by_phenotype(env: Env, phenotype: RarePhenotype) -> Vec<u64>;
+    fn get_units_by_phenotype_page(
+        env: Env,
+        phenotype: RarePhenotype,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64>;
+    fn update_status(
+        env: Env,
+        unit_id: u64,
+        new_status: BloodStatus,
+        authorized_by: Address,
+        reason: Option<String>,
+    ) -> BloodUnit;
+    fn mark_delivered(
+        env: Env,
+        unit_id: u64,
+        authorized_by: Address,
+        delivery_location: String,
+    ) -> BloodUnit;
+    fn release_reservation(env: Env, reservation_id: u64);
+}
+
+/// Requests functions used by the coordinator, matching and payments contracts.
+#[contractclient(name = "RequestContractClient")]
+pub trait RequestContractInterface {
+    fn get_request(env: Env, request_id: u64) -> BloodRequest;
+    fn get_rare_phenotype(env: Env, request_id: u64) -> Option<RarePhenotype>;
+    fn assign_units(env: Env, caller: Address, request_id: u64, unit_ids: Vec<u64>);
+    fn update_assigned_units(env: Env, caller: Address, request_id: u64, unit_ids: Vec<u64>);
+    fn update_request_status(env: Env, caller: Address, request_id: u64, new_status: RequestStatus);
+}
+
+/// Payments functions used by the coordinator.
+#[contractclient(name = "PaymentContractClient")]
+pub trait PaymentContractInterface {
+    fn get_payment(env: Env, payment_id: u64) -> Payment;
+    fn update_status(env: Env, caller: Address, payment_id: u64, status: PaymentStatus);
+    fn release_escrow(env: Env, caller: Address, payment_id: u64);
+    fn refund_escrow(env: Env, caller: Address, payment_id: u64);
+    fn release_partial(env: Env, caller: Address, payment_id: u64, amount: i128);
+    fn record_dispute(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        reason: DisputeReason,
+        case_id: String,
+    );
+}
+
+/// Coordinator functions used by the temperature contract.
+#[contractclient(name = "CoordinatorContractClient")]
+pub trait CoordinatorContractInterface {
+    fn flag_temperature_breach(
+        env: Env,
+        caller: Address,
+        payment_id: u64,
+        excursion_summary: ExcursionSummary,
+    );
+}
+
+/// Matching functions used by the coordinator to replace breached units.
+#[contractclient(name = "MatchingContractClient")]
+pub trait MatchingContractInterface {
+    fn find_replacement(
+        env: Env,
+        blood_type: BloodType,
+        urgency: Urgency,
+        quantity_ml: u32,
+        bank: Address,
+        exclude: Vec<u64>,
+    ) -> Option<u64>;
+}
+
+/// Identity functions used by the coordinator to check delivery proofs and by
+/// payments to check invoicing banks.
+#[contractclient(name = "IdentityContractClient")]
+pub trait IdentityContractInterface {
+    fn get_delivery(env: Env, request_id: u64) -> Option<DeliveryProof>;
+    fn get_organization(env: Env, org_id: Address) -> Option<Organization>;
+}
+
+/// Delivery functions used by the coordinator to check cold-chain compliance.
+#[contractclient(name = "DeliveryContractClient")]
+pub trait DeliveryContractInterface {
+    fn get_compliance_attestation(env: Env, delivery_id: u64) -> (Bytes, bool);
+}
+
+/// Reputation functions used by the coordinator's SLA sweep.
+#[contractclient(name = "ReputationContractClient")]
+pub trait ReputationContractInterface {
+    fn report_sla_breach(env: Env, reporter: Address, entity_id: u64);
+}
+
+/// Deployment registry lookup used by every contract that resolves peers.
+#[contractclient(name = "RegistryContractClient")]
+pub trait RegistryContractInterface {
+    fn get_service(env: Env, name: Symbol) -> Address;
+}
@@ -0,0 +1,25 @@
+use soroban_sdk::{contracttype, Address, BytesN, String};
+
+/// Kind of organization registered with the identity contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrgType {
+    BloodBank,
+    Hospital,
+}
+
+/// Organization record stored by the identity contract and returned by
+/// `get_organization`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Organization {
+    pub id: Address,
+    pub org_type: OrgType,
+    pub name: String,
+    pub license_number: String,
+    pub verified: bool,
+    pub verified_timestamp: Option<u64>,
+    pub rating: u32,
+    pub total_ratings: u32,
+    pub location_hash: BytesN<32>,
+}
@@ -0,0 +1,37 @@
+//! Domain types and cross-contract clients shared by the lifebank contracts.
+//!
+//! Every contract that stores, returns or decodes one of these values uses the
+//! definition from this crate instead of keeping a local copy. A value passed
+//! between two contracts is therefore the same Rust type on both sides, so its
+//! XDR layout (struct field names and order, enum variant names) cannot drift
+//! between producer and consumer: a change here recompiles every contract
+//! against the new layout, and a contract that still declared its own copy
+//! would fail to type-check where it hands that copy to a shared client.
+//!
+//! The client traits in [`clients`] describe the subset of each contract's
+//! interface that other contracts call. `tests/clients.rs` runs every client
+//! against the real contract it targets. [`registry::resolve_service`] looks
+//! peers up in the deployment registry for every contract that has one.
+//! With the `testutils` feature, [`testutils`] holds fixtures the contract
+//! test suites share.
+
+#![no_std]
+
+pub mod blood;
+pub mod clients;
+pub mod identity;
+pub mod logistics;
+pub mod payment;
+pub mod registry;
+pub mod request;
+#[cfg(feature = "testutils")]
+pub mod testutils;
+
+pub use blood::{BloodComponent, BloodStatus, BloodType, BloodUnit, RarePhenotype};
+pub use identity::{OrgType, Organization};
+pub use logistics::{DeliveryProof, ExcursionSummary};
+pub use payment::{DisputeReason, FeeBreakdown, Payment, PaymentStatus};
+pub use request::{BloodRequest, RequestStatus, Urgency};
+
+#[cfg(test)]
+mod test;
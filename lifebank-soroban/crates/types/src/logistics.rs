@@ -0,0 +1,31 @@
+use soroban_sdk::{contracttype, Address};
+
+/// Delivery proof recorded by the identity contract and returned by
+/// `get_delivery`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryProof {
+    pub request_id: u64,
+    pub org_id: Address,
+    pub recipient: Address,
+    pub quantity_delivered: u32,
+    pub temperature_ok: bool,
+    pub delivered_at: u64,
+    pub verified: bool,
+    pub verified_at: Option<u64>,
+}
+
+/// Summary of a sustained temperature excursion, passed by the temperature
+/// contract to the coordinator when automatically raising a payment dispute.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExcursionSummary {
+    /// Blood unit affected
+    pub unit_id: u64,
+    /// Number of consecutive violations that triggered this excursion
+    pub violation_count: u32,
+    /// Peak temperature recorded during the excursion (×100 scale)
+    pub peak_celsius_x100: i32,
+    /// Ledger timestamp when the excursion was first detected
+    pub detected_at: u64,
+}
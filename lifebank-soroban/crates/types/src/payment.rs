@@ -0,0 +1,105 @@
+use soroban_sdk::{contracttype, Address, String};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaymentStatus {
+    Pending,
+    Locked,
+    Released,
+    Refunded,
+    Disputed,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisputeReason {
+    FailedDelivery,
+    TemperatureExcursion,
+    PaymentContested,
+    WrongItem,
+    DamagedGoods,
+    LateDelivery,
+    Other,
+}
+
+/// Fees taken from an escrow at release and sent to the treasury. The
+/// payee receives the escrowed amount less `total`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub platform_fee: i128,
+    pub fixed_fee: i128,
+    pub urgency_surcharge: i128,
+    pub total: i128,
+    /// Where the fees went; `None` when no fee policy applied.
+    pub treasury: Option<Address>,
+}
+
+impl FeeBreakdown {
+    /// No fees charged.
+    pub fn none() -> Self {
+        FeeBreakdown {
+            platform_fee: 0,
+            fixed_fee: 0,
+            urgency_surcharge: 0,
+            total: 0,
+            treasury: None,
+        }
+    }
+}
+
+/// Payment record stored in the payments contract and returned by
+/// `get_payment`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Payment {
+    pub id: u64,
+    pub request_id: u64,
+    pub payer: Address,
+    pub payee: Address,
+    pub amount: i128,
+    pub status: PaymentStatus,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub dispute_reason_code: Option<u32>,
+    pub dispute_case_id: Option<String>,
+    pub dispute_resolved: bool,
+    /// Token contract address — set only for escrow-backed payments.
+    pub token: Option<Address>,
+    /// Amount released to the payee, fees included. Below `amount` while
+    /// milestones are outstanding and after a partial settlement, where the
+    /// remainder is returned to the payer.
+    pub released_amount: i128,
+    /// Fees withheld from the payee at release.
+    pub fee: FeeBreakdown,
+}
+
+impl Payment {
+    /// Whether the payment may move from its current status to `new_status`.
+    ///
+    /// Pending ──► Locked ──► Released / Refunded (terminal)
+    ///    │           │
+    ///    ▼           ▼
+    /// Cancelled   Disputed ──► Released / Refunded
+    pub fn can_transition_to(&self, new_status: PaymentStatus) -> bool {
+        matches!(
+            (self.status, new_status),
+            (PaymentStatus::Pending, PaymentStatus::Locked)
+                | (PaymentStatus::Pending, PaymentStatus::Cancelled)
+                | (PaymentStatus::Locked, PaymentStatus::Released)
+                | (PaymentStatus::Locked, PaymentStatus::Refunded)
+                | (PaymentStatus::Locked, PaymentStatus::Disputed)
+                | (PaymentStatus::Disputed, PaymentStatus::Released)
+                | (PaymentStatus::Disputed, PaymentStatus::Refunded)
+        )
+    }
+
+    /// Released, Refunded and Cancelled payments never change status again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status,
+            PaymentStatus::Released | PaymentStatus::Refunded | PaymentStatus::Cancelled
+        )
+    }
+}
@@ -0,0 +1,20 @@
+//! Peer resolution through the deployment registry.
+//!
+//! Contracts keep the peer addresses they were configured with and, once an
+//! admin points them at a registry, prefer the registry's current entry, so a
+//! `rotate_service` there rewires every contract that resolves the service.
+
+use soroban_sdk::{Address, Env, Symbol};
+
+use crate::clients::RegistryContractClient;
+
+/// The registry's current address for `service`, or `None` when `registry`
+/// is unset or does not list the service; callers then fall back to the
+/// address they stored themselves.
+pub fn resolve_service(env: &Env, registry: Option<Address>, service: &str) -> Option<Address> {
+    let registry = registry?;
+    RegistryContractClient::new(env, &registry)
+        .try_get_service(&Symbol::new(env, service))
+        .ok()?
+        .ok()
+}
@@ -0,0 +1,54 @@
+use crate::blood::{BloodComponent, BloodType};
+use soroban_sdk::{contracttype, Address, Vec};
+
+/// Urgency levels for a blood request
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Urgency {
+    Critical,
+    Urgent,
+    Routine,
+    Scheduled,
+}
+
+impl Urgency {
+    /// Numeric priority — higher is more urgent.
+    pub fn priority(&self) -> u32 {
+        match self {
+            Self::Critical => 4,
+            Self::Urgent => 3,
+            Self::Routine => 2,
+            Self::Scheduled => 1,
+        }
+    }
+}
+
+/// Lifecycle of a blood request in the requests contract
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RequestStatus {
+    Pending,
+    Approved,
+    Fulfilled,
+    Cancelled,
+}
+
+/// Blood request stored in the requests contract and returned by
+/// `get_request`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BloodRequest {
+    pub id: u64,
+    pub hospital_id: Address,
+    pub blood_type: BloodType,
+    pub component: BloodComponent,
+    pub quantity_ml: u32,
+    pub urgency: Urgency,
+    pub created_timestamp: u64,
+    pub required_by_timestamp: u64,
+    pub status: RequestStatus,
+    pub assigned_units: Vec<u64>,
+    pub fulfilled_quantity_ml: u32,
+    /// Reservation ID on the inventory contract, set when units are reserved.
+    pub reservation_id: Option<u64>,
+}
@@ -0,0 +1,168 @@
+//! Wire-format checks. Struct field names and enum variant names are the XDR
+//! layout of these types and are part of every stored record, so renaming
+//! one is a breaking change that must come with a storage migration.
+
+use crate::*;
+use soroban_sdk::{
+    testutils::Address as _, Address, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+fn field_names(env: &Env, value: Val) -> Vec<Symbol> {
+    Map::<Symbol, Val>::try_from_val(env, &value)
+        .unwrap()
+        .keys()
+}
+
+fn expected_fields(env: &Env, names: &[&str]) -> Vec<Symbol> {
+    let mut map: Map<Symbol, u32> = Map::new(env);
+    for name in names {
+        map.set(Symbol::new(env, name), 0);
+    }
+    map.keys()
+}
+
+fn variant_name(env: &Env, value: Val) -> Symbol {
+    let parts = Vec::<Val>::try_from_val(env, &value).unwrap();
+    Symbol::try_from_val(env, &parts.get(0).unwrap()).unwrap()
+}
+
+#[test]
+fn test_blood_request_layout() {
+    let env = Env::default();
+    let request = BloodRequest {
+        id: 1,
+        hospital_id: Address::generate(&env),
+        blood_type: BloodType::ONegative,
+        component: BloodComponent::RedCells,
+        quantity_ml: 450,
+        urgency: Urgency::Critical,
+        created_timestamp: 10,
+        required_by_timestamp: 20,
+        status: RequestStatus::Pending,
+        assigned_units: Vec::new(&env),
+        fulfilled_quantity_ml: 0,
+        reservation_id: Some(3),
+    };
+
+    assert_eq!(
+        field_names(&env, request.clone().into_val(&env)),
+        expected_fields(
+            &env,
+            &[
+                "id",
+                "hospital_id",
+                "blood_type",
+                "component",
+                "quantity_ml",
+                "urgency",
+                "created_timestamp",
+                "required_by_timestamp",
+                "status",
+                "assigned_units",
+                "fulfilled_quantity_ml",
+                "reservation_id",
+            ],
+        )
+    );
+
+    let val: Val = request.clone().into_val(&env);
+    assert_eq!(BloodRequest::try_from_val(&env, &val).unwrap(), request);
+}
+
+#[test]
+fn test_blood_unit_layout() {
+    let env = Env::default();
+    let unit = BloodUnit {
+        id: 7,
+        blood_type: BloodType::APositive,
+        quantity_ml: 450,
+        bank_id: Address::generate(&env),
+        donor_id: None,
+        donation_timestamp: 1,
+        expiration_timestamp: 2,
+        status: BloodStatus::Available,
+        metadata: Map::new(&env),
+    };
+
+    assert_eq!(
+        field_names(&env, unit.into_val(&env)),
+        expected_fields(
+            &env,
+            &[
+                "id",
+                "blood_type",
+                "quantity_ml",
+                "bank_id",
+                "donor_id",
+                "donation_timestamp",
+                "expiration_timestamp",
+                "status",
+                "metadata",
+            ],
+        )
+    );
+}
+
+#[test]
+fn test_payment_layout() {
+    let env = Env::default();
+    let payment = Payment {
+        id: 1,
+        request_id: 2,
+        payer: Address::generate(&env),
+        payee: Address::generate(&env),
+        amount: 100,
+        status: PaymentStatus::Locked,
+        created_at: 0,
+        updated_at: 0,
+        dispute_reason_code: None,
+        dispute_case_id: Some(String::from_str(&env, "case")),
+        dispute_resolved: false,
+        token: None,
+        released_amount: 0,
+        fee: FeeBreakdown::none(),
+    };
+
+    assert_eq!(
+        field_names(&env, payment.into_val(&env)),
+        expected_fields(
+            &env,
+            &[
+                "id",
+                "request_id",
+                "payer",
+                "payee",
+                "amount",
+                "status",
+                "created_at",
+                "updated_at",
+                "dispute_reason_code",
+                "dispute_case_id",
+                "dispute_resolved",
+                "token",
+                "released_amount",
+                "fee",
+            ],
+        )
+    );
+}
+
+#[test]
+fn test_enum_variant_names() {
+    let env = Env::default();
+    let cases: [(Val, &str); 7] = [
+        (BloodType::ABNegative.into_val(&env), "ABNegative"),
+        (BloodStatus::Compromised.into_val(&env), "Compromised"),
+        (
+            BloodComponent::Cryoprecipitate.into_val(&env),
+            "Cryoprecipitate",
+        ),
+        (Urgency::Scheduled.into_val(&env), "Scheduled"),
+        (RequestStatus::Fulfilled.into_val(&env), "Fulfilled"),
+        (PaymentStatus::Disputed.into_val(&env), "Disputed"),
+        (OrgType::BloodBank.into_val(&env), "BloodBank"),
+    ];
+    for (value, name) in cases {
+        assert_eq!(variant_name(&env, value), Symbol::new(&env, name));
+    }
+}
@@ -0,0 +1,18 @@
+//! Fixtures for contract tests, behind the `testutils` feature.
+
+use soroban_sdk::{Bytes, BytesN, Env};
+
+/// Upload a minimal valid WASM module and return its hash. It stands in for
+/// the new build in upgrade tests; the native contract is re-registered at
+/// the same address afterwards, so the upgraded instance keeps running the
+/// crate's own code against the storage left behind.
+pub fn upload_placeholder_wasm(env: &Env) -> BytesN<32> {
+    let wasm: [u8; 40] = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic + version
+        0x00, 30, 17, b'c', b'o', b'n', b't', b'r', b'a', b'c', b't', b'e', b'n', b'v', b'm', b'e',
+        b't', b'a', b'v', b'0', // custom section "contractenvmetav0"
+        0, 0, 0, 0, 0, 0, 0, 23, 0, 0, 0, 0, // interface version: protocol 23
+    ];
+    env.deployer()
+        .upload_contract_wasm(Bytes::from_slice(env, &wasm))
+}
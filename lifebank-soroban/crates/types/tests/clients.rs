@@ -0,0 +1,342 @@
+//! Runs every shared client against the contract it targets.
+//!
+//! A call only succeeds when the arguments the client encodes decode on the
+//! contract side and the value the contract returns decodes as the shared
+//! type, so each test fails as soon as a client and its contract disagree.
+
+use lifebank_types::clients::{
+    CoordinatorContractClient, DeliveryContractClient, IdentityContractClient,
+    InventoryContractClient, MatchingContractClient, PaymentContractClient, RegistryContractClient,
+    ReputationContractClient, RequestContractClient,
+};
+use lifebank_types::registry::resolve_service;
+use lifebank_types::{
+    BloodComponent, BloodStatus, BloodType, DisputeReason, ExcursionSummary, OrgType,
+    PaymentStatus, RarePhenotype, RequestStatus, Urgency,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, BytesN, Env, Error, String, Symbol, Vec,
+};
+
+struct Suite {
+    env: Env,
+    admin: Address,
+    bank: Address,
+    hospital: Address,
+    inventory: Address,
+    requests: Address,
+}
+
+/// Inventory with one authorised bank (the admin) and requests with one
+/// authorised hospital, both initialised by the same admin.
+fn setup() -> Suite {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let hospital = Address::generate(&env);
+
+    let inventory = env.register(inventory_contract::InventoryContract, ());
+    inventory_contract::InventoryContractClient::new(&env, &inventory).initialize(&admin);
+
+    let requests = env.register(request_contract::RequestContract, ());
+    let requests_admin = request_contract::RequestContractClient::new(&env, &requests);
+    requests_admin.initialize(&admin, &inventory);
+    requests_admin.authorize_hospital(&hospital);
+
+    Suite {
+        bank: admin.clone(),
+        env,
+        admin,
+        hospital,
+        inventory,
+        requests,
+    }
+}
+
+fn register_unit(suite: &Suite, blood_type: BloodType) -> u64 {
+    inventory_contract::InventoryContractClient::new(&suite.env, &suite.inventory).register_blood(
+        &suite.bank,
+        &blood_type,
+        &450u32,
+        &None,
+    )
+}
+
+fn create_request(suite: &Suite) -> u64 {
+    request_contract::RequestContractClient::new(&suite.env, &suite.requests).create_request(
+        &suite.hospital,
+        &BloodType::ONegative,
+        &BloodComponent::RedCells,
+        &450u32,
+        &Urgency::Critical,
+        &10_000u64,
+    )
+}
+
+#[test]
+fn test_inventory_client() {
+    let suite = setup();
+    let env = &suite.env;
+    let client = InventoryContractClient::new(env, &suite.inventory);
+
+    let unit_id = register_unit(&suite, BloodType::ONegative);
+    let rare_id = inventory_contract::InventoryContractClient::new(env, &suite.inventory)
+        .register_rare_blood(
+            &suite.bank,
+            &BloodType::OPositive,
+            &RarePhenotype::Bombay,
+            &450u32,
+            &None,
+        );
+
+    assert_eq!(client.get_admin(), suite.admin);
+    assert_eq!(
+        client.get_units_by_blood_type(&BloodType::ONegative),
+        vec![env, unit_id]
+    );
+    assert_eq!(
+        client.get_units_by_phenotype(&RarePhenotype::Bombay),
+        vec![env, rare_id]
+    );
+    assert_eq!(
+        client.get_units_by_phenotype_page(&RarePhenotype::Bombay, &0, &10),
+        vec![env, rare_id]
+    );
+
+    let unit = client.get_blood_unit(&unit_id);
+    assert_eq!(unit.bank_id, suite.bank);
+    assert_eq!(unit.status, BloodStatus::Available);
+
+    let updated = client.update_status(&unit_id, &BloodStatus::Reserved, &suite.bank, &None);
+    assert_eq!(updated.status, BloodStatus::Reserved);
+}
+
+#[test]
+fn test_request_client() {
+    let suite = setup();
+    let env = &suite.env;
+    let client = RequestContractClient::new(env, &suite.requests);
+
+    let request_id = create_request(&suite);
+    let request = client.get_request(&request_id);
+    assert_eq!(request.hospital_id, suite.hospital);
+    assert_eq!(request.component, BloodComponent::RedCells);
+    assert_eq!(request.reservation_id, None);
+
+    assert_eq!(client.get_rare_phenotype(&request_id), None);
+    request_contract::RequestContractClient::new(env, &suite.requests).set_rare_phenotype(
+        &suite.hospital,
+        &request_id,
+        &RarePhenotype::Bombay,
+    );
+    assert_eq!(
+        client.get_rare_phenotype(&request_id),
+        Some(RarePhenotype::Bombay)
+    );
+
+    let unit_id = register_unit(&suite, BloodType::ONegative);
+    client.assign_units(&suite.admin, &request_id, &vec![env, unit_id]);
+    assert_eq!(
+        client.get_request(&request_id).status,
+        RequestStatus::Approved
+    );
+
+    client.update_request_status(&suite.admin, &request_id, &RequestStatus::Cancelled);
+    assert_eq!(
+        client.get_request(&request_id).status,
+        RequestStatus::Cancelled
+    );
+}
+
+#[test]
+fn test_payment_client() {
+    let suite = setup();
+    let env = &suite.env;
+    let request_id = create_request(&suite);
+
+    // Payment creation decodes the linked request through the shared client.
+    let payments = env.register(payment_contract::PaymentContract, ());
+    let payments_admin = payment_contract::PaymentContractClient::new(env, &payments);
+    payments_admin.initialize(&suite.admin, &Some(suite.requests.clone()));
+    let payment_id = payments_admin.create_payment(&request_id, &suite.hospital, &suite.bank, &100);
+
+    let client = PaymentContractClient::new(env, &payments);
+    let payment = client.get_payment(&payment_id);
+    assert_eq!(payment.request_id, request_id);
+    assert_eq!(payment.amount, 100);
+
+    client.update_status(&suite.admin, &payment_id, &PaymentStatus::Locked);
+    client.record_dispute(
+        &suite.admin,
+        &payment_id,
+        &DisputeReason::TemperatureExcursion,
+        &String::from_str(env, "case-1"),
+    );
+    assert_eq!(
+        client.get_payment(&payment_id).status,
+        PaymentStatus::Disputed
+    );
+}
+
+#[test]
+fn test_identity_client() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let identity = env.register(identity_contract::IdentityContract, ());
+    let identity_admin = identity_contract::IdentityContractClient::new(&env, &identity);
+    identity_admin.initialize(&admin);
+    let org_id = identity_admin.register_organization(
+        &Address::generate(&env),
+        &identity_contract::OrgType::BloodBank,
+        &String::from_str(&env, "Central Bank"),
+        &String::from_str(&env, "LIC-1"),
+        &BytesN::from_array(&env, &[1; 32]),
+        &Vec::new(&env),
+    );
+    let recipient = Address::generate(&env);
+    identity_admin.verify_delivery(&admin, &7, &org_id, &recipient, &450, &true);
+
+    let client = IdentityContractClient::new(&env, &identity);
+    let proof = client.get_delivery(&7).unwrap();
+    assert_eq!(proof.org_id, org_id);
+    assert_eq!(proof.recipient, recipient);
+    assert!(proof.verified);
+    assert_eq!(client.get_delivery(&8), None);
+
+    identity_admin.verify_organization(&admin, &org_id);
+    let org = client.get_organization(&org_id).unwrap();
+    assert_eq!(org.org_type, OrgType::BloodBank);
+    assert!(org.verified);
+    assert!(client.get_organization(&recipient).is_none());
+}
+
+#[test]
+fn test_delivery_client() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let delivery = env.register(delivery_contract::DeliveryContract, ());
+    let delivery_admin = delivery_contract::DeliveryContractClient::new(&env, &delivery);
+    delivery_admin.initialize(&admin, &Address::generate(&env));
+    let hash = Bytes::from_array(&env, &[9; 4]);
+    delivery_admin.record_compliance_attestation(&admin, &3, &hash, &true);
+
+    let client = DeliveryContractClient::new(&env, &delivery);
+    assert_eq!(client.get_compliance_attestation(&3), (hash, true));
+}
+
+#[test]
+fn test_reputation_client() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let reporter = Address::generate(&env);
+    let reputation = env.register(reputation_contract::ReputationContract, ());
+    let reputation_admin = reputation_contract::ReputationContractClient::new(&env, &reputation);
+    reputation_admin.initialize(&Address::generate(&env));
+    reputation_admin.set_breach_reporter(&reporter);
+
+    ReputationContractClient::new(&env, &reputation).report_sla_breach(&reporter, &5);
+    assert_eq!(reputation_admin.get_input(&5).unwrap().penalties.len(), 1);
+}
+
+#[test]
+fn test_matching_client() {
+    let suite = setup();
+    let env = &suite.env;
+
+    let matching = env.register(matching_contract::MatchingContract, ());
+    matching_contract::MatchingContractClient::new(env, &matching).initialize(
+        &suite.admin,
+        &suite.inventory,
+        &suite.requests,
+    );
+    let unit_id = register_unit(&suite, BloodType::ONegative);
+
+    let client = MatchingContractClient::new(env, &matching);
+    assert_eq!(
+        client.find_replacement(
+            &BloodType::ONegative,
+            &Urgency::Critical,
+            &450,
+            &suite.bank,
+            &Vec::new(env),
+        ),
+        Some(unit_id)
+    );
+    assert_eq!(
+        client.find_replacement(
+            &BloodType::ONegative,
+            &Urgency::Critical,
+            &450,
+            &suite.bank,
+            &vec![env, unit_id],
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_coordinator_client() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let coordinator = env.register(coordinator_contract::CoordinatorContract, ());
+    coordinator_contract::CoordinatorContractClient::new(&env, &coordinator).initialize(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+
+    // The summary decodes and the call reaches the reporter check.
+    let summary = ExcursionSummary {
+        unit_id: 1,
+        violation_count: 3,
+        peak_celsius_x100: 1_200,
+        detected_at: 0,
+    };
+    let result = CoordinatorContractClient::new(&env, &coordinator).try_flag_temperature_breach(
+        &Address::generate(&env),
+        &1,
+        &summary,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(Error::from_contract_error(
+            coordinator_contract::CoordinatorError::Unauthorized as u32
+        )))
+    );
+}
+
+#[test]
+fn test_registry_client() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let registry = env.register(registry_contract::RegistryContract, ());
+    let registry_admin = registry_contract::RegistryContractClient::new(&env, &registry);
+    registry_admin.initialize(&Address::generate(&env));
+    let inventory = Address::generate(&env);
+    let name = Symbol::new(&env, "inventory");
+    registry_admin.register_service(&name, &inventory);
+
+    assert_eq!(
+        RegistryContractClient::new(&env, &registry).get_service(&name),
+        inventory
+    );
+
+    // Unlisted services and a missing registry leave the caller's fallback.
+    assert_eq!(
+        resolve_service(&env, Some(registry.clone()), "inventory"),
+        Some(inventory)
+    );
+    assert_eq!(resolve_service(&env, Some(registry), "payments"), None);
+    assert_eq!(resolve_service(&env, None, "inventory"), None);
+}
@@ -0,0 +1,233 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use payment_contract::{
+    DisputeOutcome, MilestoneCondition, PaymentContract, PaymentContractClient,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, String as SorobanString, Vec as SorobanVec,
+};
+
+use lifebank_types::{DisputeReason, PaymentStatus};
+
+/// Funds minted to each hospital before the sequence starts.
+const HOSPITAL_FUNDS: i128 = 1_000_000;
+
+const DISPUTE_TIMEOUT: u64 = 86_400;
+
+/// One call against the payments contract. Indices wrap around the
+/// hospitals and payments created so far.
+#[derive(Arbitrary, Debug)]
+enum EscrowOperation {
+    Lock { hospital: bool, amount: u16 },
+    Release { payment: u8 },
+    ReleasePartial { payment: u8, amount: u16 },
+    SetMilestones { payment: u8, amounts: Vec<u16> },
+    ReleaseMilestone { payment: u8, index: u8 },
+    Refund { payment: u8 },
+    Dispute { payment: u8 },
+    ResolveDispute { payment: u8, outcome: u8, bps: u16 },
+    ProcessExpiredDisputes { payments: Vec<u8> },
+    AdvanceTime { hours: u8 },
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    operations: Vec<EscrowOperation>,
+}
+
+fn pick(payments: &[u64], index: u8) -> Option<u64> {
+    if payments.is_empty() {
+        None
+    } else {
+        Some(payments[index as usize % payments.len()])
+    }
+}
+
+fuzz_target!(|input: FuzzInput| {
+    if input.operations.len() > 64 {
+        return;
+    }
+
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let hospitals = [Address::generate(&env), Address::generate(&env)];
+
+    let token = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    for hospital in hospitals.iter() {
+        StellarAssetClient::new(&env, &token).mint(hospital, &HOSPITAL_FUNDS);
+    }
+
+    let client = PaymentContractClient::new(&env, &env.register(PaymentContract, ()));
+    client.initialize(&admin, &None);
+    client.set_dispute_timeout(&admin, &DISPUTE_TIMEOUT);
+
+    let mut payments: std::vec::Vec<u64> = std::vec::Vec::new();
+    let mut next_request_id = 1u64;
+
+    for op in input.operations.iter() {
+        match op {
+            EscrowOperation::Lock { hospital, amount } => {
+                let hospital = &hospitals[*hospital as usize];
+                let request_id = next_request_id;
+                next_request_id += 1;
+                if let Ok(Ok(payment_id)) = client.try_create_escrow(
+                    &request_id,
+                    hospital,
+                    &bank,
+                    &(*amount as i128),
+                    &token,
+                ) {
+                    payments.push(payment_id);
+                }
+            }
+
+            EscrowOperation::Release { payment } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let _ = client.try_release_escrow(&admin, &payment_id);
+                }
+            }
+
+            EscrowOperation::ReleasePartial { payment, amount } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let _ = client.try_release_partial(&admin, &payment_id, &(*amount as i128));
+                }
+            }
+
+            EscrowOperation::SetMilestones { payment, amounts } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let mut milestones = SorobanVec::new(&env);
+                    for amount in amounts.iter().take(4) {
+                        milestones.push_back((*amount as i128, MilestoneCondition::Manual));
+                    }
+                    let _ = client.try_set_milestones(&admin, &payment_id, &milestones);
+                }
+            }
+
+            EscrowOperation::ReleaseMilestone { payment, index } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let _ = client.try_release_milestone(&admin, &payment_id, &(*index as u32 % 4));
+                }
+            }
+
+            EscrowOperation::Refund { payment } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let _ = client.try_refund_escrow(&admin, &payment_id);
+                }
+            }
+
+            EscrowOperation::Dispute { payment } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let _ = client.try_record_dispute(
+                        &admin,
+                        &payment_id,
+                        &DisputeReason::FailedDelivery,
+                        &SorobanString::from_str(&env, "CASE-1"),
+                    );
+                }
+            }
+
+            EscrowOperation::ResolveDispute {
+                payment,
+                outcome,
+                bps,
+            } => {
+                if let Some(payment_id) = pick(&payments, *payment) {
+                    let outcome = match outcome % 3 {
+                        0 => DisputeOutcome::Refund,
+                        1 => DisputeOutcome::Release,
+                        _ => DisputeOutcome::Split(*bps as u32),
+                    };
+                    let _ = client.try_resolve_dispute(
+                        &admin,
+                        &payment_id,
+                        &outcome,
+                        &BytesN::from_array(&env, &[0; 32]),
+                    );
+                }
+            }
+
+            EscrowOperation::ProcessExpiredDisputes { payments: picked } => {
+                let mut ids = SorobanVec::new(&env);
+                for index in picked.iter().take(8) {
+                    if let Some(payment_id) = pick(&payments, *index) {
+                        ids.push_back(payment_id);
+                    }
+                }
+                let _ = client.try_process_expired_disputes(&admin, &ids);
+            }
+
+            EscrowOperation::AdvanceTime { hours } => {
+                let now = env.ledger().timestamp();
+                env.ledger().set_timestamp(now + *hours as u64 * 3_600);
+            }
+        }
+
+        // The contract holds exactly what is still owed to someone: the
+        // unreleased part of the Locked and Disputed escrows.
+        let mut held: i128 = 0;
+        let mut paid_out: i128 = 0;
+        for payment_id in payments.iter() {
+            let payment = client.get_payment(payment_id);
+            assert!(
+                payment.released_amount >= 0 && payment.released_amount <= payment.amount,
+                "INVARIANT VIOLATION: payment {} released {} of {}",
+                payment_id,
+                payment.released_amount,
+                payment.amount
+            );
+            match payment.status {
+                PaymentStatus::Locked | PaymentStatus::Disputed => {
+                    held += payment.amount - payment.released_amount;
+                    assert_eq!(
+                        client.get_remaining_escrow(payment_id),
+                        payment.amount - payment.released_amount
+                    );
+                }
+                PaymentStatus::Released => assert!(
+                    payment.released_amount > 0,
+                    "INVARIANT VIOLATION: payment {} Released with nothing paid",
+                    payment_id
+                ),
+                _ => {}
+            }
+            paid_out += payment.released_amount;
+
+            // Released milestones never exceed what left the escrow.
+            let released_milestones: i128 = client
+                .get_milestones(payment_id)
+                .iter()
+                .filter(|milestone| milestone.released_at.is_some())
+                .map(|milestone| milestone.amount)
+                .sum();
+            assert!(released_milestones <= payment.released_amount);
+        }
+        assert_eq!(
+            token_client.balance(&client.address),
+            held,
+            "INVARIANT VIOLATION: contract balance does not match Locked + Disputed escrows after {:?}",
+            op
+        );
+
+        // Tokens are only ever moved between the hospitals, the contract and
+        // the bank, and the bank receives nothing beyond released shares.
+        let hospitals_total: i128 = hospitals.iter().map(|h| token_client.balance(h)).sum();
+        assert_eq!(
+            hospitals_total + held + token_client.balance(&bank),
+            HOSPITAL_FUNDS * hospitals.len() as i128,
+            "INVARIANT VIOLATION: tokens created or destroyed after {:?}",
+            op
+        );
+        assert_eq!(token_client.balance(&bank), paid_out);
+    }
+});
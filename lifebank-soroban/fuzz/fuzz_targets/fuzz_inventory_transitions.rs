@@ -0,0 +1,263 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use inventory_contract::{
+    InventoryContract, InventoryContractClient, ALLOWED_BLOOD_STATUS_TRANSITIONS,
+};
+use libfuzzer_sys::fuzz_target;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, Vec as SorobanVec,
+};
+
+use lifebank_types::{BloodStatus, BloodType, BloodUnit};
+
+/// Seven statuses, picked by index so every pair is reachable.
+const STATUSES: [BloodStatus; 7] = [
+    BloodStatus::Available,
+    BloodStatus::Reserved,
+    BloodStatus::InTransit,
+    BloodStatus::Delivered,
+    BloodStatus::Expired,
+    BloodStatus::Compromised,
+    BloodStatus::Disposed,
+];
+
+const BLOOD_TYPES: [BloodType; 8] = [
+    BloodType::APositive,
+    BloodType::ANegative,
+    BloodType::BPositive,
+    BloodType::BNegative,
+    BloodType::ABPositive,
+    BloodType::ABNegative,
+    BloodType::OPositive,
+    BloodType::ONegative,
+];
+
+/// One call against the inventory contract. Indices wrap around the units
+/// and reservations created so far.
+#[derive(Arbitrary, Debug)]
+enum InventoryOperation {
+    Register { blood_type: u8 },
+    UpdateStatus { unit: u8, status: u8 },
+    BatchUpdateStatus { units: Vec<u8>, status: u8 },
+    Reserve { units: Vec<u8>, duration: u16 },
+    ReleaseReservation { reservation: u8 },
+    AdvanceTime { hours: u8 },
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    operations: Vec<InventoryOperation>,
+}
+
+fn pick<T: Copy>(items: &[T], index: u8) -> Option<T> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items[index as usize % items.len()])
+    }
+}
+
+fn pick_many(env: &Env, units: &[u64], indices: &[u8]) -> SorobanVec<u64> {
+    let mut picked = SorobanVec::new(env);
+    for index in indices.iter().take(4) {
+        if let Some(unit_id) = pick(units, *index) {
+            picked.push_back(unit_id);
+        }
+    }
+    picked
+}
+
+fn snapshot(client: &InventoryContractClient, units: &[u64]) -> std::vec::Vec<BloodUnit> {
+    units
+        .iter()
+        .map(|unit_id| client.get_blood_unit(unit_id))
+        .collect()
+}
+
+fn is_allowed(from: BloodStatus, to: BloodStatus) -> bool {
+    ALLOWED_BLOOD_STATUS_TRANSITIONS.contains(&(from, to))
+}
+
+fuzz_target!(|input: FuzzInput| {
+    if input.operations.len() > 64 {
+        return;
+    }
+
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let bank = Address::generate(&env);
+    let client = InventoryContractClient::new(&env, &env.register(InventoryContract, ()));
+    client.initialize(&bank);
+
+    let mut units: std::vec::Vec<u64> = std::vec::Vec::new();
+    let mut live_reservations: std::vec::Vec<u64> = std::vec::Vec::new();
+    let mut released_reservations: std::vec::Vec<u64> = std::vec::Vec::new();
+
+    for op in input.operations.iter() {
+        let before = snapshot(&client, &units);
+
+        let succeeded = match op {
+            InventoryOperation::Register { blood_type } => {
+                let blood_type = pick(&BLOOD_TYPES, *blood_type).unwrap();
+                match client.try_register_blood(&bank, &blood_type, &450, &None) {
+                    Ok(Ok(unit_id)) => {
+                        let unit = client.get_blood_unit(&unit_id);
+                        assert_eq!(
+                            unit.status,
+                            BloodStatus::Available,
+                            "INVARIANT VIOLATION: unit {} registered as {:?}",
+                            unit_id,
+                            unit.status
+                        );
+                        assert!(
+                            !units.contains(&unit_id),
+                            "INVARIANT VIOLATION: unit ID reused"
+                        );
+                        units.push(unit_id);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+
+            InventoryOperation::UpdateStatus { unit, status } => {
+                let Some(unit_id) = pick(&units, *unit) else {
+                    continue;
+                };
+                let status = pick(&STATUSES, *status).unwrap();
+                let result = client.try_update_status(&unit_id, &status, &bank, &None);
+                if result.is_ok() {
+                    assert_eq!(client.get_blood_unit(&unit_id).status, status);
+                }
+                result.is_ok()
+            }
+
+            InventoryOperation::BatchUpdateStatus {
+                units: picked,
+                status,
+            } => {
+                let unit_ids = pick_many(&env, &units, picked);
+                let status = pick(&STATUSES, *status).unwrap();
+                client
+                    .try_batch_update_status(&unit_ids, &status, &bank, &None)
+                    .is_ok()
+            }
+
+            InventoryOperation::Reserve {
+                units: picked,
+                duration,
+            } => {
+                let unit_ids = pick_many(&env, &units, picked);
+                match client.try_reserve_blood(&bank, &unit_ids, &0, &(*duration as u64)) {
+                    Ok(Ok(reservation_id)) => {
+                        for unit_id in unit_ids.iter() {
+                            assert_eq!(
+                                client.get_blood_unit(&unit_id).status,
+                                BloodStatus::Reserved,
+                                "INVARIANT VIOLATION: reserved unit {} not Reserved",
+                                unit_id
+                            );
+                        }
+                        live_reservations.push(reservation_id);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+
+            InventoryOperation::ReleaseReservation { reservation } => {
+                if live_reservations.is_empty() {
+                    continue;
+                }
+                let index = *reservation as usize % live_reservations.len();
+                let reservation_id = live_reservations[index];
+                let reserved = client.get_reservation(&reservation_id).unit_ids;
+                let result = client.try_release_reservation(&reservation_id);
+                if result.is_ok() {
+                    // Units the reservation still held are back on the shelf;
+                    // units that moved on since keep their status.
+                    for (position, unit_id) in units.iter().enumerate() {
+                        if reserved.contains(unit_id)
+                            && before[position].status == BloodStatus::Reserved
+                        {
+                            assert_eq!(
+                                client.get_blood_unit(unit_id).status,
+                                BloodStatus::Available
+                            );
+                        }
+                    }
+                    live_reservations.remove(index);
+                    released_reservations.push(reservation_id);
+                }
+                result.is_ok()
+            }
+
+            InventoryOperation::AdvanceTime { hours } => {
+                let now = env.ledger().timestamp();
+                env.ledger().set_timestamp(now + *hours as u64 * 3_600);
+                continue;
+            }
+        };
+
+        let after = snapshot(&client, &units);
+        for (old, new) in before.iter().zip(after.iter()) {
+            // A failed call leaves every unit as it was.
+            if !succeeded {
+                assert_eq!(
+                    old.status, new.status,
+                    "INVARIANT VIOLATION: failed {:?} changed unit {}",
+                    op, old.id
+                );
+            }
+            // Every status change follows the transition table, so terminal
+            // statuses never change.
+            if old.status != new.status {
+                assert!(
+                    is_allowed(old.status, new.status),
+                    "INVARIANT VIOLATION: unit {} moved {:?} -> {:?}",
+                    old.id,
+                    old.status,
+                    new.status
+                );
+            }
+            assert_eq!(old.blood_type, new.blood_type);
+            assert_eq!(old.quantity_ml, new.quantity_ml);
+            assert_eq!(old.bank_id, new.bank_id);
+            assert_eq!(old.expiration_timestamp, new.expiration_timestamp);
+        }
+    }
+
+    for reservation_id in live_reservations.iter() {
+        assert!(client.try_get_reservation(reservation_id).is_ok());
+    }
+    for reservation_id in released_reservations.iter() {
+        assert!(
+            client.try_get_reservation(reservation_id).is_err(),
+            "INVARIANT VIOLATION: released reservation {} still stored",
+            reservation_id
+        );
+    }
+
+    // The audit trail only ever records legal transitions, and its counter
+    // matches the entries kept.
+    for unit_id in units.iter() {
+        let history = client.get_status_history(unit_id);
+        for entry in history.iter() {
+            assert!(
+                is_allowed(entry.from_status, entry.to_status),
+                "INVARIANT VIOLATION: history of unit {} records {:?} -> {:?}",
+                unit_id,
+                entry.from_status,
+                entry.to_status
+            );
+        }
+        assert_eq!(
+            client.get_status_change_count(unit_id),
+            history.len() as u64
+        );
+    }
+});
@@ -0,0 +1,157 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use temperature_contract::{TemperatureContract, TemperatureContractClient};
+
+/// Units the sequence logs against; few enough that pages fill up.
+const UNITS: u64 = 3;
+
+/// A three-reading streak compromises a unit.
+const COMPROMISE_STREAK: u32 = 3;
+
+/// One call against the temperature contract.
+#[derive(Arbitrary, Debug)]
+enum TemperatureOperation {
+    SetThreshold {
+        unit: u8,
+        min: i16,
+        max: i16,
+    },
+    LogReading {
+        unit: u8,
+        celsius_x100: i16,
+        timestamp: u32,
+    },
+    ResetCompromised {
+        unit: u8,
+    },
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    operations: Vec<TemperatureOperation>,
+}
+
+/// What the sequence has observed for one unit, used to check the stored log.
+#[derive(Default)]
+struct UnitLog {
+    readings: std::vec::Vec<(i32, u64)>,
+    /// Readings since the last admin reset, as violation flags.
+    since_reset: std::vec::Vec<bool>,
+}
+
+fuzz_target!(|input: FuzzInput| {
+    if input.operations.len() > 128 {
+        return;
+    }
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = TemperatureContractClient::new(&env, &env.register(TemperatureContract, ()));
+    client.initialize(&admin);
+
+    let mut logs: std::vec::Vec<UnitLog> = (0..UNITS).map(|_| UnitLog::default()).collect();
+
+    for op in input.operations.iter() {
+        match op {
+            TemperatureOperation::SetThreshold { unit, min, max } => {
+                let unit_id = *unit as u64 % UNITS;
+                let _ = client.try_set_threshold(&admin, &unit_id, &(*min as i32), &(*max as i32));
+            }
+
+            TemperatureOperation::LogReading {
+                unit,
+                celsius_x100,
+                timestamp,
+            } => {
+                let unit_id = *unit as u64 % UNITS;
+                let celsius_x100 = *celsius_x100 as i32;
+                let timestamp = *timestamp as u64;
+                if let Ok(Ok(())) = client.try_log_reading(&unit_id, &celsius_x100, &timestamp) {
+                    let log = &mut logs[unit_id as usize];
+                    log.readings.push((celsius_x100, timestamp));
+                    let stored = client.get_readings(&unit_id);
+                    let last = stored.last().unwrap();
+                    log.since_reset.push(last.is_violation);
+                }
+            }
+
+            TemperatureOperation::ResetCompromised { unit } => {
+                let unit_id = *unit as u64 % UNITS;
+                if let Ok(Ok(())) = client.try_reset_compromised_status(&admin, &unit_id) {
+                    logs[unit_id as usize].since_reset.clear();
+                }
+            }
+        }
+
+        for (unit_id, log) in logs.iter().enumerate() {
+            let unit_id = unit_id as u64;
+
+            // Paging neither drops, duplicates nor reorders readings.
+            let stored = client.get_readings(&unit_id);
+            assert_eq!(
+                stored.len() as usize,
+                log.readings.len(),
+                "INVARIANT VIOLATION: unit {} stores {} readings, {} were logged",
+                unit_id,
+                stored.len(),
+                log.readings.len()
+            );
+            for (reading, (celsius_x100, timestamp)) in stored.iter().zip(log.readings.iter()) {
+                assert_eq!(reading.temperature_celsius_x100, *celsius_x100);
+                assert_eq!(reading.timestamp, *timestamp);
+            }
+
+            // Violations are exactly the flagged readings.
+            let violations = client.get_violations(&unit_id);
+            let flagged = stored.iter().filter(|reading| reading.is_violation).count();
+            assert_eq!(violations.len() as usize, flagged);
+
+            // The summary agrees with the log it is computed from.
+            match client.try_get_temperature_summary(&unit_id) {
+                Ok(Ok(summary)) => {
+                    let temps = log.readings.iter().map(|(celsius_x100, _)| *celsius_x100);
+                    assert_eq!(summary.count as usize, log.readings.len());
+                    assert_eq!(summary.min_celsius_x100, temps.clone().min().unwrap());
+                    assert_eq!(summary.max_celsius_x100, temps.max().unwrap());
+                    assert_eq!(summary.violation_count as usize, flagged);
+                    assert!(summary.avg_celsius_x100 >= summary.min_celsius_x100);
+                    assert!(summary.avg_celsius_x100 <= summary.max_celsius_x100);
+                }
+                _ => assert!(
+                    log.readings.is_empty(),
+                    "INVARIANT VIOLATION: no summary for unit {} with readings",
+                    unit_id
+                ),
+            }
+
+            // The streak is the run of violations at the end of the log, and
+            // a unit is compromised once a run reached the limit since the
+            // last reset.
+            let streak = log
+                .since_reset
+                .iter()
+                .rev()
+                .take_while(|violation| **violation)
+                .count() as u32;
+            assert_eq!(client.get_consecutive_violation_streak(&unit_id), streak);
+
+            let mut run = 0u32;
+            let mut reached = false;
+            for violation in log.since_reset.iter() {
+                run = if *violation { run + 1 } else { 0 };
+                reached |= run >= COMPROMISE_STREAK;
+            }
+            assert_eq!(
+                client.is_compromised(&unit_id),
+                reached,
+                "INVARIANT VIOLATION: unit {} compromise flag disagrees with its readings",
+                unit_id
+            );
+        }
+    }
+});